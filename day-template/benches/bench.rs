@@ -0,0 +1,21 @@
+{{ if with_bench }}use aoclib::config::Config;
+use criterion::\{criterion_group, criterion_main, Criterion};
+use {package_name}::\{part1, part2};
+
+const YEAR: u32 = {year};
+const DAY: u8 = {day};
+
+fn bench(c: &mut Criterion) \{
+    let input = Config::load()
+        .expect("config must be loadable")
+        .input_for(YEAR, DAY);
+    // aoctool finds the results by these names
+    let mut group = c.benchmark_group("{package_name}");
+    group.bench_function("part1", |b| b.iter(|| part1(&input)));
+    group.bench_function("part2", |b| b.iter(|| part2(&input)));
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
+{{ endif }}
@@ -1,11 +1,43 @@
-use aoclib::parse;
+{{ if not bin_only }}use aoclib::parse;
 use color_eyre::Result;
 use std::path::Path;
 
-pub fn part1(input: &Path) -> Result<()> \{
+/// The answer may be of any type which implements `Display`.
+pub fn part1(input: &Path) -> Result<u64> \{
     unimplemented!("input file: \{:?}", input)
 }
 
-pub fn part2(input: &Path) -> Result<()> \{
+pub fn part2(input: &Path) -> Result<u64> \{
     unimplemented!("input file: \{:?}", input)
 }
+
+#[cfg(test)]
+mod tests \{
+    use super::*;
+    use aoclib::config::Config;
+    use std::path::PathBuf;
+
+    const YEAR: u32 = {year};
+    const DAY: u8 = {day};
+
+    /// Path to an example input, as recorded in `$inputs/examples/NN-K.txt`.
+    fn example(index: usize) -> PathBuf \{
+        Config::load()
+            .expect("config must be loadable")
+            .input_files(YEAR)
+            .join("examples")
+            .join(format!("\{DAY:02}-\{index}.txt"))
+    }
+
+    #[test]
+    fn part1_example() \{
+        // replace with an assertion against the example's expected answer
+        part1(&example(1)).unwrap();
+    }
+
+    #[test]
+    fn part2_example() \{
+        // replace with an assertion against the example's expected answer
+        part2(&example(1)).unwrap();
+    }
+}{{ endif }}
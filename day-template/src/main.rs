@@ -1,13 +1,25 @@
-use aoclib::\{config::Config, website::get_input};
-use {package_name}::\{part1, part2};
-
+use aoclib::config::Config;
+{{ if not bin_only }}use {package_name}::\{part1, part2};
+{{ endif }}
 use clap::Parser;
-use color_eyre::eyre::Result;
-use std::path::PathBuf;
+use color_eyre::eyre::\{ensure, Result};
+{{ if bin_only }}use std::\{
+    path::\{Path, PathBuf},
+    process::Command,
+};{{ else }}use std::\{path::PathBuf, process::Command};{{ endif }}
 
 const YEAR: u32 = {year};
 const DAY: u8 = {day};
+{{ if bin_only }}
+/// The answer may be of any type which implements `Display`.
+fn part1(input: &Path) -> Result<u64> \{
+    unimplemented!("input file: \{:?}", input)
+}
 
+fn part2(input: &Path) -> Result<u64> \{
+    unimplemented!("input file: \{:?}", input)
+}
+{{ endif }}
 #[derive(Parser, Debug)]
 struct RunArgs \{
     /// input file
@@ -28,11 +40,18 @@ impl RunArgs \{
     fn input(&self) -> Result<PathBuf> \{
         match self.input \{
             None => \{
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, YEAR, DAY)?;
-                Ok(config.input_for(YEAR, DAY))
+                let input = Config::load()?.input_for(YEAR, DAY);
+                // this simplifies the workflow after cloning the repo on a new computer.
+                // aoctool downloads the input, as only it can use a session key kept in
+                // the keychain, or given by the environment or a profile
+                if !input.exists() \{
+                    let status = Command::new("aoc")
+                        .args(["fetch-input", "--year", &YEAR.to_string()])
+                        .args(["--day", &DAY.to_string()])
+                        .status()?;
+                    ensure!(status.success(), "`aoc fetch-input` failed");
+                }
+                Ok(input)
             }
             Some(ref path) => Ok(path.clone()),
         }
@@ -44,11 +63,12 @@ fn main() -> Result<()> \{
     let args = RunArgs::parse();
     let input_path = args.input()?;
 
+    // aoctool finds the answers in these lines, so keep their format
     if !args.no_part1 \{
-        part1(&input_path)?;
+        println!("[aoc] part 1: \{}", part1(&input_path)?);
     }
     if args.part2 \{
-        part2(&input_path)?;
+        println!("[aoc] part 2: \{}", part2(&input_path)?);
     }
     Ok(())
 }
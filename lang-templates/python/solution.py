@@ -0,0 +1,33 @@
+"""Advent of Code {year}, day {day}."""
+
+import argparse
+from pathlib import Path
+
+INPUT_PATH = Path("{input_path}")
+
+
+def part1(input_path: Path) -> int:
+    """The answer may be of any type which can be printed."""
+    raise NotImplementedError("part1")
+
+
+def part2(input_path: Path) -> int:
+    raise NotImplementedError("part2")
+
+
+def main() -> None:
+    parser = argparse.ArgumentParser()
+    parser.add_argument("input", nargs="?", type=Path, default=INPUT_PATH)
+    parser.add_argument("--no-part1", action="store_true", help="skip part 1")
+    parser.add_argument("--part2", action="store_true", help="run part 2")
+    args = parser.parse_args()
+
+    # aoctool finds the answers in these lines, so keep their format
+    if not args.no_part1:
+        print(f"[aoc] part 1: \{part1(args.input)}")
+    if args.part2:
+        print(f"[aoc] part 2: \{part2(args.input)}")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,83 @@
+use aoclib::{config::Config, website::get_input};
+use clap::Parser;
+use color_eyre::eyre::{eyre, Result};
+use std::path::{Path, PathBuf};
+
+/// A day's solution, as registered in `days.rs`.
+pub struct Day {
+    pub year: u32,
+    pub day: u8,
+    pub part1: fn(&Path) -> Result<String>,
+    pub part2: fn(&Path) -> Result<String>,
+}
+
+/// Register a day crate, whose `part1` and `part2` return any `Display` type.
+macro_rules! day {
+    ($year:literal, $day:literal, $crate_name:ident) => {
+        crate::Day {
+            year: $year,
+            day: $day,
+            part1: |input| $crate_name::part1(input).map(|answer| answer.to_string()),
+            part2: |input| $crate_name::part2(input).map(|answer| answer.to_string()),
+        }
+    };
+}
+
+mod days;
+
+#[derive(Parser, Debug)]
+struct RunArgs {
+    /// day to run
+    day: u8,
+
+    /// input file
+    ///
+    /// when unset, defaults to the day's real input
+    input: Option<PathBuf>,
+
+    /// year of the day to run
+    ///
+    /// when unset, defaults to the latest year registered
+    #[arg(long)]
+    year: Option<u32>,
+
+    /// skip part 1
+    #[arg(long)]
+    no_part1: bool,
+
+    /// run part 2
+    #[arg(long)]
+    part2: bool,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = RunArgs::parse();
+
+    let year = args
+        .year
+        .or_else(|| days::DAYS.iter().map(|day| day.year).max())
+        .ok_or_else(|| eyre!("no days are registered"))?;
+    let day = days::DAYS
+        .iter()
+        .find(|day| day.year == year && day.day == args.day)
+        .ok_or_else(|| eyre!("day {} of {year} is not registered", args.day))?;
+
+    let input_path = match args.input {
+        Some(path) => path,
+        None => {
+            let config = Config::load()?;
+            get_input(&config, year, day.day)?;
+            config.input_for(year, day.day)
+        }
+    };
+
+    // aoctool finds the answers in these lines, so keep their format
+    if !args.no_part1 {
+        println!("[aoc] part 1: {}", (day.part1)(&input_path)?);
+    }
+    if args.part2 {
+        println!("[aoc] part 2: {}", (day.part2)(&input_path)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,125 @@
+//! Start managing a workspace which was not created by `aoctool`.
+
+use aoclib::config::Config;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::{settings::Settings, vfs::Fs, Error, Host, PathOpts, YearOpts};
+
+/// Subdirectories of a workspace which commonly hold its inputs, in order of preference.
+pub(crate) const INPUT_DIRS: &[&str] = &["inputs", "input", "data"];
+
+/// What was inferred about an existing workspace.
+#[derive(Debug, Clone, Serialize)]
+pub struct Adoption {
+    pub implementation: PathBuf,
+    /// The inputs directory, if one was found.
+    pub input_files: Option<PathBuf>,
+    /// The naming pattern of the existing day crates, if they agree on one.
+    pub day_name: Option<String>,
+    /// Days with existing crates.
+    pub days: Vec<u8>,
+}
+
+/// Inspect an existing workspace for a year, without changing anything.
+pub fn inspect(fs: &dyn Fs, implementation: &Path, year: u32) -> Result<Adoption, Error> {
+    let cargo_toml_path = implementation.join("Cargo.toml");
+    if !fs.exists(&cargo_toml_path) {
+        return Err(Error::NoCargoToml);
+    }
+    let manifest: toml_edit::Document = fs
+        .read_to_string(&cargo_toml_path)
+        .map_err(|err| Error::Io("reading Cargo.toml", err))?
+        .parse()?;
+    let members = manifest
+        .as_table()
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(|members| members.as_array())
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|member| member.as_str())
+                .map(ToOwned::to_owned)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let mut patterns = Vec::new();
+    let mut days = Vec::new();
+    for member in &members {
+        if let Some((pattern, day)) = infer_day_name(member, year) {
+            patterns.push(pattern);
+            days.push(day);
+        }
+    }
+    days.sort_unstable();
+    days.dedup();
+    patterns.dedup();
+    let day_name = match patterns.as_slice() {
+        [pattern] => Some(pattern.clone()),
+        _ => None,
+    };
+
+    let input_files = INPUT_DIRS
+        .iter()
+        .map(|dir| implementation.join(dir))
+        .find(|dir| fs.is_dir(dir));
+
+    Ok(Adoption {
+        implementation: implementation.to_owned(),
+        input_files,
+        day_name,
+        days,
+    })
+}
+
+/// Infer the naming pattern of a day crate from its name, as in `"aoc2023-day05"` to
+/// `"aoc{year}-day{day:02}"`.
+///
+/// The day is the last number in the name, which must be within 1-25.
+pub(crate) fn infer_day_name(member: &str, year: u32) -> Option<(String, u8)> {
+    let name = Path::new(member).file_name()?.to_str()?;
+    let number = Regex::new(r"\d+").expect("number pattern is valid");
+    let last = number.find_iter(name).last()?;
+    let day = last
+        .as_str()
+        .parse::<u8>()
+        .ok()
+        .filter(|day| (1..=25).contains(day))?;
+    let day_placeholder = if last.as_str().len() == 2 {
+        "{day:02}"
+    } else {
+        "{day}"
+    };
+    let prefix = name[..last.start()].replace(&year.to_string(), "{year}");
+    let suffix = &name[last.end()..];
+    Some((format!("{prefix}{day_placeholder}{suffix}"), day))
+}
+
+/// Adopt an existing workspace for a year: configure its paths and day naming pattern, as
+/// inferred by [`inspect`].
+///
+/// Paths which are already configured differently are an error, as with `init-year`.
+pub fn adopt(
+    host: &Host,
+    config: &mut Config,
+    settings: &mut Settings,
+    implementation: &Path,
+    year: u32,
+) -> Result<Adoption, Error> {
+    let adoption = inspect(host.fs.as_ref(), implementation, year)?;
+    if let Some(day_name) = &adoption.day_name {
+        if settings.day_name(year, 1) != crate::settings::format_day_name(day_name, year, 1) {
+            settings.years.entry(year).or_default().day_name = Some(day_name.clone());
+        }
+    }
+    let path_opts = PathOpts {
+        input_files: adoption.input_files.clone(),
+        implementation: Some(adoption.implementation.clone()),
+        day_templates: None,
+    };
+    crate::initialize_year(host, config, settings, year, path_opts, YearOpts::default())?;
+    Ok(adoption)
+}
@@ -0,0 +1,140 @@
+//! Extract an answer from the output of a day's binary.
+//!
+//! Day crates generated from the stock template report each answer on a line of its own, like
+//! `[aoc] part 1: 1234`; see [`reported`]. Day crates generated from older templates don't agree
+//! on an output format, so the rule used to find the answer otherwise is configurable.
+
+use clap::ValueEnum;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// A rule for finding the answer in a day binary's output.
+///
+/// In `aoctool.toml`, these look like:
+///
+/// ```toml
+/// extract = { rule = "last-line" }
+/// extract = { rule = "regex", pattern = 'answer: (\d+)', group = 1 }
+/// extract = { rule = "json-field", field = "result.answer" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "kebab-case")]
+pub enum Extraction {
+    /// The last non-empty line of output, trimmed.
+    #[default]
+    LastLine,
+    /// The specified capture group of the last match of a regular expression.
+    Regex {
+        pattern: String,
+        #[serde(default = "default_group")]
+        group: usize,
+    },
+    /// A field of the last line of output which parses as a JSON object.
+    ///
+    /// Nested fields are separated by `.`.
+    JsonField { field: String },
+}
+
+fn default_group() -> usize {
+    1
+}
+
+impl Extraction {
+    /// Extract the answer from a binary's output.
+    ///
+    /// Returns `Ok(None)` if the rule does not match the output.
+    pub fn extract(&self, output: &str) -> Result<Option<String>, Error> {
+        let answer = match self {
+            Self::LastLine => output
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(ToOwned::to_owned),
+            Self::Regex { pattern, group } => {
+                let re = Regex::new(pattern).map_err(Error::AnswerPattern)?;
+                re.captures_iter(output)
+                    .filter_map(|captures| captures.get(*group))
+                    .last()
+                    .map(|capture| capture.as_str().trim().to_owned())
+            }
+            Self::JsonField { field } => output
+                .lines()
+                .rev()
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .find_map(|value| {
+                    let value = field
+                        .split('.')
+                        .try_fold(&value, |value, key| value.get(key))?;
+                    match value {
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        serde_json::Value::Null
+                        | serde_json::Value::Array(_)
+                        | serde_json::Value::Object(_) => None,
+                        other => Some(other.to_string()),
+                    }
+                }),
+        };
+        Ok(answer.filter(|answer| !answer.is_empty()))
+    }
+}
+
+/// Prefix of the lines on which day binaries report their answers.
+pub const ANSWER_PREFIX: &str = "[aoc]";
+
+/// The answer to a part which a day binary reported in its output, as `[aoc] part N: <answer>`.
+///
+/// If the part was reported more than once, the last report wins.
+pub fn reported(output: &str, part: Part) -> Option<String> {
+    let label = format!("{part}:");
+    output
+        .lines()
+        .rev()
+        .filter_map(|line| line.strip_prefix(ANSWER_PREFIX))
+        .filter_map(|line| line.trim_start().strip_prefix(&label))
+        .map(str::trim)
+        .find(|answer| !answer.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// Find the answer to a part in a day binary's output.
+///
+/// An answer [reported](reported) in the stock format is preferred; otherwise, the extraction rule
+/// is applied.
+pub fn find(output: &str, part: Part, extraction: &Extraction) -> Result<Option<String>, Error> {
+    match reported(output, part) {
+        Some(answer) => Ok(Some(answer)),
+        None => extraction.extract(output),
+    }
+}
+
+/// A part of a day's puzzle.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ValueEnum,
+)]
+pub enum Part {
+    #[serde(rename = "1")]
+    #[value(name = "1")]
+    One,
+    #[serde(rename = "2")]
+    #[value(name = "2")]
+    Two,
+}
+
+impl Part {
+    /// The website's name for this part.
+    pub fn level(self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "part {}", self.level())
+    }
+}
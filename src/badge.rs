@@ -0,0 +1,66 @@
+//! Badges of a year's stars, for embedding in READMEs.
+//!
+//! Stars are counted from the year's [progress](crate::progress), so `stars sync` first for an
+//! accurate count.
+
+use aoclib::config::Config;
+use clap::ValueEnum;
+
+use crate::{progress::Progress, vfs::Fs, Error};
+
+/// A kind of badge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// A standalone SVG image.
+    Svg,
+    /// JSON for a shields.io endpoint badge.
+    Shields,
+}
+
+/// Count the stars earned in a year.
+pub fn stars(fs: &dyn Fs, config: &Config, year: u32) -> Result<u32, Error> {
+    let progress = Progress::load(fs, config, year)?;
+    Ok((1..=25)
+        .map(|day| u32::from(progress.day(day).stars()))
+        .sum())
+}
+
+/// Render a badge of a year's stars.
+pub fn render(fs: &dyn Fs, config: &Config, year: u32, format: Format) -> Result<String, Error> {
+    let stars = stars(fs, config, year)?;
+    let label = format!("AoC {year}");
+    let message = format!("{stars} ⭐");
+    // the yellow of the website's stars, once they're all earned
+    let color = if stars >= 50 { "ffff66" } else { "9f9f9f" };
+    Ok(match format {
+        Format::Svg => svg(&label, &message, color),
+        Format::Shields => serde_json::json!({
+            "schemaVersion": 1,
+            "label": label,
+            "message": message,
+            "color": color,
+        })
+        .to_string(),
+    })
+}
+
+/// A flat badge in the style of shields.io.
+fn svg(label: &str, message: &str, color: &str) -> String {
+    // roughly the width of a character of 11px Verdana, plus padding
+    let width = |text: &str| text.chars().count() * 7 + 10;
+    let (label_width, message_width) = (width(label), width(message));
+    let total = label_width + message_width;
+    let (label_x, message_x) = (label_width / 2, label_width + message_width / 2);
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{total}" height="20" role="img" aria-label="{label}: {message}">
+  <title>{label}: {message}</title>
+  <rect width="{label_width}" height="20" fill="#0f0f23"/>
+  <rect x="{label_width}" width="{message_width}" height="20" fill="#{color}"/>
+  <g font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11" text-anchor="middle">
+    <text x="{label_x}" y="14" fill="#009900">{label}</text>
+    <text x="{message_x}" y="14" fill="#0f0f23">{message}</text>
+  </g>
+</svg>
+"##
+    )
+}
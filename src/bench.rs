@@ -0,0 +1,298 @@
+//! Benchmark days with the criterion benches of the bench template, and report the results.
+//!
+//! Results are kept in `benchmarks/<year>.toml` within the implementation directory, so that they
+//! can be committed alongside the solutions:
+//!
+//! ```toml
+//! [days.1.part1]
+//! mean-ns = 10732.4
+//! median-ns = 10698.1
+//! ```
+//!
+//! Each day's results are its baseline: benchmarking it again compares the new results with them,
+//! and flags a part as regressed when it has slowed down by more than a threshold. New results
+//! replace the old, except those of regressed parts, which are kept until the regression is fixed
+//! or accepted.
+
+use aoclib::config::Config;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    answer::Part,
+    day_dir, output,
+    run::{cargo, rust_day_dir, CargoOpts},
+    settings::Settings,
+    vfs::Fs,
+    DayOpts, Error,
+};
+
+/// Slowdown of a part's mean time, in percent, beyond which it is flagged as regressed, by default.
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 5.0;
+
+/// Name of the criterion bench of the bench template.
+const BENCH_NAME: &str = "bench";
+
+/// Path to a year's benchmark report.
+pub fn path(config: &Config, year: u32) -> PathBuf {
+    config
+        .implementation(year)
+        .join("benchmarks")
+        .join(format!("{year}.toml"))
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Report {
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub days: BTreeMap<u8, DayBenchmarks>,
+}
+
+/// The results of benchmarking a day.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayBenchmarks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<Estimate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<Estimate>,
+}
+
+impl DayBenchmarks {
+    pub fn get(&self, part: Part) -> Option<&Estimate> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self, part: Part) -> &mut Option<Estimate> {
+        match part {
+            Part::One => &mut self.part1,
+            Part::Two => &mut self.part2,
+        }
+    }
+}
+
+/// How to benchmark days.
+#[derive(Debug, Clone, Default)]
+pub struct BenchOpts {
+    /// How cargo builds the benches.
+    pub cargo: CargoOpts,
+    /// Slowdown in percent beyond which a part is regressed, or else
+    /// [`DEFAULT_REGRESSION_THRESHOLD`].
+    pub threshold: Option<f64>,
+    /// Record the new results even for regressed parts.
+    pub accept: bool,
+}
+
+/// The outcome of benchmarking a day, compared with its baseline.
+#[derive(Debug, Clone)]
+pub struct DayBench {
+    pub day: u8,
+    pub results: DayBenchmarks,
+    /// The results recorded before, if any.
+    pub baseline: DayBenchmarks,
+    /// The parts which slowed down beyond the threshold.
+    pub regressions: Vec<Part>,
+}
+
+impl DayBench {
+    /// The change in a part's mean time from its baseline, as a fraction: `0.1` is 10% slower.
+    pub fn change(&self, part: Part) -> Option<f64> {
+        let current = self.results.get(part)?;
+        let baseline = self.baseline.get(part)?;
+        Some(current.mean_ns / baseline.mean_ns - 1.0)
+    }
+}
+
+/// Criterion's estimate of how long a part takes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Estimate {
+    pub mean_ns: f64,
+    pub median_ns: f64,
+}
+
+impl Report {
+    /// Load a year's benchmark report.
+    ///
+    /// If nothing has been benchmarked, it is empty.
+    pub fn load(config: &Config, year: u32) -> Result<Self, Error> {
+        let path = path(config, year);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = std::fs::read_to_string(&path)
+            .map_err(|err| Error::Io("reading benchmark report", err))?;
+        toml::from_str(&data).map_err(|err| Error::ParseBenchmarks(err, path.display().to_string()))
+    }
+
+    /// Save a year's benchmark report.
+    pub fn save(&self, config: &Config, year: u32) -> Result<(), Error> {
+        let path = path(config, year);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| Error::Io("creating benchmarks directory", err))?;
+        }
+        let data = toml::to_string_pretty(self).map_err(Error::SerializeBenchmarks)?;
+        tracing::debug!(path = %path.display(), "writing benchmark report");
+        std::fs::write(&path, data).map_err(|err| Error::Io("writing benchmark report", err))
+    }
+}
+
+/// Whether a day has a criterion bench, as scaffolded by `init --with-bench`.
+pub fn has_bench(config: &Config, settings: &Settings, year: u32, day: u8) -> bool {
+    day_dir(config, settings, year, day, &DayOpts::default())
+        .join("benches")
+        .join(format!("{BENCH_NAME}.rs"))
+        .exists()
+}
+
+/// Run a day's criterion bench, streaming its output, compare the results with the day's baseline,
+/// and record them in the year's report.
+///
+/// Without a cargo profile, cargo's bench profile is used.
+pub fn bench_day(
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    opts: &BenchOpts,
+) -> Result<DayBench, Error> {
+    let cargo_opts = &opts.cargo;
+    let package_name = settings.day_name(year, day);
+    rust_day_dir(config, settings, year, day)?;
+    if !has_bench(config, settings, year, day) {
+        return Err(Error::NoBenchmarks(package_name));
+    }
+
+    let workspace = config.implementation(year);
+    let target_dir = cargo_opts.target_dir(&workspace);
+    // criterion finds the target directory from the environment, but not from `--target-dir`
+    let cargo_opts = CargoOpts {
+        profile: Some(
+            cargo_opts
+                .profile
+                .clone()
+                .unwrap_or_else(|| "bench".to_string()),
+        ),
+        target_dir: None,
+        ..cargo_opts.clone()
+    };
+    let mut command = Command::new(cargo());
+    command
+        .args(["bench", "--package", &package_name, "--bench", BENCH_NAME])
+        .current_dir(&workspace)
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .stdout(output::child_stdout());
+    cargo_opts.apply(&mut command);
+    let status = command
+        .status()
+        .map_err(|err| Error::Io("running cargo bench", err))?;
+    if !status.success() {
+        return Err(Error::BenchFailed(package_name));
+    }
+
+    let criterion_dir = target_dir.join("criterion");
+    let estimate = |part: &str| {
+        // the bench template groups benchmarks by day; older ones don't
+        read_estimate(&criterion_dir.join(&package_name).join(part))
+            .or_else(|| read_estimate(&criterion_dir.join(part)))
+    };
+    let results = DayBenchmarks {
+        part1: estimate("part1"),
+        part2: estimate("part2"),
+    };
+
+    let mut report = Report::load(config, year)?;
+    let baseline = report.days.get(&day).cloned().unwrap_or_default();
+    let mut bench = DayBench {
+        day,
+        results,
+        baseline,
+        regressions: Vec::new(),
+    };
+    let threshold = opts
+        .threshold
+        .or(settings.bench.regression_threshold)
+        .unwrap_or(DEFAULT_REGRESSION_THRESHOLD);
+    bench.regressions = [Part::One, Part::Two]
+        .into_iter()
+        .filter(|&part| {
+            bench
+                .change(part)
+                .is_some_and(|change| change * 100.0 > threshold)
+        })
+        .collect();
+
+    let recorded = report.days.entry(day).or_default();
+    for part in [Part::One, Part::Two] {
+        if opts.accept || !bench.regressions.contains(&part) {
+            if let Some(estimate) = bench.results.get(part) {
+                *recorded.get_mut(part) = Some(*estimate);
+            }
+        }
+    }
+    report.save(config, year)?;
+    Ok(bench)
+}
+
+/// Read criterion's latest estimate of a benchmark from its output directory.
+fn read_estimate(benchmark_dir: &Path) -> Option<Estimate> {
+    let estimates =
+        std::fs::read_to_string(benchmark_dir.join("new").join("estimates.json")).ok()?;
+    let estimates = serde_json::from_str::<serde_json::Value>(&estimates).ok()?;
+    let point_estimate =
+        |statistic: &str| estimates.get(statistic)?.get("point_estimate")?.as_f64();
+    Some(Estimate {
+        mean_ns: point_estimate("mean")?,
+        median_ns: point_estimate("median")?,
+    })
+}
+
+/// Render a Markdown table of each benchmarked day's mean time per part.
+pub fn timings_table(report: &Report) -> String {
+    let mut table = String::from("| Day | Part 1 | Part 2 |\n| --- | ---: | ---: |\n");
+    let mean = |benchmarks: &DayBenchmarks, part| {
+        benchmarks
+            .get(part)
+            .map(|estimate| format_nanos(estimate.mean_ns))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    for (day, benchmarks) in &report.days {
+        table.push_str(&format!(
+            "| {day} | {} | {} |\n",
+            mean(benchmarks, Part::One),
+            mean(benchmarks, Part::Two),
+        ));
+    }
+    table
+}
+
+/// Update the timings table in the workspace's `README.md` from the year's benchmark report.
+///
+/// The table is kept between `<!-- timings:start -->` and `<!-- timings:end -->`; if the README
+/// lacks them, a section containing them is appended. Returns the path of the README.
+pub fn update_readme_timings(fs: &dyn Fs, config: &Config, year: u32) -> Result<PathBuf, Error> {
+    let table = timings_table(&Report::load(config, year)?);
+    let readme_path = config.implementation(year).join("README.md");
+    crate::update_marked(fs, &readme_path, "timings", "Timings", &table)?;
+    Ok(readme_path)
+}
+
+/// Format a duration in nanoseconds for humans, like "10.73 µs".
+pub fn format_nanos(nanos: f64) -> String {
+    const UNITS: &[&str] = &["ns", "µs", "ms", "s"];
+    let mut value = nanos;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{value:.2} {}", UNITS[unit])
+}
@@ -0,0 +1,35 @@
+//! `cargo aoc`: run `aoc` within the current cargo workspace.
+//!
+//! Cargo runs this as `cargo-aoc aoc <args>`; this runs `aoc <args>`, telling it the workspace
+//! root.
+
+use aoctool::cargo::{workspace_root, WORKSPACE_VAR};
+use color_eyre::eyre::{Result, WrapErr};
+use std::{path::PathBuf, process::Command};
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let mut args = std::env::args_os().skip(1).peekable();
+    // cargo passes the name of the subcommand before its arguments
+    if args.peek().is_some_and(|arg| arg == "aoc") {
+        args.next();
+    }
+
+    // prefer the `aoc` installed alongside this binary
+    let sibling = std::env::current_exe()
+        .wrap_err("locating cargo-aoc")?
+        .with_file_name(format!("aoc{}", std::env::consts::EXE_SUFFIX));
+    let aoc = if sibling.exists() {
+        sibling
+    } else {
+        PathBuf::from("aoc")
+    };
+
+    let status = Command::new(aoc)
+        .args(args)
+        .env(WORKSPACE_VAR, workspace_root()?)
+        .status()
+        .wrap_err("running aoc")?;
+    std::process::exit(status.code().unwrap_or(1));
+}
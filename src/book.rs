@@ -0,0 +1,119 @@
+//! An optional mdBook for the year's writeups.
+//!
+//! When `book/` exists in the implementation directory, each newly initialized day gets a page
+//! which includes its solution, and an entry in the book's summary.
+
+use std::path::Path;
+
+use crate::{append_if_not_present, vfs::Fs, Error};
+
+/// Name of the book directory within the implementation directory.
+pub const BOOK_DIR: &str = "book";
+
+/// Create an mdBook skeleton in the implementation directory.
+///
+/// Existing files are left alone.
+pub fn scaffold_book(fs: &dyn Fs, implementation_dir: &Path, year: u32) -> Result<(), Error> {
+    let book_dir = implementation_dir.join(BOOK_DIR);
+    let src_dir = book_dir.join("src");
+    fs.create_dir_all(&src_dir)
+        .map_err(|err| Error::Io("creating book dir", err))?;
+
+    let write_new = |path: &Path, contents: String| -> Result<(), Error> {
+        if !fs.exists(path) {
+            fs.write(path, contents.as_bytes())
+                .map_err(|err| Error::Io("writing book file", err))?;
+        }
+        Ok(())
+    };
+
+    write_new(
+        &book_dir.join("book.toml"),
+        format!("[book]\ntitle = \"Advent of Code {year}\"\nsrc = \"src\"\n\n[build]\nbuild-dir = \"book\"\n"),
+    )?;
+    write_new(
+        &src_dir.join("README.md"),
+        format!(
+            "# Advent of Code {year}\n\nWriteups of solutions to [Advent of Code {year}](https://adventofcode.com/{year}).\n"
+        ),
+    )?;
+    write_new(
+        &src_dir.join("SUMMARY.md"),
+        "# Summary\n\n[Introduction](README.md)\n\n".to_string(),
+    )?;
+
+    // mdBook's build output does not belong in version control
+    append_if_not_present(fs, implementation_dir.join(".gitignore"), "/book/book/")?;
+
+    Ok(())
+}
+
+/// Add a page for a day to the book, if the book exists.
+///
+/// The page includes the day's solution, so it stays current as the solution changes.
+pub fn add_day_page(
+    fs: &dyn Fs,
+    implementation_dir: &Path,
+    year: u32,
+    day: u8,
+    day_name: &str,
+) -> Result<(), Error> {
+    let src_dir = implementation_dir.join(BOOK_DIR).join("src");
+    if !fs.is_dir(&src_dir) {
+        return Ok(());
+    }
+
+    let page_name = format!("{day_name}.md");
+    let page = src_dir.join(&page_name);
+    if !fs.exists(&page) {
+        let url = aoclib::website::url_for_day(year, day);
+        fs.write(
+            &page,
+            format!(
+                "# Day {day}\n\n[Puzzle]({url})\n\n## Solution\n\n```rust\n{{{{#include ../../{day_name}/src/lib.rs}}}}\n```\n"
+            )
+            .as_bytes(),
+        )
+        .map_err(|err| Error::Io("writing book day page", err))?;
+    }
+    append_if_not_present(
+        fs,
+        src_dir.join("SUMMARY.md"),
+        format!("- [Day {day}]({page_name})"),
+    )
+}
+
+/// Remove a day's page from the book, if the book exists.
+pub fn remove_day_page(
+    fs: &dyn Fs,
+    implementation_dir: &Path,
+    day: u8,
+    day_name: &str,
+) -> Result<(), Error> {
+    let src_dir = implementation_dir.join(BOOK_DIR).join("src");
+    if !fs.is_dir(&src_dir) {
+        return Ok(());
+    }
+
+    let page_name = format!("{day_name}.md");
+    let page = src_dir.join(&page_name);
+    if fs.exists(&page) {
+        fs.remove_file(&page)
+            .map_err(|err| Error::Io("removing book day page", err))?;
+    }
+
+    let summary_path = src_dir.join("SUMMARY.md");
+    if let Ok(summary) = fs.read_to_string(&summary_path) {
+        let entry = format!("- [Day {day}]({page_name})");
+        let updated = summary
+            .lines()
+            .filter(|line| *line != entry)
+            .map(|line| format!("{line}\n"))
+            .collect::<String>();
+        if updated != summary {
+            fs.write(&summary_path, updated.as_bytes())
+                .map_err(|err| Error::Io("writing book summary", err))?;
+        }
+    }
+    Ok(())
+}
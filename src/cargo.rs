@@ -0,0 +1,56 @@
+//! Running as a cargo subcommand, `cargo aoc`.
+//!
+//! The `cargo-aoc` binary finds the workspace it was run from, and passes its root to `aoc` in
+//! [`WORKSPACE_VAR`]. The workspace is then the implementation directory of its year, whatever the
+//! configuration says.
+
+use aoclib::config::Config;
+use std::{path::PathBuf, process::Command};
+
+use crate::{infer::infer_date, settings::Settings, Error};
+
+/// Environment variable naming the root of the workspace `aoc` is working within.
+pub const WORKSPACE_VAR: &str = "AOCTOOL_WORKSPACE";
+
+/// The root of the cargo workspace containing the current directory.
+pub fn workspace_root() -> Result<PathBuf, Error> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args(["locate-project", "--workspace", "--message-format", "plain"])
+        .output()
+        .map_err(|err| Error::Io("running cargo locate-project", err))?;
+    if !output.status.success() {
+        return Err(Error::NoCargoWorkspace(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    let manifest = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    manifest
+        .parent()
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| Error::NoCargoWorkspace(manifest.display().to_string()))
+}
+
+/// Make the workspace named by [`WORKSPACE_VAR`], if any, the implementation directory of its year.
+///
+/// The year is inferred from the workspace's path as by [`infer_date`], or else is this year.
+/// Nothing changes if a configured implementation directory already contains the workspace.
+pub fn apply_workspace(config: &mut Config, settings: &Settings) {
+    let Some(root) = std::env::var_os(WORKSPACE_VAR).map(PathBuf::from) else {
+        return;
+    };
+    let contained = config.paths.keys().any(|&year| {
+        config
+            .implementation(year)
+            .canonicalize()
+            .is_ok_and(|implementation| root.starts_with(implementation))
+    });
+    if contained {
+        return;
+    }
+    let year = infer_date(config, settings, &root)
+        .ok()
+        .and_then(|(year, _)| year)
+        .unwrap_or_else(|| time::OffsetDateTime::now_utc().year() as u32);
+    config.paths.entry(year).or_default().implementation = Some(root);
+}
@@ -0,0 +1,219 @@
+//! A programmatic interface to aoctool, for editors, bots, and other tools which embed it.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), aoctool::Error> {
+//! let aoc = aoctool::Aoc::builder().build()?;
+//! let day_dir = aoc.init_day(2024, 5)?;
+//! let input = aoc.fetch_input(2024, 5)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Its methods block, but may be called from async code too: their requests run on a runtime of
+//! aoctool's own, as [`block_on`](crate::nonblocking::block_on) describes. Inputs can also be
+//! downloaded without blocking, with [`Aoc::fetch_input_async`].
+
+use aoclib::config::Config;
+use std::path::PathBuf;
+
+use crate::{
+    nonblocking,
+    settings::Settings,
+    templates::{self, RenderedFile},
+    website, DayOpts, Error, Host, PathOpts, YearOpts,
+};
+
+/// The configuration and settings aoctool works from, and the operations of its CLI.
+pub struct Aoc {
+    config: Config,
+    settings: Settings,
+    day_opts: DayOpts,
+    host: Host,
+    /// The configuration as loaded or given, before the environment or the keychain overrode it:
+    /// the one which is saved.
+    saved: Config,
+    ignore_env: bool,
+}
+
+/// Builds an [`Aoc`], by default from the user's configuration and settings files.
+#[derive(Default)]
+pub struct AocBuilder {
+    config: Option<Config>,
+    settings: Option<Settings>,
+    day_opts: DayOpts,
+    host: Option<Host>,
+    ignore_env: bool,
+}
+
+impl AocBuilder {
+    /// Use this configuration, rather than loading the configuration file.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Use these settings, rather than loading the aoctool settings file.
+    pub fn settings(mut self, settings: Settings) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Initialize days with these options.
+    pub fn day_opts(mut self, day_opts: DayOpts) -> Self {
+        self.day_opts = day_opts;
+        self
+    }
+
+    /// Reach the network through this host, rather than the [real](Host::real) one.
+    pub fn host(mut self, host: Host) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// Don't override the configuration with [environment variables](crate::env).
+    pub fn ignore_env(mut self) -> Self {
+        self.ignore_env = true;
+        self
+    }
+
+    /// Load whatever was not given, and resolve a session kept in the keychain.
+    ///
+    /// A configuration file which can't be loaded is treated as empty, as the CLI treats it.
+    pub fn build(self) -> Result<Aoc, Error> {
+        let saved = match self.config {
+            Some(config) => config,
+            None => Config::load().unwrap_or_default(),
+        };
+        let settings = match self.settings {
+            Some(settings) => settings,
+            None => Settings::load()?,
+        };
+        let mut config = saved.clone();
+        if !self.ignore_env {
+            crate::env::apply(&mut config);
+        }
+        crate::keychain::resolve_config(&mut config)?;
+        Ok(Aoc {
+            config,
+            settings,
+            day_opts: self.day_opts,
+            host: self.host.unwrap_or_default(),
+            saved,
+            ignore_env: self.ignore_env,
+        })
+    }
+}
+
+impl Aoc {
+    pub fn builder() -> AocBuilder {
+        AocBuilder::default()
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn host(&self) -> &Host {
+        &self.host
+    }
+
+    /// Initialize a day, as `aoc init` does, returning its directory.
+    ///
+    /// See [`initialize`](crate::initialize) for what this entails.
+    pub fn init_day(&self, year: u32, day: u8) -> Result<PathBuf, Error> {
+        crate::initialize(
+            &self.host,
+            &self.config,
+            &self.settings,
+            year,
+            day,
+            false,
+            false,
+            &self.day_opts,
+        )?;
+        Ok(crate::day_dir(
+            &self.config,
+            &self.settings,
+            year,
+            day,
+            &self.day_opts,
+        ))
+    }
+
+    /// Initialize a year's workspace, as `aoc init-year` does.
+    ///
+    /// The year's paths are recorded in this `Aoc`'s configuration; [`save`](Self::save) keeps
+    /// them. See [`initialize_year`](crate::initialize_year) for what this entails.
+    pub fn init_year(
+        &mut self,
+        year: u32,
+        path_opts: PathOpts,
+        year_opts: YearOpts,
+    ) -> Result<(), Error> {
+        crate::initialize_year(
+            &self.host,
+            &mut self.saved,
+            &mut self.settings,
+            year,
+            path_opts,
+            year_opts,
+        )?;
+        let paths = self.saved.paths.get(&year).cloned().unwrap_or_default();
+        self.config.paths.insert(year, paths);
+        if !self.ignore_env {
+            crate::env::apply(&mut self.config);
+        }
+        Ok(())
+    }
+
+    /// Download a day's input, unless it was downloaded already, returning its path.
+    ///
+    /// From async code, use [`fetch_input_async`](Self::fetch_input_async) instead.
+    pub fn fetch_input(&self, year: u32, day: u8) -> Result<PathBuf, Error> {
+        website::get_input(&self.host, &self.config, year, day)
+    }
+
+    /// Download a day's input, as [`fetch_input`](Self::fetch_input) does, without blocking.
+    pub async fn fetch_input_async(&self, year: u32, day: u8) -> Result<PathBuf, Error> {
+        nonblocking::get_input(&self.host, &self.config, year, day).await
+    }
+
+    /// Render a day's templates, without writing anything.
+    ///
+    /// Missing stock templates are not downloaded; [`init_day`](Self::init_day) downloads them.
+    pub fn render_templates(&self, year: u32, day: u8) -> Result<Vec<RenderedFile>, Error> {
+        let day_name = self.settings.day_name(year, day);
+        let context = templates::Context::new(
+            &self.host,
+            &self.config,
+            &self.settings,
+            &self.day_opts,
+            year,
+            day,
+            &day_name,
+        )?;
+        templates::preview_templates(
+            self.host.fs.as_ref(),
+            &self.config,
+            &self.settings,
+            &self.day_opts,
+            &context,
+            year,
+        )
+    }
+
+    /// Save the configuration and settings files.
+    ///
+    /// The configuration is saved as it was loaded or given, with the years since initialized,
+    /// rather than as the environment overrode it, or with the session from the keychain.
+    pub fn save(&mut self) -> Result<(), Error> {
+        self.saved
+            .save()
+            .map_err(|err| Error::SaveConfig(err.to_string()))?;
+        self.settings.save()
+    }
+}
@@ -0,0 +1,305 @@
+//! Diagnose problems with the configuration, each with a suggested fix.
+
+use aoclib::config::Config;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+use crate::{
+    keychain, nonblocking,
+    settings::{self, Settings},
+    templates::{self, TemplateSet},
+    website, Error, Host,
+};
+
+/// How far the local clock may drift from the website's before it matters: puzzles unlock, and
+/// `countdown` ends, by the local clock.
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5);
+
+/// The result of a single diagnostic.
+#[derive(Debug, Clone)]
+pub struct Check {
+    /// What was checked.
+    pub subject: String,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    /// All is well; with a note on what was found.
+    Pass(String),
+    /// Something is wrong; with what to do about it.
+    Fail { problem: String, fix: String },
+}
+
+impl Check {
+    fn pass(subject: impl Into<String>, note: impl Into<String>) -> Self {
+        Check {
+            subject: subject.into(),
+            outcome: Outcome::Pass(note.into()),
+        }
+    }
+
+    fn fail(
+        subject: impl Into<String>,
+        problem: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Check {
+            subject: subject.into(),
+            outcome: Outcome::Fail {
+                problem: problem.into(),
+                fix: fix.into(),
+            },
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        matches!(self.outcome, Outcome::Pass(_))
+    }
+}
+
+/// Run every diagnostic on the configuration.
+///
+/// This makes requests of the website, to check the session key and the clock.
+pub fn diagnose(host: &Host, config: &Config) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let config_path = aoclib::config::path();
+    checks.push(if config_path.exists() {
+        Check::pass("config file", config_path.display().to_string())
+    } else {
+        Check::fail(
+            "config file",
+            format!("{} does not exist", config_path.display()),
+            "run `aoc config set --session <key>` to create it",
+        )
+    });
+
+    let settings = match Settings::load() {
+        Ok(settings) => {
+            checks.push(Check::pass(
+                "settings file",
+                settings::path().display().to_string(),
+            ));
+            settings
+        }
+        Err(err) => {
+            checks.push(Check::fail(
+                "settings file",
+                error_chain(&err),
+                format!("correct or remove {}", settings::path().display()),
+            ));
+            Settings::default()
+        }
+    };
+
+    checks.push(check_session(host, config));
+    checks.push(check_clock(host));
+
+    for &year in config.paths.keys() {
+        checks.extend(check_paths(config, year));
+        checks.push(check_templates(host, config, &settings, year));
+    }
+    checks
+}
+
+fn check_session(host: &Host, config: &Config) -> Check {
+    const SUBJECT: &str = "session key";
+    const NEW_SESSION: &str = "log in to adventofcode.com, copy the `session` cookie, and run \
+                               `aoc config set --session <key>`";
+    if config.session.is_empty() {
+        return Check::fail(SUBJECT, "no session key is configured", NEW_SESSION);
+    }
+    let session = match keychain::resolve(&config.session) {
+        Ok(session) => session,
+        Err(err) => {
+            return Check::fail(
+                SUBJECT,
+                error_chain(&err),
+                "unlock the keychain, or store the session key again with `aoc config set --keychain --session <key>`",
+            )
+        }
+    };
+    match website::whoami(host.http.as_ref(), &session) {
+        Ok(name) => Check::pass(SUBJECT, format!("logged in as {name}")),
+        Err(Error::NotLoggedIn) => Check::fail(
+            SUBJECT,
+            "the session key is invalid or has expired",
+            NEW_SESSION,
+        ),
+        Err(err) => Check::fail(
+            SUBJECT,
+            error_chain(&err),
+            "check the network connection, and try again",
+        ),
+    }
+}
+
+fn check_clock(host: &Host) -> Check {
+    const SUBJECT: &str = "clock";
+    let server_time = match server_time(host) {
+        Ok(server_time) => server_time,
+        Err(err) => {
+            return Check::fail(
+                SUBJECT,
+                error_chain(&err),
+                "check the network connection, and try again",
+            )
+        }
+    };
+    let skew = OffsetDateTime::now_utc() - server_time;
+    let description = if skew.is_negative() {
+        format!("{:.1}s behind the website", -skew.as_seconds_f64())
+    } else {
+        format!("{:.1}s ahead of the website", skew.as_seconds_f64())
+    };
+    if skew.unsigned_abs() > MAX_CLOCK_SKEW {
+        Check::fail(
+            SUBJECT,
+            description,
+            "enable time synchronization (NTP) in the operating system",
+        )
+    } else {
+        Check::pass(SUBJECT, description)
+    }
+}
+
+/// The website's time, from the `Date` header of its response.
+///
+/// The header has a resolution of a second, so this may be up to a second behind.
+fn server_time(host: &Host) -> Result<OffsetDateTime, Error> {
+    tracing::info!("requesting server time");
+    let response = nonblocking::block_on(host.http.head("https://adventofcode.com/"))?;
+    response
+        .header("date")
+        .and_then(parse_http_date)
+        .ok_or(Error::NoServerTime)
+}
+
+/// Parse an HTTP date, like "Sun, 06 Nov 1994 08:49:37 GMT".
+fn parse_http_date(date: &str) -> Option<OffsetDateTime> {
+    const MONTHS: &[&str] = &[
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut fields = date.split_whitespace().skip(1);
+    let day = fields.next()?.parse().ok()?;
+    let month_name = fields.next()?;
+    let month = MONTHS.iter().position(|&month| month == month_name)?;
+    let year = fields.next()?.parse().ok()?;
+    let mut clock = fields.next()?.split(':').map(str::parse::<u8>);
+    let (hour, minute, second) = (
+        clock.next()?.ok()?,
+        clock.next()?.ok()?,
+        clock.next()?.ok()?,
+    );
+    let date =
+        time::Date::from_calendar_date(year, time::Month::try_from(month as u8 + 1).ok()?, day)
+            .ok()?;
+    let time = time::Time::from_hms(hour, minute, second).ok()?;
+    Some(date.with_time(time).assume_utc())
+}
+
+/// Check that a year's directories exist and are writable.
+fn check_paths(config: &Config, year: u32) -> Vec<Check> {
+    [
+        ("implementation", config.implementation(year)),
+        ("input files", config.input_files(year)),
+    ]
+    .into_iter()
+    .map(|(name, path)| {
+        let subject = format!("{year} {name}");
+        match writable(&path) {
+            Ok(()) if path.is_dir() => Check::pass(subject, path.display().to_string()),
+            Ok(()) => Check::pass(
+                subject,
+                format!("{} (will be created)", path.display()),
+            ),
+            Err(problem) => Check::fail(
+                subject,
+                problem,
+                format!(
+                    "make it writable, or configure another with `aoc config set --year {year} --{}`",
+                    name.replace(' ', "-")
+                ),
+            ),
+        }
+    })
+    .collect()
+}
+
+/// Check that a directory is writable, or could be created.
+fn writable(path: &Path) -> Result<(), String> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(path);
+    if !existing.is_dir() {
+        return Err(format!("{} is not a directory", existing.display()));
+    }
+    let probe = existing.join(".aoctool-doctor");
+    std::fs::write(&probe, "")
+        .and_then(|()| std::fs::remove_file(&probe))
+        .map_err(|err| format!("{} is not writable: {err}", existing.display()))
+}
+
+/// Check that a year's day templates render.
+fn check_templates(host: &Host, config: &Config, settings: &Settings, year: u32) -> Check {
+    let subject = format!("{year} day templates");
+    let dirs = templates::template_dirs(config, settings, year);
+    let fs = host.fs.as_ref();
+    if dirs.iter().all(|dir| !fs.is_dir(dir)) {
+        return Check::pass(
+            subject,
+            "stock templates will be downloaded on the next `init`",
+        );
+    }
+    let missing = templates::missing_stock_templates(fs, config, settings, year).len();
+
+    let dirs = dirs.iter().map(PathBuf::as_path).collect::<Vec<_>>();
+    let result = TemplateSet::load_layered(fs, &dirs).and_then(|template_set| {
+        let context = templates::Context::placeholder(config, settings, year);
+        Ok((
+            template_set.files.len(),
+            template_set.validate(fs, &context)?,
+        ))
+    });
+    match result {
+        Ok((count, problems)) if problems.is_empty() => {
+            let missing = match missing {
+                0 => String::new(),
+                missing => {
+                    format!("; {missing} stock templates will be downloaded on the next `init`")
+                }
+            };
+            Check::pass(subject, format!("{count} templates ok{missing}"))
+        }
+        Ok((_, problems)) => Check::fail(
+            subject,
+            problems
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+            format!("correct the templates; `aoc templates validate --year {year}` rechecks them"),
+        ),
+        Err(err) => Check::fail(
+            subject,
+            error_chain(&err),
+            "correct the templates, or `aoc config clear --day-template` to use the stock ones",
+        ),
+    }
+}
+
+/// An error and its sources, on one line.
+fn error_chain(err: &Error) -> String {
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        message.push_str(&format!(": {err}"));
+        source = err.source();
+    }
+    message
+}
@@ -0,0 +1,59 @@
+//! Configuration from environment variables, for CI pipelines and containers.
+//!
+//! Each variable which is set overrides the configuration file, so that a session can be injected
+//! without writing one to disk.
+
+use aoclib::config::Config;
+use std::path::PathBuf;
+
+use crate::Error;
+
+/// Environment variable holding the website session key.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Environment variable holding the default year.
+pub const YEAR_VAR: &str = "AOC_YEAR";
+
+/// Environment variable naming the directory of input files, in a subdirectory for each year.
+pub const INPUTS_DIR_VAR: &str = "AOC_INPUTS_DIR";
+
+/// The year in [`YEAR_VAR`], if it is set.
+pub fn year() -> Result<Option<u32>, Error> {
+    match std::env::var(YEAR_VAR) {
+        Ok(year) => year
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| Error::InvalidEnvVar(YEAR_VAR, year)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Whether the session key is set in the environment, so that no configuration file is needed.
+pub fn has_session() -> bool {
+    std::env::var_os(SESSION_VAR).is_some_and(|session| !session.is_empty())
+}
+
+/// Override the configuration with those environment variables which are set.
+///
+/// The inputs directory applies to every configured year, and to the year in [`YEAR_VAR`], or
+/// else this year. Input files are named for their day alone, so each year's go in a
+/// subdirectory of their own, named for the year.
+pub fn apply(config: &mut Config) {
+    if let Some(session) = std::env::var(SESSION_VAR)
+        .ok()
+        .filter(|session| !session.is_empty())
+    {
+        config.session = session;
+    }
+    if let Some(inputs_dir) = std::env::var_os(INPUTS_DIR_VAR).map(PathBuf::from) {
+        let year = year()
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| time::OffsetDateTime::now_utc().year() as u32);
+        config.paths.entry(year).or_default();
+        for (year, paths) in config.paths.iter_mut() {
+            paths.input_files = Some(inputs_dir.join(year.to_string()));
+        }
+    }
+}
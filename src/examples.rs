@@ -0,0 +1,94 @@
+//! Example inputs and their expected answers.
+//!
+//! Examples live in the `examples` subdirectory of a year's inputs directory. Example `K` of day
+//! `NN` consists of the input `NN-K.txt` and, optionally, the expected answers `NN-K.answers.toml`:
+//!
+//! ```toml
+//! part1 = "142"
+//! part2 = "281"
+//! ```
+//!
+//! Examples are numbered from 1.
+
+use aoclib::config::Config;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::{answer::Part, Error};
+
+/// Expected answers for an example.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    /// The expected answer for a particular part, if known.
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Example {
+    pub index: usize,
+    pub input: PathBuf,
+    pub answers: Answers,
+}
+
+/// Directory containing a year's examples.
+pub fn examples_dir(config: &Config, year: u32) -> PathBuf {
+    config.input_files(year).join("examples")
+}
+
+/// Path to the input of a particular example.
+pub fn example_input(config: &Config, year: u32, day: u8, index: usize) -> PathBuf {
+    examples_dir(config, year).join(format!("{day:02}-{index}.txt"))
+}
+
+/// Path to the expected answers of a particular example.
+pub fn example_answers(config: &Config, year: u32, day: u8, index: usize) -> PathBuf {
+    examples_dir(config, year).join(format!("{day:02}-{index}.answers.toml"))
+}
+
+/// Load a particular example, if its input exists.
+pub fn example(
+    config: &Config,
+    year: u32,
+    day: u8,
+    index: usize,
+) -> Result<Option<Example>, Error> {
+    let input = example_input(config, year, day, index);
+    if !input.exists() {
+        return Ok(None);
+    }
+    let answers_path = example_answers(config, year, day, index);
+    let answers = if answers_path.exists() {
+        let data = std::fs::read_to_string(&answers_path)
+            .map_err(|err| Error::Io("reading example answers", err))?;
+        toml::from_str(&data)
+            .map_err(|err| Error::ParseExampleAnswers(err, answers_path.display().to_string()))?
+    } else {
+        Answers::default()
+    };
+    Ok(Some(Example {
+        index,
+        input,
+        answers,
+    }))
+}
+
+/// Load all examples for a day.
+///
+/// Examples are numbered contiguously; the first missing index ends the list.
+pub fn examples_for(config: &Config, year: u32, day: u8) -> Result<Vec<Example>, Error> {
+    let mut examples = Vec::new();
+    while let Some(example) = example(config, year, day, examples.len() + 1)? {
+        examples.push(example);
+    }
+    Ok(examples)
+}
@@ -0,0 +1,125 @@
+//! Export everything tracked locally about a year, for analysis elsewhere.
+//!
+//! Each part of each day about which anything is known becomes one flat record, so that the same
+//! records serve as rows of a CSV file or objects of a JSON array.
+
+use aoclib::config::Config;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{answer::Part, bench, progress::Progress, vfs::Fs, Error};
+
+/// A format in which to export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+/// Everything known about one part of a day.
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    pub year: u32,
+    pub day: u8,
+    pub part: u8,
+    /// Whether the part's star is earned.
+    pub solved: bool,
+    /// The answer recorded as correct.
+    pub answer: Option<String>,
+    /// How long the part took to solve, in seconds: part 1 from the start, part 2 from part 1.
+    pub solve_seconds: Option<u64>,
+    /// Mean time of the part's latest recorded benchmark.
+    pub bench_mean_ns: Option<f64>,
+    /// Median time of the part's latest recorded benchmark.
+    pub bench_median_ns: Option<f64>,
+}
+
+/// The names of the fields of a [`Record`], in order.
+const HEADER: &[&str] = &[
+    "year",
+    "day",
+    "part",
+    "solved",
+    "answer",
+    "solve_seconds",
+    "bench_mean_ns",
+    "bench_median_ns",
+];
+
+impl Record {
+    fn fields(&self) -> Vec<String> {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        vec![
+            self.year.to_string(),
+            self.day.to_string(),
+            self.part.to_string(),
+            self.solved.to_string(),
+            optional(self.answer.clone()),
+            optional(self.solve_seconds.map(|seconds| seconds.to_string())),
+            optional(self.bench_mean_ns.map(|ns| ns.to_string())),
+            optional(self.bench_median_ns.map(|ns| ns.to_string())),
+        ]
+    }
+}
+
+/// Gather the records of a year.
+pub fn records(fs: &dyn Fs, config: &Config, year: u32) -> Result<Vec<Record>, Error> {
+    let progress = Progress::load(fs, config, year)?;
+    let benchmarks = bench::Report::load(config, year)?;
+
+    let mut records = Vec::new();
+    for day in 1..=25 {
+        let day_progress = progress.day(day);
+        let day_benchmarks = benchmarks.days.get(&day).cloned().unwrap_or_default();
+        for part in [Part::One, Part::Two] {
+            let estimate = day_benchmarks.get(part);
+            let record = Record {
+                year,
+                day,
+                part: part.level(),
+                solved: day_progress.stars() >= part.level(),
+                answer: day_progress.answers.get(part).map(ToOwned::to_owned),
+                solve_seconds: day_progress
+                    .times
+                    .solve_duration(part)
+                    .map(|duration| duration.as_secs()),
+                bench_mean_ns: estimate.map(|estimate| estimate.mean_ns),
+                bench_median_ns: estimate.map(|estimate| estimate.median_ns),
+            };
+            if record.solved || record.answer.is_some() || estimate.is_some() {
+                records.push(record);
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Render records in a format.
+pub fn render(records: &[Record], format: Format) -> Result<String, Error> {
+    match format {
+        Format::Json => serde_json::to_string_pretty(records).map_err(Error::SerializeExport),
+        Format::Csv => {
+            let mut csv = HEADER.join(",");
+            csv.push('\n');
+            for record in records {
+                let fields = record
+                    .fields()
+                    .iter()
+                    .map(|field| csv_field(field))
+                    .collect::<Vec<_>>();
+                csv.push_str(&fields.join(","));
+                csv.push('\n');
+            }
+            Ok(csv)
+        }
+    }
+}
+
+/// Quote a CSV field if it needs it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
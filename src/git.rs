@@ -0,0 +1,173 @@
+//! Thin wrappers around the `git` command line.
+
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{output, Error};
+
+/// Run `git` with the specified arguments in `dir`.
+pub(crate) fn git<I, S>(dir: &Path, args: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    let mut command = Command::new("git");
+    command
+        .current_dir(dir)
+        .args(args)
+        .stdout(output::child_stdout());
+    tracing::debug!(?command, "running git");
+    let status = command
+        .status()
+        .map_err(|err| Error::Io("running git", err))?;
+    if !status.success() {
+        return Err(Error::GitFailed(format!(
+            "{:?}",
+            command.get_args().collect::<Vec<_>>()
+        )));
+    }
+    Ok(())
+}
+
+/// Run `git` with the specified arguments in `dir`, reporting only whether it succeeded.
+pub(crate) fn git_succeeds<I, S>(dir: &Path, args: I) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or_default()
+}
+
+/// Read a value from the user's git configuration.
+pub(crate) fn config_value(key: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "--get", key])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8(output.stdout).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Stage `paths` within `dir`, and commit them with `message`.
+///
+/// Returns whether a commit was made: nothing is committed if `dir` is not in a git repository, or
+/// if the paths have no changes.
+pub(crate) fn commit_paths(dir: &Path, paths: &[PathBuf], message: &str) -> Result<bool, Error> {
+    if !git_succeeds(dir, ["rev-parse", "--git-dir"]) {
+        return Ok(false);
+    }
+    let paths = paths
+        .iter()
+        .filter(|path| path.exists())
+        .collect::<Vec<_>>();
+    if paths.is_empty() {
+        return Ok(false);
+    }
+    let mut add = vec![OsStr::new("add"), OsStr::new("--")];
+    add.extend(paths.iter().map(|path| path.as_os_str()));
+    git(dir, add)?;
+    let mut staged = vec![
+        OsStr::new("diff"),
+        OsStr::new("--cached"),
+        OsStr::new("--quiet"),
+        OsStr::new("--"),
+    ];
+    staged.extend(paths.iter().map(|path| path.as_os_str()));
+    if git_succeeds(dir, staged) {
+        return Ok(false);
+    }
+    let mut commit = vec![
+        OsStr::new("commit"),
+        OsStr::new("--quiet"),
+        OsStr::new("-m"),
+        OsStr::new(message),
+        OsStr::new("--"),
+    ];
+    commit.extend(paths.iter().map(|path| path.as_os_str()));
+    git(dir, commit)?;
+    Ok(true)
+}
+
+/// A git repository and optional ref (branch or tag), written as `<url>[#<ref>]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitSource {
+    pub url: String,
+    pub reference: Option<String>,
+}
+
+impl std::str::FromStr for GitSource {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.rsplit_once('#') {
+            Some((url, reference)) if !reference.is_empty() => Self {
+                url: url.to_string(),
+                reference: Some(reference.to_string()),
+            },
+            _ => Self {
+                url: s.trim_end_matches('#').to_string(),
+                reference: None,
+            },
+        })
+    }
+}
+
+impl std::fmt::Display for GitSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reference {
+            Some(reference) => write!(f, "{}#{}", self.url, reference),
+            None => write!(f, "{}", self.url),
+        }
+    }
+}
+
+impl GitSource {
+    /// Ensure that `dir` contains an up-to-date checkout of this source.
+    ///
+    /// If `dir` does not exist or is empty, the repository is cloned into it. Otherwise, it
+    /// must already be a clone; it is updated from its remote.
+    pub fn sync(&self, dir: &Path) -> Result<(), Error> {
+        let is_empty = std::fs::read_dir(dir)
+            .map(|mut entries| entries.next().is_none())
+            .unwrap_or(true);
+
+        if is_empty {
+            if let Some(parent) = dir.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|err| Error::Io("creating template parent directory", err))?;
+            }
+            let target = dir.to_string_lossy();
+            let mut args = vec!["clone", "--quiet"];
+            if let Some(reference) = &self.reference {
+                args.extend(["--branch", reference.as_str()]);
+            }
+            args.extend([self.url.as_str(), target.as_ref()]);
+            return git(Path::new("."), args);
+        }
+
+        git(dir, ["fetch", "--quiet", "--tags", "origin"])?;
+        if let Some(reference) = &self.reference {
+            git(dir, ["checkout", "--quiet", reference.as_str()])?;
+        }
+        // tags leave us with a detached head, which has nothing to pull
+        if git_succeeds(dir, ["symbolic-ref", "-q", "HEAD"]) {
+            git(dir, ["pull", "--quiet", "--ff-only"])?;
+        }
+        Ok(())
+    }
+}
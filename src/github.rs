@@ -0,0 +1,62 @@
+//! Creating repositories on GitHub.
+
+use serde::Deserialize;
+
+use crate::{http::HttpClient, nonblocking, settings::Settings, Error};
+
+/// A repository created on GitHub.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Repository {
+    pub full_name: String,
+    pub html_url: String,
+    pub clone_url: String,
+}
+
+/// The GitHub token: from the settings, or else from the `GITHUB_TOKEN` environment variable.
+fn token(settings: &Settings) -> Result<String, Error> {
+    settings
+        .github
+        .token
+        .clone()
+        .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+        .filter(|token| !token.is_empty())
+        .ok_or(Error::NoGitHubToken)
+}
+
+/// Create a repository belonging to the token's user.
+pub fn create_repository(
+    http: &dyn HttpClient,
+    settings: &Settings,
+    name: &str,
+    private: bool,
+) -> Result<Repository, Error> {
+    let body = serde_json::json!({
+        "name": name,
+        "private": private,
+        "description": "Advent of Code solutions",
+    })
+    .to_string();
+    let authorization = format!("Bearer {}", token(settings)?);
+    tracing::info!(name, "creating GitHub repository");
+    let response = nonblocking::block_on(http.post(
+        "https://api.github.com/user/repos",
+        &[
+            ("authorization", authorization.as_str()),
+            ("accept", "application/vnd.github+json"),
+            ("content-type", "application/json"),
+        ],
+        body.as_bytes(),
+    ))?;
+    let text = response.text();
+    if !response.is_success() {
+        #[derive(Deserialize)]
+        struct ApiError {
+            message: String,
+        }
+        let message = serde_json::from_str::<ApiError>(&text)
+            .map(|err| err.message)
+            .unwrap_or(text);
+        return Err(Error::GitHub(format!("{}: {message}", response.status)));
+    }
+    serde_json::from_str(&text).map_err(|err| Error::GitHub(err.to_string()))
+}
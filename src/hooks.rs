@@ -0,0 +1,66 @@
+//! Commands run after a day is initialized.
+//!
+//! Hooks are configured in `aoctool.toml`:
+//!
+//! ```toml
+//! [hooks]
+//! post-init = ["cargo fmt -p {package_name}", "git add {day_dir}"]
+//! ```
+//!
+//! Each command is rendered as a TinyTemplate with the template variables, plus `day_dir`, then run
+//! with `sh -c` in the implementation directory.
+
+use serde::Serialize;
+use std::{path::Path, process::Command};
+
+use crate::{
+    output,
+    settings::Settings,
+    templates::{self, Context, Engine},
+    Error,
+};
+
+/// Variables available within hook commands.
+#[derive(Debug, Serialize)]
+struct HookContext<'a> {
+    #[serde(flatten)]
+    context: &'a Context,
+    /// Path to the new day.
+    day_dir: String,
+}
+
+/// Render the post-init hooks for a day.
+pub fn post_init_commands(
+    settings: &Settings,
+    context: &Context,
+    day_dir: &Path,
+) -> Result<Vec<String>, Error> {
+    let context = HookContext {
+        context,
+        day_dir: day_dir.display().to_string(),
+    };
+    settings
+        .hooks
+        .post_init
+        .iter()
+        .map(|command| templates::render_str(Engine::TinyTemplate, command, command, &context))
+        .collect()
+}
+
+/// Run rendered hook commands in `dir`, stopping at the first which fails.
+pub(crate) fn run(dir: &Path, commands: &[String]) -> Result<(), Error> {
+    for command in commands {
+        tracing::info!(command, "running hook");
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .current_dir(dir)
+            .stdout(output::child_stdout())
+            .status()
+            .map_err(|err| Error::Io("running hook", err))?;
+        if !status.success() {
+            return Err(Error::HookFailed(command.clone()));
+        }
+    }
+    Ok(())
+}
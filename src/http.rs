@@ -0,0 +1,326 @@
+//! The HTTP layer, behind a trait, so that requests can be answered without a network.
+//!
+//! Every request of the website and of GitHub, and every template download, goes through the
+//! client of the [`Host`](crate::Host) it is given; the real one makes requests with `reqwest`. A
+//! [`MockClient`] answers them from canned responses instead, and records them:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! let mock = Arc::new(aoctool::http::MockClient::new());
+//! mock.respond("https://example.com/master/day-template/Cargo.toml", 200, "[package]");
+//! let host = aoctool::Host {
+//!     http: mock.clone(),
+//!     ..aoctool::Host::real()
+//! };
+//! // ... download templates through `host` ...
+//! assert_eq!(mock.requests().len(), 1);
+//! ```
+//!
+//! The client is async, for [`nonblocking`](crate::nonblocking); the blocking functions elsewhere
+//! wait on it with [`block_on`](crate::nonblocking::block_on).
+
+use futures::future::{self, BoxFuture};
+use reqwest::header;
+use std::{collections::BTreeMap, sync::Mutex};
+
+use crate::Error;
+
+const USER_AGENT: &str = concat!("github.com/coriolinus/aoctool v", env!("CARGO_PKG_VERSION"));
+
+/// A response, read in full.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    /// Headers, by lowercase name.
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Whether the status is 2xx.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The body as text, replacing invalid UTF-8.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// A header, by case-insensitive name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+}
+
+/// Something which can make HTTP requests.
+///
+/// Unsuccessful statuses are responses like any other, rather than errors.
+pub trait HttpClient: Send + Sync {
+    /// Request `url`, sending `cookie` if given.
+    fn get<'a>(
+        &'a self,
+        url: &'a str,
+        cookie: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Response, Error>>;
+
+    /// Request only the headers of `url`.
+    fn head<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<Response, Error>>;
+
+    /// Post a form to `url`, sending `cookie` if given.
+    fn post_form<'a>(
+        &'a self,
+        url: &'a str,
+        cookie: Option<&'a str>,
+        form: &'a [(&'a str, &'a str)],
+    ) -> BoxFuture<'a, Result<Response, Error>>;
+
+    /// Post `body` to `url`, with these headers.
+    fn post<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: &'a [u8],
+    ) -> BoxFuture<'a, Result<Response, Error>>;
+}
+
+/// Make real requests with `reqwest`.
+///
+/// A client is built for each request: a client's connections belong to the async runtime which
+/// made them, and a request may be made on aoctool's own runtime, through a blocking function, or
+/// on that of an async caller.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReqwestClient;
+
+impl ReqwestClient {
+    /// Build the client through which every request is made.
+    fn client() -> Result<reqwest::Client, Error> {
+        reqwest::Client::builder()
+            .gzip(true)
+            .timeout(std::time::Duration::from_secs(5))
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(Error::ClientBuilder)
+    }
+
+    async fn send(
+        url: &str,
+        request: reqwest::RequestBuilder,
+        cookie: Option<&str>,
+    ) -> Result<Response, Error> {
+        let request = match cookie {
+            Some(cookie) => request.header(header::COOKIE, cookie),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|err| Error::Http(url.to_string(), err))?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|err| Error::Http(url.to_string(), err))?
+            .to_vec();
+        Ok(Response {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+impl HttpClient for ReqwestClient {
+    fn get<'a>(
+        &'a self,
+        url: &'a str,
+        cookie: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        Box::pin(async move { Self::send(url, Self::client()?.get(url), cookie).await })
+    }
+
+    fn head<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<Response, Error>> {
+        Box::pin(async move { Self::send(url, Self::client()?.head(url), None).await })
+    }
+
+    fn post_form<'a>(
+        &'a self,
+        url: &'a str,
+        cookie: Option<&'a str>,
+        form: &'a [(&'a str, &'a str)],
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        Box::pin(async move {
+            let request = Self::client()?.post(url).form(form);
+            Self::send(url, request, cookie).await
+        })
+    }
+
+    fn post<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: &'a [u8],
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        Box::pin(async move {
+            let request = headers
+                .iter()
+                .fold(Self::client()?.post(url), |request, (name, value)| {
+                    request.header(*name, *value)
+                })
+                .body(body.to_vec());
+            Self::send(url, request, None).await
+        })
+    }
+}
+
+/// A request made of a [`MockClient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// `GET`, `HEAD`, or `POST`.
+    pub method: &'static str,
+    pub url: String,
+    pub cookie: Option<String>,
+    /// The form posted, if any.
+    pub form: Vec<(String, String)>,
+    /// The headers sent with a [post](HttpClient::post).
+    pub headers: Vec<(String, String)>,
+    /// The body of a post, if any.
+    pub body: Vec<u8>,
+}
+
+/// Answer requests from canned responses, recording each, for tests.
+///
+/// Requests of a URL without a response get a 404.
+#[derive(Debug, Default)]
+pub struct MockClient {
+    responses: Mutex<BTreeMap<String, Response>>,
+    requests: Mutex<Vec<Request>>,
+}
+
+impl MockClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer requests of `url` with this status and body.
+    pub fn respond(&self, url: &str, status: u16, body: impl Into<Vec<u8>>) {
+        self.respond_with(
+            url,
+            Response {
+                status,
+                body: body.into(),
+                ..Response::default()
+            },
+        );
+    }
+
+    /// Answer requests of `url` with this response.
+    pub fn respond_with(&self, url: &str, response: Response) {
+        self.responses
+            .lock()
+            .expect("mock responses are not poisoned")
+            .insert(url.to_string(), response);
+    }
+
+    /// The requests made so far, in order.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests
+            .lock()
+            .expect("mock requests are not poisoned")
+            .clone()
+    }
+
+    fn answer(&self, request: Request) -> Response {
+        let url = request.url.clone();
+        self.requests
+            .lock()
+            .expect("mock requests are not poisoned")
+            .push(request);
+        self.responses
+            .lock()
+            .expect("mock responses are not poisoned")
+            .get(&url)
+            .cloned()
+            .unwrap_or(Response {
+                status: 404,
+                ..Response::default()
+            })
+    }
+}
+
+fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+impl Request {
+    fn new(method: &'static str, url: &str, cookie: Option<&str>) -> Self {
+        Request {
+            method,
+            url: url.to_string(),
+            cookie: cookie.map(ToOwned::to_owned),
+            form: Vec::new(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+}
+
+impl HttpClient for MockClient {
+    fn get<'a>(
+        &'a self,
+        url: &'a str,
+        cookie: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        Box::pin(future::ready(Ok(
+            self.answer(Request::new("GET", url, cookie))
+        )))
+    }
+
+    fn head<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<Response, Error>> {
+        let response = Response {
+            body: Vec::new(),
+            ..self.answer(Request::new("HEAD", url, None))
+        };
+        Box::pin(future::ready(Ok(response)))
+    }
+
+    fn post_form<'a>(
+        &'a self,
+        url: &'a str,
+        cookie: Option<&'a str>,
+        form: &'a [(&'a str, &'a str)],
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        let request = Request {
+            form: pairs(form),
+            ..Request::new("POST", url, cookie)
+        };
+        Box::pin(future::ready(Ok(self.answer(request))))
+    }
+
+    fn post<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: &'a [u8],
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        let request = Request {
+            headers: pairs(headers),
+            body: body.to_vec(),
+            ..Request::new("POST", url, None)
+        };
+        Box::pin(future::ready(Ok(self.answer(request))))
+    }
+}
@@ -0,0 +1,61 @@
+//! Infer the year and day from the current directory.
+//!
+//! When run from within a day's directory, such as `…/2023/day07`, the year and day can be read
+//! from the path rather than defaulting to today's date.
+
+use aoclib::config::Config;
+use regex::Regex;
+use std::path::Path;
+
+use crate::{settings::Settings, Error};
+
+/// Pattern matched against the current directory by default.
+///
+/// The named groups `year` and `day` are each taken from their last match.
+pub const DEFAULT_CWD_PATTERN: &str =
+    r"/(?P<year>20\d\d)(?:[/-]|$)|/(?:day|y20\d\dd)(?P<day>\d{1,2})(?:/|$)";
+
+/// The year and day implied by a directory, where they can be inferred.
+///
+/// The year and day are taken from the named groups `year` and `day` of the settings'
+/// `cwd-pattern`, matched against the directory. Failing that, the year is the one whose
+/// implementation directory contains `dir`, if any.
+pub fn infer_date(
+    config: &Config,
+    settings: &Settings,
+    dir: &Path,
+) -> Result<(Option<u32>, Option<u8>), Error> {
+    let pattern = settings
+        .cwd_pattern
+        .as_deref()
+        .unwrap_or(DEFAULT_CWD_PATTERN);
+    let pattern = Regex::new(pattern).map_err(Error::CwdPattern)?;
+    let path = dir.to_string_lossy();
+
+    let mut year = None;
+    let mut day = None;
+    for captures in pattern.captures_iter(&path) {
+        if let Some(matched) = captures.name("year") {
+            year = matched.as_str().parse().ok().or(year);
+        }
+        if let Some(matched) = captures.name("day") {
+            day = matched.as_str().parse().ok().or(day);
+        }
+    }
+
+    if year.is_none() {
+        year = config
+            .paths
+            .keys()
+            .copied()
+            .filter_map(|year| {
+                let implementation = config.implementation(year).canonicalize().ok()?;
+                dir.starts_with(&implementation)
+                    .then_some((implementation.components().count(), year))
+            })
+            .max()
+            .map(|(_, year)| year);
+    }
+
+    Ok((year, day.filter(|day| (1..=25).contains(day))))
+}
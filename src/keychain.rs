@@ -0,0 +1,71 @@
+//! Session keys kept in the platform keychain: the Secret Service, macOS Keychain, or Windows
+//! Credential Manager.
+//!
+//! Rather than the session key itself, the configuration then holds a reference to it, like
+//! `keychain:default`, which is resolved as the configuration is loaded. Keychain support requires
+//! building with the `keychain` feature.
+//!
+//! `aoclib` knows nothing of references, so the stock day template downloads a missing input by
+//! running `aoc fetch-input`, rather than with `aoclib::website::get_input`.
+
+use aoclib::config::Config;
+
+use crate::Error;
+
+/// Prefix of a session which refers to a keychain entry.
+pub const REFERENCE_PREFIX: &str = "keychain:";
+
+/// Name of the service under which entries are kept in the keychain.
+#[cfg(feature = "keychain")]
+const SERVICE: &str = "aoctool";
+
+/// The reference to a keychain entry, to be stored in place of its session key.
+pub fn reference(name: &str) -> String {
+    format!("{REFERENCE_PREFIX}{name}")
+}
+
+/// The name of the keychain entry to which a session refers, if it is a reference.
+pub fn referenced(session: &str) -> Option<&str> {
+    session.strip_prefix(REFERENCE_PREFIX)
+}
+
+/// Store a session key in the keychain.
+#[cfg(feature = "keychain")]
+pub fn store(name: &str, session: &str) -> Result<(), Error> {
+    keyring::Entry::new(SERVICE, name)
+        .and_then(|entry| entry.set_password(session))
+        .map_err(Error::Keychain)
+}
+
+/// Retrieve a session key from the keychain.
+#[cfg(feature = "keychain")]
+pub fn retrieve(name: &str) -> Result<String, Error> {
+    keyring::Entry::new(SERVICE, name)
+        .and_then(|entry| entry.get_password())
+        .map_err(Error::Keychain)
+}
+
+#[cfg(not(feature = "keychain"))]
+pub fn store(_name: &str, _session: &str) -> Result<(), Error> {
+    Err(Error::NoKeychain)
+}
+
+#[cfg(not(feature = "keychain"))]
+pub fn retrieve(_name: &str) -> Result<String, Error> {
+    Err(Error::NoKeychain)
+}
+
+/// The session key for a session: from the keychain if it is a reference, or else the session
+/// itself.
+pub fn resolve(session: &str) -> Result<String, Error> {
+    match referenced(session) {
+        Some(name) => retrieve(name),
+        None => Ok(session.to_string()),
+    }
+}
+
+/// Replace a reference in the configuration's session with the session key from the keychain.
+pub fn resolve_config(config: &mut Config) -> Result<(), Error> {
+    config.session = resolve(&config.session)?;
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! Private leaderboards, from the website's JSON API.
+
+use aoclib::config::Config;
+use serde::Deserialize;
+use std::{collections::BTreeMap, time::Duration};
+
+use crate::{answer::Part, http::HttpClient, nonblocking, Error};
+
+/// A private leaderboard, as served by the website.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Leaderboard {
+    pub owner_id: u64,
+    pub event: String,
+    /// Members by their ids.
+    pub members: BTreeMap<String, Member>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    /// Anonymous members have no name.
+    pub name: Option<String>,
+    pub local_score: u64,
+    pub stars: u64,
+    /// Unix timestamp of the member's latest star, or 0 if they have none.
+    pub last_star_ts: i64,
+    /// Stars earned, by day and then by part.
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<String, BTreeMap<String, Star>>,
+}
+
+impl Member {
+    /// The member's name, or how the website shows an anonymous member.
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    /// The parts of a day for which the member has a star.
+    pub fn day_stars(&self, day: u8) -> u8 {
+        self.completion_day_level
+            .get(&day.to_string())
+            .map_or(0, |parts| parts.len() as u8)
+    }
+
+    /// When the member earned the star of a part, as a Unix timestamp.
+    pub fn star_ts(&self, day: u8, part: Part) -> Option<i64> {
+        self.completion_day_level
+            .get(&day.to_string())?
+            .get(&part.level().to_string())
+            .map(|star| star.get_star_ts)
+    }
+
+    /// The time from the member's part 1 star to their part 2 star, for each day with both.
+    pub fn part_deltas(&self) -> Vec<(u8, Duration)> {
+        (1..=25)
+            .filter_map(|day| {
+                let delta = self.star_ts(day, Part::Two)? - self.star_ts(day, Part::One)?;
+                Some((day, Duration::from_secs(u64::try_from(delta).ok()?)))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Star {
+    /// Unix timestamp at which the star was earned.
+    pub get_star_ts: i64,
+}
+
+impl Leaderboard {
+    /// A member by id, or else the leaderboard's owner.
+    ///
+    /// Everyone has a leaderboard of their own, whose id is their user id.
+    pub fn member(&self, id: Option<u64>) -> Option<&Member> {
+        let id = id.unwrap_or(self.owner_id);
+        self.members.values().find(|member| member.id == id)
+    }
+
+    /// Members in order of rank: by local score, then by who got their latest star first.
+    pub fn ranked(&self) -> Vec<&Member> {
+        let mut members = self.members.values().collect::<Vec<_>>();
+        members.sort_by_key(|member| (std::cmp::Reverse(member.local_score), member.last_star_ts));
+        members
+    }
+}
+
+/// Download a private leaderboard's JSON.
+///
+/// The website asks that this be done at most once every 15 minutes. This blocks on
+/// [`nonblocking::leaderboard_json`].
+pub fn fetch_json(
+    http: &dyn HttpClient,
+    config: &Config,
+    year: u32,
+    id: u64,
+) -> Result<String, Error> {
+    nonblocking::block_on(nonblocking::leaderboard_json(http, config, year, id))
+}
+
+/// Download and parse a private leaderboard.
+pub fn fetch(
+    http: &dyn HttpClient,
+    config: &Config,
+    year: u32,
+    id: u64,
+) -> Result<Leaderboard, Error> {
+    parse(&fetch_json(http, config, year, id)?)
+}
+
+/// Parse a private leaderboard's JSON.
+pub fn parse(json: &str) -> Result<Leaderboard, Error> {
+    // without a valid session, the website redirects to a page of HTML
+    serde_json::from_str(json).map_err(Error::ParseLeaderboard)
+}
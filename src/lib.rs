@@ -1,153 +1,98 @@
 use clap::Args;
 use path_absolutize::Absolutize;
-use serde::Serialize;
 use std::{
-    io::{BufRead, BufReader, BufWriter, Write},
     path::{Path, PathBuf},
-    str::FromStr,
+    sync::Arc,
 };
 use thiserror::Error;
-use tinytemplate::TinyTemplate;
-use toml_edit::Document;
 
 use aoclib::config::Config;
+use settings::Settings;
+use vfs::Fs;
 
-const TEMPLATE_FILES: &[&str] = &["Cargo.toml", "src/lib.rs", "src/main.rs"];
+pub use client::{Aoc, AocBuilder};
 
-/// Get `Cargo.toml` of the implementation directory.
-///
-/// Return its path and the parsed `Document`.
-fn get_cargo_toml(config: &Config, year: u32) -> Result<(PathBuf, Document), Error> {
-    // parse the local Cargo.toml to discover if we're in the right place
-    let cargo_toml_path = config.implementation(year).join("Cargo.toml");
-    if !cargo_toml_path.exists() {
-        Err(Error::NoCargoToml)?;
-    }
-    let manifest = Document::from_str(
-        &std::fs::read_to_string(&cargo_toml_path)
-            .map_err(|err| Error::Io("reading Cargo.toml", err))?,
-    )?;
+pub mod adopt;
+pub mod answer;
+pub mod badge;
+pub mod bench;
+pub mod book;
+pub mod cargo;
+pub mod client;
+pub mod doctor;
+pub mod env;
+pub mod examples;
+pub mod export;
+pub mod git;
+pub mod github;
+pub mod hooks;
+pub mod http;
+pub mod infer;
+pub mod keychain;
+pub mod leaderboard;
+pub mod local;
+pub mod manifest;
+pub mod migrate;
+pub mod nonblocking;
+pub mod output;
+pub mod progress;
+pub mod run;
+pub mod runner;
+pub mod settings;
+pub mod submit;
+pub mod templates;
+pub mod unlock;
+pub mod utils;
+pub mod vfs;
+pub mod website;
 
-    Ok((cargo_toml_path, manifest))
+/// What aoctool reaches the outside world through.
+///
+/// The CLI uses the [real](Host::real) network and filesystem; tests and embedders can put a
+/// [mock](http::MockClient) or [memory](vfs::MemoryFs) in their place.
+#[derive(Clone)]
+pub struct Host {
+    /// The client through which every HTTP request is made.
+    pub http: Arc<dyn http::HttpClient>,
+    /// The filesystem through which scaffolding reads and writes; see [`vfs`].
+    pub fs: Arc<dyn vfs::Fs>,
 }
 
-fn add_crate_to_workspace(
-    cargo_toml_path: &Path,
-    manifest: &mut Document,
-    crate_name: &str,
-) -> Result<(), Error> {
-    use toml_edit::{Array, Item, Table, Value};
-
-    let root_table = manifest.as_table_mut();
-
-    let workspace = root_table
-        .entry("workspace")
-        .or_insert(Item::Table(Table::new()));
-    let workspace = workspace.as_table_mut().ok_or(Error::MalformedToml)?;
-
-    let members = workspace
-        .entry("members")
-        .or_insert(Item::Value(Value::Array(Array::new())));
-    let members = members
-        .as_value_mut()
-        .ok_or(Error::MalformedToml)?
-        .as_array_mut()
-        .ok_or(Error::MalformedToml)?;
-
-    if members.iter().any(|item| {
-        item.as_str()
-            .map(|item_str| item_str == crate_name)
-            .unwrap_or_default()
-    }) {
-        Err(Error::CrateAlreadyExists(crate_name.to_string()))?;
+impl Host {
+    /// The real network and filesystem.
+    pub fn real() -> Self {
+        Host {
+            http: Arc::new(http::ReqwestClient),
+            fs: Arc::new(vfs::RealFs),
+        }
     }
 
-    members.push(crate_name);
-
-    std::fs::write(cargo_toml_path, manifest.to_string())
-        .map_err(|err| Error::Io("writing updated Cargo.toml", err))?;
-    Ok(())
+    /// This host, refusing to change the filesystem outside of `roots`.
+    pub fn rooted(&self, roots: Vec<PathBuf>) -> Self {
+        Host {
+            fs: Arc::new(vfs::Rooted::new(self.fs.clone(), roots)),
+            ..self.clone()
+        }
+    }
 }
 
-/// Ensure the template directory from the configuration exists and is initialized.
-fn ensure_template_dir(config: &Config, year: u32) -> Result<PathBuf, Error> {
-    let template_dir = config.day_template(year);
-    for template in TEMPLATE_FILES {
-        let template_path = template_dir.join(template);
-        if !template_path.exists() {
-            // if we have a subdirectory of template_dir, like `crate/src/foo.rs`, this will ensure everything exists
-            if let Some(parent) = template_path.parent() {
-                std::fs::create_dir_all(parent)
-                    .map_err(|err| Error::Io("creating template parent directory", err))?;
-            }
-            let url = format!(
-                "https://raw.githubusercontent.com/coriolinus/aoctool/master/day-template/{}",
-                template
-            );
-            let client = reqwest::blocking::Client::builder()
-                .gzip(true)
-                .timeout(std::time::Duration::from_secs(5))
-                .build()
-                .map_err(Error::ClientBuilder)?;
-            let mut response = client
-                .get(&url)
-                .send()
-                .map_err(Error::RequestingInput)?
-                .error_for_status()
-                .map_err(Error::ResponseStatus)?;
-            let mut file = std::fs::OpenOptions::new()
-                .write(true)
-                .create_new(true)
-                .open(template_path)
-                .map_err(|err| Error::Io("creating template file", err))?;
-            response.copy_to(&mut file).map_err(Error::Downloading)?;
-        }
+impl Default for Host {
+    fn default() -> Self {
+        Self::real()
     }
-    Ok(template_dir)
 }
 
-fn render_templates_into(
+/// The directory of a day's solution.
+pub fn day_dir(
     config: &Config,
-    day_dir: &Path,
+    settings: &Settings,
     year: u32,
     day: u8,
-    day_name: &str,
-) -> Result<(), Error> {
-    #[derive(Serialize)]
-    struct Context {
-        year: u32,
-        day: u8,
-        package_name: String,
-    }
-
-    let context = Context {
-        year,
-        day,
-        package_name: day_name.to_string(),
-    };
-
-    // render templates
-    let template_dir = ensure_template_dir(config, year)?;
-    for template in TEMPLATE_FILES {
-        let mut tt = TinyTemplate::new();
-        let template_text = std::fs::read_to_string(template_dir.join(template))
-            .map_err(|err| Error::Io("reading template file", err))?;
-        tt.add_template(template, &template_text)
-            .map_err(|err| Error::Template(err, template.to_string()))?;
-        let rendered_text = tt
-            .render(template, &context)
-            .map_err(|err| Error::Template(err, template.to_string()))?;
-
-        let mut file = std::fs::OpenOptions::new()
-            .write(true)
-            .create_new(true)
-            .open(day_dir.join(template))
-            .map_err(|err| Error::Io("opening template destination for writing", err))?;
-        file.write_all(rendered_text.as_bytes())
-            .map_err(|err| Error::Io("writing rendered template", err))?;
-    }
-
-    Ok(())
+    day_opts: &DayOpts,
+) -> PathBuf {
+    config
+        .implementation(year)
+        .join(day_opts.day_dir(&settings.day_name(year, day)))
 }
 
 /// Initialize a new day.
@@ -159,43 +104,473 @@ fn render_templates_into(
 /// - updating the workspaces of this crate
 /// - copying in a few templates to set up the day
 /// - downloading the puzzle input
+/// - running the post-init hooks, if a day was created
+/// - committing the new day, if [requested](DayOpts::git_commit)
+///
+/// Steps which are already done are skipped: an existing day directory is reused, a crate which is
+/// already a workspace member stays one, existing files are not rendered over unless
+/// [forced](DayOpts::force), and an input which was already downloaded is kept. So `initialize` can
+/// be re-run to complete a day which was only partly initialized.
+///
+/// If any step before the hooks fails, the earlier steps are undone: a newly created day directory
+/// is removed, files [forced](DayOpts::force) over in an existing one are restored, and the
+/// workspace `Cargo.toml` is restored.
+///
+/// Days in [other languages](DayOpts::lang) are not crates: the workspace is neither checked nor
+/// updated, and no book page is added.
+#[allow(clippy::too_many_arguments)]
 pub fn initialize(
+    host: &Host,
     config: &Config,
+    settings: &Settings,
     year: u32,
     day: u8,
     skip_create_crate: bool,
     skip_get_input: bool,
+    day_opts: &DayOpts,
 ) -> Result<(), Error> {
+    let puzzle_title = if fetches_titles(settings, skip_create_crate) {
+        website::puzzle_title(host.http.as_ref(), year, day)
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+    initialize_titled(
+        host,
+        config,
+        settings,
+        year,
+        day,
+        skip_create_crate,
+        skip_get_input,
+        day_opts,
+        puzzle_title,
+    )
+}
+
+/// Initialize several days of a year, as [`initialize`] does.
+///
+/// The puzzle titles of the unlocked days are fetched first,
+/// [a few](nonblocking::MAX_CONCURRENT_REQUESTS) at a time, rather than one by one. Days which
+/// have not yet unlocked get no input. Every day is attempted, even after one fails; `report` is
+/// called with each day and its result once it is initialized, and the results are returned in
+/// the order of `days`.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_days(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    days: &[u8],
+    skip_create_crate: bool,
+    skip_get_input: bool,
+    day_opts: &DayOpts,
+    mut report: impl FnMut(u8, &Result<(), Error>),
+) -> Vec<Result<(), Error>> {
+    let unlocked = days
+        .iter()
+        .copied()
+        .filter(|&day| matches!(unlock::time_until_unlock(year, day), Ok(None)))
+        .collect::<Vec<_>>();
+    let mut titles = if fetches_titles(settings, skip_create_crate) {
+        nonblocking::block_on(nonblocking::puzzle_titles(
+            host.http.as_ref(),
+            year,
+            &unlocked,
+        ))
+    } else {
+        Default::default()
+    };
+
+    days.iter()
+        .map(|&day| {
+            let result = initialize_titled(
+                host,
+                config,
+                settings,
+                year,
+                day,
+                skip_create_crate,
+                skip_get_input || !unlocked.contains(&day),
+                day_opts,
+                titles.remove(&day),
+            );
+            report(day, &result);
+            result
+        })
+        .collect()
+}
+
+/// Whether initializing a day renders templates which may use its puzzle title.
+fn fetches_titles(settings: &Settings, skip_create_crate: bool) -> bool {
+    !skip_create_crate && !settings.local_templates
+}
+
+/// [`initialize`], with the puzzle title already fetched.
+#[allow(clippy::too_many_arguments)]
+fn initialize_titled(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    skip_create_crate: bool,
+    skip_get_input: bool,
+    day_opts: &DayOpts,
+    puzzle_title: Option<String>,
+) -> Result<(), Error> {
+    let mut rollback = Rollback::default();
+    match initialize_day(
+        host,
+        config,
+        settings,
+        year,
+        day,
+        skip_create_crate,
+        skip_get_input,
+        day_opts,
+        puzzle_title,
+        &mut rollback,
+    ) {
+        Ok(finish) => {
+            let fs = host.fs.as_ref();
+            let mut progress = progress::Progress::load(fs, config, year)?;
+            progress.record_start(day, false);
+            progress.save(fs, config, year)?;
+
+            let implementation_dir = config.implementation(year);
+            hooks::run(&implementation_dir, &finish.post_init)?;
+            if let Some((message, paths)) = finish.commit {
+                git::commit_paths(&implementation_dir, &paths, &message)?;
+            }
+            Ok(())
+        }
+        Err(err) => {
+            rollback.undo(host.fs.as_ref());
+            Err(err)
+        }
+    }
+}
+
+/// What remains to be done once a day is initialized.
+#[derive(Debug, Default)]
+struct Finish {
+    post_init: Vec<String>,
+    /// Message and paths of the commit to make, if any.
+    commit: Option<(String, Vec<PathBuf>)>,
+}
+
+/// Changes made while initializing a day, so that they can be undone if a later step fails.
+#[derive(Debug, Default)]
+struct Rollback {
+    /// A day directory which did not exist before.
+    day_dir: Option<PathBuf>,
+    /// The workspace manifest, and its original contents.
+    manifest: Option<(PathBuf, String)>,
+    /// The implementation directory, day, and day name of a book page which did not exist before.
+    book_page: Option<(PathBuf, u8, String)>,
+    /// The runner's files, and their original contents.
+    runner: Vec<(PathBuf, String)>,
+    /// Files rendered into the day directory, and their original contents, if they existed; an
+    /// existing file is only rendered over when [forced](DayOpts::force).
+    rendered: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+impl Rollback {
+    /// Undo the recorded changes, as far as possible.
+    ///
+    /// This is only called while another error is being returned, so its own errors are ignored.
+    fn undo(self, fs: &dyn Fs) {
+        // in reverse, so that a file written twice gets its first original back
+        for (path, original) in self.rendered.into_iter().rev() {
+            let _ = match original {
+                Some(original) => fs.write(&path, &original),
+                None => fs.remove_file(&path),
+            };
+        }
+        for (path, original) in self.runner {
+            let _ = fs.write(&path, original.as_bytes());
+        }
+        if let Some((implementation_dir, day, day_name)) = self.book_page {
+            let _ = book::remove_day_page(fs, &implementation_dir, day, &day_name);
+        }
+        if let Some((cargo_toml_path, original)) = self.manifest {
+            let _ = fs.write(&cargo_toml_path, original.as_bytes());
+        }
+        if let Some(day_dir) = self.day_dir {
+            let _ = fs.remove_dir_all(&day_dir);
+        }
+    }
+}
+
+/// The steps of [`initialize`] which are undone on failure.
+#[allow(clippy::too_many_arguments)]
+fn initialize_day(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    skip_create_crate: bool,
+    skip_get_input: bool,
+    day_opts: &DayOpts,
+    puzzle_title: Option<String>,
+    rollback: &mut Rollback,
+) -> Result<Finish, Error> {
+    let fs = host.fs.as_ref();
     let implementation_dir = config.implementation(year);
-    let (cargo_toml_path, mut manifest) = get_cargo_toml(config, year)?;
+    let workspace = if day_opts.language().is_none() {
+        Some(manifest::get_cargo_toml(fs, config, year)?)
+    } else {
+        None
+    };
+    let mut finish = Finish::default();
 
     if !skip_create_crate {
         // set up new sub-crate basics
-        let day_name = format!("day{:02}", day);
-        let day_dir = implementation_dir.join(&day_name);
-        std::fs::create_dir_all(day_dir.join("src"))
+        let day_name = settings.day_name(year, day);
+        let day_dir = day_dir(config, settings, year, day, day_opts);
+        if !fs.exists(&day_dir) {
+            rollback.day_dir = Some(day_dir.clone());
+        }
+        fs.create_dir_all(&day_dir)
             .map_err(|err| Error::Io("creating day dir", err))?;
 
-        // update the workspaces of this crate
-        add_crate_to_workspace(&cargo_toml_path, &mut manifest, &day_name)?;
+        if let Some((cargo_toml_path, mut manifest)) = workspace {
+            fs.create_dir_all(&day_dir.join("src"))
+                .map_err(|err| Error::Io("creating day src dir", err))?;
+
+            // update the workspaces of this crate
+            rollback.manifest = Some((cargo_toml_path.clone(), manifest.to_string()));
+            match manifest::add_crate_to_workspace(fs, &cargo_toml_path, &mut manifest, &day_name) {
+                Err(Error::CrateAlreadyExists(_)) => {}
+                result => result?,
+            }
+        }
 
         // render templates, creating new sub-crate
-        render_templates_into(config, &day_dir, year, day, &day_name)?;
+        let mut context =
+            templates::Context::new(host, config, settings, day_opts, year, day, &day_name)?;
+        context.puzzle_title = puzzle_title.unwrap_or_default();
+        templates::render_templates_recorded(
+            host,
+            config,
+            settings,
+            day_opts,
+            &context,
+            &day_dir,
+            year,
+            &mut rollback.rendered,
+        )?;
+
+        // add a writeup page if the year has a book
+        if day_opts.language().is_none() {
+            let page = implementation_dir
+                .join(book::BOOK_DIR)
+                .join("src")
+                .join(format!("{day_name}.md"));
+            if !fs.exists(&page) {
+                rollback.book_page = Some((implementation_dir.clone(), day, day_name.clone()));
+            }
+            book::add_day_page(fs, &implementation_dir, year, day, &day_name)?;
+        }
+
+        // register the day with the runner, which needs its library
+        if day_opts.language().is_none() && !day_opts.bin_only {
+            for path in runner::files(fs, &implementation_dir) {
+                let original = fs
+                    .read_to_string(&path)
+                    .map_err(|err| Error::Io("reading runner file", err))?;
+                rollback.runner.push((path, original));
+            }
+            runner::add_day(fs, &implementation_dir, year, day, &day_name)?;
+        }
+
+        finish.post_init = hooks::post_init_commands(settings, &context, &day_dir)?;
+        if let Some(message) = init_commit_message(settings, day_opts, &context)? {
+            let paths = vec![
+                day_dir,
+                implementation_dir.join("Cargo.toml"),
+                implementation_dir.join(".gitignore"),
+                implementation_dir.join(book::BOOK_DIR).join("src"),
+                implementation_dir.join(runner::RUNNER_DIR),
+            ];
+            finish.commit = Some((message, paths));
+        }
     }
 
     if !skip_get_input {
         // download the input
-        aoclib::website::get_input(config, year, day)?;
+        website::get_input(host, config, year, day)?;
+    }
+
+    Ok(finish)
+}
+
+/// The message of the commit to make after initializing a day, if one is requested.
+fn init_commit_message(
+    settings: &Settings,
+    day_opts: &DayOpts,
+    context: &templates::Context,
+) -> Result<Option<String>, Error> {
+    if !(day_opts.git_commit || settings.git.commit_on_init) {
+        return Ok(None);
+    }
+    let message = settings
+        .git
+        .init_commit_message
+        .as_deref()
+        .unwrap_or(settings::DEFAULT_INIT_COMMIT_MESSAGE);
+    templates::render_str(templates::Engine::TinyTemplate, message, message, context).map(Some)
+}
+
+/// Remove a day, undoing [`initialize`].
+///
+/// This entails:
+///
+/// - deleting the day's directory
+/// - removing it from the workspace members, unless it is in [another language](DayOpts::lang)
+/// - removing its page from the book, if there is one
+/// - removing it from the runner, if there is one
+/// - optionally, deleting its input file
+pub fn remove_day(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    remove_input: bool,
+    day_opts: &DayOpts,
+) -> Result<(), Error> {
+    let fs = host.fs.as_ref();
+    let implementation_dir = config.implementation(year);
+    let day_name = settings.day_name(year, day);
+    let day_dir = day_dir(config, settings, year, day, day_opts);
+
+    if day_opts.language().is_none() {
+        let (cargo_toml_path, mut manifest) = manifest::get_cargo_toml(fs, config, year)?;
+        if manifest::remove_crate_from_manifest(&mut manifest, &day_name)? {
+            manifest::write(fs, &cargo_toml_path, &manifest)?;
+        }
+        book::remove_day_page(fs, &implementation_dir, day, &day_name)?;
+        runner::remove_day(fs, &implementation_dir, year, day, &day_name)?;
+    }
+
+    if fs.exists(&day_dir) {
+        fs.remove_dir_all(&day_dir)
+            .map_err(|err| Error::Io("removing day dir", err))?;
+    }
+
+    if remove_input {
+        let input = config.input_for(year, day);
+        if fs.exists(&input) {
+            fs.remove_file(&input)
+                .map_err(|err| Error::Io("removing input file", err))?;
+        }
     }
 
     Ok(())
 }
 
+/// What `initialize` would do for a day.
+#[derive(Debug)]
+pub struct InitPreview {
+    pub day_dir: PathBuf,
+    /// Rendered templates, relative to `day_dir`.
+    pub files: Vec<templates::RenderedFile>,
+    /// Existing files which would be left alone, rather than rendered over.
+    pub existing: Vec<PathBuf>,
+    /// Stock templates which would be downloaded before rendering; they are not included in
+    /// `files`.
+    pub missing_templates: Vec<PathBuf>,
+    /// Unified diff of the workspace `Cargo.toml`.
+    ///
+    /// Empty for days in other languages, which do not join the workspace.
+    pub manifest_diff: String,
+    /// Where the input would be downloaded, if it is not already present.
+    pub input: Option<PathBuf>,
+    /// Post-init hooks which would run.
+    pub hooks: Vec<String>,
+    /// Message of the commit which would be made.
+    pub commit_message: Option<String>,
+}
+
+/// Preview the effects of `initialize`, without writing anything.
+///
+/// The puzzle title is not fetched, so templates render without it.
+#[allow(clippy::too_many_arguments)]
+pub fn preview_initialize(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    skip_create_crate: bool,
+    skip_get_input: bool,
+    day_opts: &DayOpts,
+) -> Result<InitPreview, Error> {
+    let fs = host.fs.as_ref();
+    let mut workspace = if day_opts.language().is_none() {
+        Some(manifest::get_cargo_toml(fs, config, year)?)
+    } else {
+        None
+    };
+    let day_name = settings.day_name(year, day);
+    let day_dir = day_dir(config, settings, year, day, day_opts);
+
+    let mut preview = InitPreview {
+        day_dir,
+        files: Vec::new(),
+        existing: Vec::new(),
+        missing_templates: Vec::new(),
+        manifest_diff: String::new(),
+        input: None,
+        hooks: Vec::new(),
+        commit_message: None,
+    };
+
+    if !skip_create_crate {
+        if let Some((_, manifest)) = &mut workspace {
+            let before = manifest.to_string();
+            match manifest::add_crate_to_manifest(manifest, &day_name) {
+                Err(Error::CrateAlreadyExists(_)) => {}
+                result => result?,
+            }
+            preview.manifest_diff = similar::TextDiff::from_lines(&before, &manifest.to_string())
+                .unified_diff()
+                .header("Cargo.toml", "Cargo.toml")
+                .to_string();
+        }
+        preview.missing_templates =
+            templates::missing_day_templates(fs, config, settings, day_opts, year);
+        let context =
+            templates::Context::new(host, config, settings, day_opts, year, day, &day_name)?;
+        let files = templates::preview_templates(fs, config, settings, day_opts, &context, year)?;
+        let (existing, files) = files.into_iter().partition::<Vec<_>, _>(|file| {
+            !day_opts.force && fs.exists(&day_dir.join(&file.destination))
+        });
+        preview.files = files;
+        preview.existing = existing.into_iter().map(|file| file.destination).collect();
+        preview.hooks = hooks::post_init_commands(settings, &context, &preview.day_dir)?;
+        preview.commit_message = init_commit_message(settings, day_opts, &context)?;
+    }
+
+    if !skip_get_input {
+        let input = config.input_for(year, day);
+        if !fs.exists(&input) {
+            preview.input = Some(input);
+        }
+    }
+
+    Ok(preview)
+}
+
 /// Check the path provided for the specified line.
 ///
 /// If the path specified does not exist, or does not contain that line, the line is appended.
 /// A newline is added to the input line.
-fn append_if_not_present<P, L>(path: P, line: L) -> Result<(), Error>
+fn append_if_not_present<P, L>(fs: &dyn Fs, path: P, line: L) -> Result<(), Error>
 where
     P: AsRef<Path>,
     L: AsRef<[u8]>,
@@ -205,36 +580,70 @@ where
     let mut line = line.as_ref().to_vec();
     line.push(b'\n');
 
-    let contains_line = {
-        std::fs::File::open(&path)
-            .map(|file| {
-                let mut reader = BufReader::new(file);
-                let mut line_buffer = Vec::new();
-                while let Ok(read_bytes) = reader.read_until(b'\n', &mut line_buffer) {
-                    if read_bytes == 0 {
-                        break;
-                    }
-                    if line_buffer == line {
-                        return true;
-                    }
-                    line_buffer.clear();
-                }
-                false
-            })
-            .unwrap_or_default()
-    };
+    let contains_line = fs
+        .read(path.as_ref())
+        .map(|contents| {
+            contents
+                .split_inclusive(|&byte| byte == b'\n')
+                .any(|existing| existing == line)
+        })
+        .unwrap_or_default();
     if !contains_line {
-        let mut file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)
-            .map_err(|err| Error::Io("opening for append", err))?;
-        file.write_all(&line)
+        fs.append(path.as_ref(), &line)
             .map_err(|err| Error::Io("appending", err))?;
     }
     Ok(())
 }
 
+/// Replace the text between a pair of markers, `<!-- {name}:start -->` and
+/// `<!-- {name}:end -->`, keeping the markers.
+///
+/// Returns `None` if the document lacks either marker.
+pub(crate) fn replace_marked(document: &str, name: &str, replacement: &str) -> Option<String> {
+    let start_marker = format!("<!-- {name}:start -->");
+    let end_marker = format!("<!-- {name}:end -->");
+    let start = document.find(&start_marker)? + start_marker.len();
+    let end = start + document[start..].find(&end_marker)?;
+    Some(format!(
+        "{}\n{replacement}{}",
+        &document[..start],
+        &document[end..]
+    ))
+}
+
+/// Update the text between a pair of markers in a Markdown file, as with [`replace_marked`].
+///
+/// If the file lacks the markers, a section with a heading is appended, containing them.
+pub(crate) fn update_marked(
+    fs: &dyn Fs,
+    path: &Path,
+    name: &str,
+    heading: &str,
+    replacement: &str,
+) -> Result<(), Error> {
+    let document = if fs.exists(path) {
+        fs.read_to_string(path)
+            .map_err(|err| Error::Io("reading markdown", err))?
+    } else {
+        String::new()
+    };
+    let document = replace_marked(&document, name, replacement).unwrap_or_else(|| {
+        let separator = if document.is_empty() || document.ends_with("\n\n") {
+            ""
+        } else if document.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+        format!(
+            "{document}{separator}## {heading}\n\n<!-- {name}:start -->\n{replacement}<!-- {name}:end -->\n"
+        )
+    });
+    tracing::info!(path = %path.display(), "writing markdown");
+    fs.write(path, document.as_bytes())
+        .map_err(|err| Error::Io("writing markdown", err))
+}
+
 /// Initialize a new year.
 ///
 /// This entails:
@@ -243,7 +652,50 @@ where
 /// - If implementation directory does not exist, or is empty, create a rust workspace there.
 /// - Ensure the inputs directory is present in `"$implementation/.gitignore"` if it is a
 ///   subdirectory of the implementation.
-pub fn initialize_year(config: &mut Config, year: u32, path_opts: PathOpts) -> Result<(), Error> {
+/// - Declare the stock day template's dependencies in `[workspace.dependencies]`, and the
+///   crates' metadata in `[workspace.package]`, where they are not already.
+/// - If requested, replace the day crates listed as workspace members with a glob matching them.
+/// - Render the workspace's `README.md`, and a license if requested, unless they exist.
+/// - Pin the workspace's toolchain in `rust-toolchain.toml`, if a channel is configured and the
+///   file does not exist.
+/// - Scaffold any optional extras requested in `year_opts`.
+/// - If requested, and the implementation is not already in a git repository, initialize one and
+///   commit the workspace skeleton.
+/// - If requested, create a GitHub repository, and push to it.
+///
+/// With [`YearOpts::shared_workspace`], the year's inputs default to their own subdirectory of the
+/// workspace's inputs, and its day crates are named for the year as well as the day.
+pub fn initialize_year(
+    host: &Host,
+    config: &mut Config,
+    settings: &mut Settings,
+    year: u32,
+    mut path_opts: PathOpts,
+    year_opts: YearOpts,
+) -> Result<(), Error> {
+    let fs = host.fs.as_ref();
+    if year_opts.shared_workspace {
+        let implementation = match path_opts.implementation.take() {
+            Some(implementation) => implementation,
+            None => std::env::current_dir()
+                .map_err(|err| Error::Io("determining current directory", err))?,
+        };
+        path_opts
+            .input_files
+            .get_or_insert_with(|| implementation.join("inputs").join(year.to_string()));
+        path_opts.implementation = Some(implementation);
+
+        let global_has_year = settings
+            .day_name
+            .as_deref()
+            .map(|pattern| pattern.contains("{year"))
+            .unwrap_or_default();
+        let year_settings = settings.years.entry(year).or_default();
+        if year_settings.day_name.is_none() && !global_has_year {
+            year_settings.day_name = Some(settings::SHARED_WORKSPACE_DAY_NAME.to_string());
+        }
+    }
+
     {
         // ensure all specified paths exist and are configured appropriately.
         let ensure_path = |maybe_path: Option<PathBuf>,
@@ -253,13 +705,12 @@ pub fn initialize_year(config: &mut Config, year: u32, path_opts: PathOpts) -> R
                 (Some(desired_path), None) => {
                     // if we have a desired path and no appropriate path has already been configured,
                     // then:
-                    if !desired_path.exists() {
-                        std::fs::create_dir_all(desired_path)
+                    if !fs.exists(desired_path) {
+                        fs.create_dir_all(desired_path)
                             .map_err(|err| Error::Io("ensuring path dir", err))?;
                     }
                     *path_destination = Some(
-                        desired_path
-                            .canonicalize()
+                        fs.canonicalize(desired_path)
                             .map_err(|err| Error::Io("canonicalizing path destination", err))?,
                     );
                 }
@@ -294,27 +745,24 @@ pub fn initialize_year(config: &mut Config, year: u32, path_opts: PathOpts) -> R
     // Create a new Rust project as required.
     // "Required" means that the target either does not exist, or is an empty directory.
     // This creates `Cargo.toml` and `.gitignore`.
-    if !impl_path.exists()
-        || (impl_path.is_dir()
-            && std::fs::read_dir(&impl_path)
-                .map(|mut dir_iter| dir_iter.next().is_none())
+    if !fs.exists(&impl_path)
+        || (fs.is_dir(&impl_path)
+            && fs
+                .read_dir(&impl_path)
+                .map(|entries| entries.is_empty())
                 .unwrap_or_default())
     {
-        std::fs::create_dir_all(&impl_path)
+        fs.create_dir_all(&impl_path)
             .map_err(|err| Error::Io("creating implementation dir", err))?;
 
         // Create default .gitignore
-        append_if_not_present(impl_path.join(".gitignore"), "/target/")?;
+        append_if_not_present(fs, impl_path.join(".gitignore"), "/target/")?;
 
         // create default `Cargo.toml` if not present.
         // Becuase `Cargo.toml` has more complicated semantics, we can't just append.
-        if let Ok(file) = std::fs::OpenOptions::new()
-            .create_new(true)
-            .write(true)
-            .open(impl_path.join("Cargo.toml"))
-        {
-            let mut buffer = BufWriter::new(file);
-            writeln!(&mut buffer, "[workspace]\nmembers = []")
+        let cargo_toml_path = impl_path.join("Cargo.toml");
+        if !fs.exists(&cargo_toml_path) {
+            fs.write(&cargo_toml_path, b"[workspace]\nmembers = []\n")
                 .map_err(|err| Error::Io("writing default Cargo.toml", err))?;
         }
     }
@@ -332,19 +780,170 @@ pub fn initialize_year(config: &mut Config, year: u32, path_opts: PathOpts) -> R
             // add a trailing slash to narrow the gitignore rule to directories
             let mut input_files_relative = input_files_relative.as_os_str().as_bytes().to_vec();
             input_files_relative.push(b'/');
-            append_if_not_present(impl_path.join(".gitignore"), input_files_relative)?;
+            append_if_not_present(fs, impl_path.join(".gitignore"), input_files_relative)?;
+        }
+    }
+
+    if fs.exists(&impl_path.join("Cargo.toml")) {
+        let (cargo_toml_path, mut manifest) = manifest::get_cargo_toml(fs, config, year)?;
+        let dependencies_added = manifest::add_workspace_dependencies(&mut manifest)?;
+        let package_added = manifest::add_workspace_package(&mut manifest, year_opts.license)?;
+        let members_globbed = year_opts.glob_members
+            && manifest::glob_members(
+                &mut manifest,
+                &settings::day_name_glob(settings.day_name_pattern(year)),
+            )?;
+        if dependencies_added || package_added || members_globbed {
+            manifest::write(fs, &cargo_toml_path, &manifest)?;
+        }
+    }
+
+    templates::render_year_files(fs, config, settings, year, year_opts.license)?;
+
+    if let Some(channel) = year_opts.toolchain.as_ref().or(settings.toolchain.as_ref()) {
+        write_toolchain_file(fs, &impl_path, channel)?;
+    }
+
+    if year_opts.with_book {
+        book::scaffold_book(fs, &impl_path, year)?;
+    }
+
+    if year_opts.with_utils {
+        utils::scaffold_utils(fs, settings, &impl_path)?;
+        let (cargo_toml_path, mut manifest) = manifest::get_cargo_toml(fs, config, year)?;
+        match manifest::add_crate_to_workspace(
+            fs,
+            &cargo_toml_path,
+            &mut manifest,
+            utils::UTILS_DIR,
+        ) {
+            Ok(()) | Err(Error::CrateAlreadyExists(_)) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    if year_opts.with_runner {
+        runner::scaffold_runner(fs, &impl_path)?;
+        let (cargo_toml_path, mut manifest) = manifest::get_cargo_toml(fs, config, year)?;
+        match manifest::add_crate_to_workspace(
+            fs,
+            &cargo_toml_path,
+            &mut manifest,
+            runner::RUNNER_DIR,
+        ) {
+            Ok(()) | Err(Error::CrateAlreadyExists(_)) => {}
+            Err(err) => return Err(err),
         }
     }
 
+    let git_init = year_opts.git_init || year_opts.create_github_repo.is_some();
+    if git_init && !git::git_succeeds(&impl_path, ["rev-parse", "--git-dir"]) {
+        git::git(&impl_path, ["init", "--quiet"])?;
+        let paths = [
+            impl_path.join("Cargo.toml"),
+            impl_path.join(".gitignore"),
+            impl_path.join("README.md"),
+            impl_path.join("LICENSE"),
+            impl_path.join("rust-toolchain.toml"),
+            impl_path.join(book::BOOK_DIR),
+            impl_path.join(utils::UTILS_DIR),
+            impl_path.join(runner::RUNNER_DIR),
+        ];
+        git::commit_paths(
+            &impl_path,
+            &paths,
+            &format!("initialize workspace for {year}"),
+        )?;
+    }
+
+    if let Some(name) = &year_opts.create_github_repo {
+        let repository = github::create_repository(
+            host.http.as_ref(),
+            settings,
+            name,
+            year_opts.github_private,
+        )?;
+        git::git(
+            &impl_path,
+            ["remote", "add", "origin", repository.clone_url.as_str()],
+        )?;
+        git::git(
+            &impl_path,
+            ["push", "--quiet", "--set-upstream", "origin", "HEAD"],
+        )?;
+    }
+
     Ok(())
 }
 
-/// Clear the templates directory.
+/// Write a `rust-toolchain.toml` pinning the toolchain channel, unless one exists.
+fn write_toolchain_file(fs: &dyn Fs, impl_path: &Path, channel: &str) -> Result<(), Error> {
+    let path = impl_path.join("rust-toolchain.toml");
+    if fs.exists(&path) {
+        return Ok(());
+    }
+    let channel = toml::Value::String(channel.to_string());
+    fs.write(
+        &path,
+        format!("[toolchain]\nchannel = {channel}\n").as_bytes(),
+    )
+    .map_err(|err| Error::Io("writing rust-toolchain.toml", err))
+}
+
+/// Clear the templates directory, or only the files of `only`, relative to it.
 ///
-/// This can be useful when the templates have been updated.
-pub fn clear_templates(config: &Config, year: u32) -> Result<(), Error> {
-    std::fs::remove_dir_all(config.day_template(year))
-        .map_err(|err| Error::Io("attempting to clear templates", err))
+/// This can be useful when the templates have been updated. The directory is backed up first,
+/// into a new directory within the system's temporary directory, which is returned.
+pub fn clear_templates(
+    host: &Host,
+    config: &Config,
+    year: u32,
+    only: &[PathBuf],
+) -> Result<PathBuf, Error> {
+    let fs = host.fs.as_ref();
+    let template_dir = config.day_template(year);
+    if let Some(missing) = only
+        .iter()
+        .find(|path| !fs.is_file(&template_dir.join(path)))
+    {
+        return Err(Error::NoSuchTemplate(missing.display().to_string()));
+    }
+
+    let backup = std::env::temp_dir().join(format!(
+        "aoctool-templates-{year}-{}",
+        time::OffsetDateTime::now_utc().unix_timestamp()
+    ));
+    copy_dir(fs, &template_dir, &backup)?;
+
+    if only.is_empty() {
+        fs.remove_dir_all(&template_dir)
+            .map_err(|err| Error::Io("attempting to clear templates", err))?;
+    } else {
+        for path in only {
+            fs.remove_file(&template_dir.join(path))
+                .map_err(|err| Error::Io("attempting to clear template", err))?;
+        }
+    }
+    Ok(backup)
+}
+
+/// Recursively copy a directory.
+fn copy_dir(fs: &dyn Fs, source: &Path, destination: &Path) -> Result<(), Error> {
+    fs.create_dir_all(destination)
+        .map_err(|err| Error::Io("creating templates backup", err))?;
+    let entries = fs
+        .read_dir(source)
+        .map_err(|err| Error::Io("reading templates to back up", err))?;
+    for path in entries {
+        let destination = destination.join(path.file_name().unwrap_or_default());
+        if fs.is_dir(&path) {
+            copy_dir(fs, &path, &destination)?;
+        } else {
+            fs.copy(&path, &destination)
+                .map_err(|err| Error::Io("backing up template", err))?;
+        }
+    }
+    Ok(())
 }
 
 #[derive(Debug, Error)]
@@ -365,19 +964,114 @@ pub enum Error {
     GetInput(#[from] aoclib::website::Error),
     #[error("crate already exists in workspace: {0}")]
     CrateAlreadyExists(String),
-    #[error("building request client for day template download")]
+    #[error("building HTTP client")]
     ClientBuilder(#[source] reqwest::Error),
-    #[error("requesting day template file")]
-    RequestingInput(#[source] reqwest::Error),
-    #[error("response status unsuccessful requesting day template")]
-    ResponseStatus(#[source] reqwest::Error),
-    #[error("downloading day template to local file")]
-    Downloading(#[source] reqwest::Error),
     #[error("CLI requested '{0}' but config file specified '{1}'")]
     ConfigCliConflict(String, String),
+    #[error("could not parse aoctool settings")]
+    ParseSettings(#[source] toml::de::Error),
+    #[error("could not serialize aoctool settings")]
+    SerializeSettings(#[source] toml::ser::Error),
+    #[error("no session configured for account: {0}")]
+    UnknownAccount(String),
+    #[error("invalid answer extraction pattern")]
+    AnswerPattern(#[source] regex::Error),
+    #[error("template error for {1}")]
+    TeraTemplate(#[source] tera::Error, String),
+    #[error("could not parse template manifest at {1}")]
+    ParseTemplateManifest(#[source] toml::de::Error, String),
+    #[error("templates for this year come from a git repository; update them with git")]
+    TemplatesFromGit,
+    #[error("git command failed: {0}")]
+    GitFailed(String),
+    #[error("invalid puzzle date")]
+    InvalidDate(#[source] time::error::ComponentRange),
+    #[error("could not parse example answers at {1}")]
+    ParseExampleAnswers(#[source] toml::de::Error, String),
+    #[error("refusing to submit: answer was computed from example {0}")]
+    SubmitExampleInput(usize),
+    #[error("refusing to submit: answer was computed from {0}, not the real input")]
+    SubmitOtherInput(String),
+    #[error("refusing to submit: answer matches the expected answer of example {0} for {1}")]
+    SubmitExampleAnswer(usize, answer::Part),
+    #[error("no templates for language '{0}'; expected them in {1}")]
+    NoLanguageTemplates(String, String),
+    #[error("hook failed: {0}")]
+    HookFailed(String),
+    #[error("could not parse progress file at {1}")]
+    ParseProgress(#[source] toml::de::Error, String),
+    #[error("could not serialize progress")]
+    SerializeProgress(#[source] toml::ser::Error),
+    #[error("invalid cwd-pattern")]
+    CwdPattern(#[source] regex::Error),
+    #[error("no GitHub token; set github.token in the settings or GITHUB_TOKEN")]
+    NoGitHubToken,
+    #[error("GitHub: {0}")]
+    GitHub(String),
+    #[error("not in a cargo workspace: {0}")]
+    NoCargoWorkspace(String),
+    #[error("{0} has not been initialized")]
+    DayNotInitialized(String),
+    #[error("{0} is written in {1}, and only Rust days can be built and run; run it yourself, and submit its answers with `aoc submit --part <part> <answer>`")]
+    NotRust(String, String),
+    #[error("{0} failed")]
+    SolutionFailed(String),
+    #[error("{0} failed to build")]
+    BuildFailed(String),
+    #[error("day {0} has no example {1}")]
+    NoSuchExample(u8, usize),
+    #[error("the solution reported no answer for {0}")]
+    NoAnswer(answer::Part),
+    #[error("profiling {0} failed; is cargo-flamegraph installed? (cargo install flamegraph)")]
+    ProfileFailed(String),
+    #[error("{0} has no benchmarks; initialize it with --with-bench")]
+    NoBenchmarks(String),
+    #[error("benchmarking {0} failed")]
+    BenchFailed(String),
+    #[error("could not parse benchmark report at {1}")]
+    ParseBenchmarks(#[source] toml::de::Error, String),
+    #[error("could not serialize benchmark report")]
+    SerializeBenchmarks(#[source] toml::ser::Error),
+    #[error("could not parse leaderboard; is the session key valid, and the leaderboard yours?")]
+    ParseLeaderboard(#[source] serde_json::Error),
+    #[error("not logged in to the website; is the session key valid?")]
+    NotLoggedIn,
+    #[error("refusing to submit: day {0} {1} is already solved")]
+    AlreadySolved(u8, answer::Part),
+    #[error("could not serialize export")]
+    SerializeExport(#[source] serde_json::Error),
+    #[error("no such profile: {0}")]
+    UnknownProfile(String),
+    #[error("day name {0:?} has an unknown placeholder {1}; use {{year}} or {{day}}, optionally zero-padded as in {{day:02}}")]
+    UnknownPlaceholder(String, String),
+    #[error("invalid value of {0}: {1:?}")]
+    InvalidEnvVar(&'static str, String),
+    #[error("could not parse project-local config at {1}")]
+    ParseLocalConfig(#[source] toml::de::Error, String),
+    #[error("settings file has layout version {0}, but this aoctool only understands up to {1}; please upgrade aoctool")]
+    SettingsTooNew(i64, i64),
+    #[error("the website's response had no valid Date header")]
+    NoServerTime,
+    #[cfg(feature = "keychain")]
+    #[error("could not access the keychain")]
+    Keychain(#[source] keyring::Error),
+    #[error(
+        "this aoctool was built without keychain support; rebuild it with `--features keychain`"
+    )]
+    NoKeychain,
+    #[error("could not serialize output")]
+    SerializeOutput(#[source] serde_json::Error),
+    #[error("no template {0} in the day templates")]
+    NoSuchTemplate(String),
+    #[error("could not save the configuration: {0}")]
+    SaveConfig(String),
+    #[error("requesting {0}")]
+    Http(String, #[source] reqwest::Error),
+    #[error("response status {0} requesting {1}")]
+    HttpStatus(u16, String),
 }
 
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Default)]
 pub struct PathOpts {
     /// Path to input files. Default: "$(pwd)/inputs"
     #[arg(long)]
@@ -391,3 +1085,117 @@ pub struct PathOpts {
     #[arg(long)]
     pub day_templates: Option<PathBuf>,
 }
+
+#[derive(Args, Debug, Default)]
+pub struct DayOpts {
+    /// Scaffold a criterion benchmark in "benches/bench.rs".
+    #[arg(long)]
+    pub with_bench: bool,
+
+    /// Scaffold a single "src/main.rs", without splitting the solution into a library.
+    #[arg(long, conflicts_with = "with_bench")]
+    pub bin_only: bool,
+
+    /// Scaffold the day in a language other than Rust, e.g. "python".
+    ///
+    /// The day is created in "$implementation/$lang/dayNN" from the language's own templates,
+    /// and is not added to the Cargo workspace.
+    #[arg(long)]
+    pub lang: Option<String>,
+
+    /// Overwrite rendered files which already exist in the day's directory.
+    ///
+    /// Without this, existing files are left alone.
+    #[arg(long)]
+    pub force: bool,
+
+    /// With `--force`, keep a copy of each overwritten file which differs, with ".bak" appended.
+    #[arg(long, requires = "force")]
+    pub backup: bool,
+
+    /// Commit the new day, if the implementation directory is in a git repository.
+    ///
+    /// The day's directory and the workspace files changed by `init` are committed.
+    #[arg(long)]
+    pub git_commit: bool,
+}
+
+impl DayOpts {
+    /// The requested language, if it is not Rust.
+    pub fn language(&self) -> Option<&str> {
+        self.lang
+            .as_deref()
+            .filter(|lang| !lang.eq_ignore_ascii_case("rust"))
+    }
+
+    /// The directory of a day, relative to the implementation directory.
+    pub fn day_dir(&self, day_name: &str) -> PathBuf {
+        match self.language() {
+            Some(lang) => Path::new(lang).join(day_name),
+            None => PathBuf::from(day_name),
+        }
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct YearOpts {
+    /// Scaffold an mdBook in "$implementation/book" for the year's writeups.
+    ///
+    /// Each day initialized afterwards gets a page including its solution.
+    #[arg(long)]
+    pub with_book: bool,
+
+    /// Create a "$implementation/utils" crate of helpers shared between the days.
+    ///
+    /// Each day initialized afterwards depends on it.
+    #[arg(long)]
+    pub with_utils: bool,
+
+    /// Create a "$implementation/runner" crate which can run any day, as
+    /// `cargo run -p runner -- 7 --part2`.
+    ///
+    /// Each day initialized afterwards is registered with it.
+    #[arg(long)]
+    pub with_runner: bool,
+
+    /// List day crates as workspace members with a single glob, like "day*", rather than by name.
+    ///
+    /// Day crates which are already listed by name are replaced by the glob.
+    #[arg(long)]
+    pub glob_members: bool,
+
+    /// Add a license file to the workspace.
+    #[arg(long, value_enum)]
+    pub license: Option<templates::License>,
+
+    /// Pin the workspace to this toolchain channel in "rust-toolchain.toml", like "1.74" or
+    /// "stable".
+    ///
+    /// Defaults to `toolchain` in the aoctool settings. When neither is set, no file is written.
+    #[arg(long, value_name = "CHANNEL")]
+    pub toolchain: Option<String>,
+
+    /// Share one workspace between every year, rather than one workspace per year.
+    ///
+    /// The implementation directory defaults to "$(pwd)" as usual, but the inputs default to
+    /// "$implementation/inputs/$year", and day crates are named like "y2023d05".
+    #[arg(long)]
+    pub shared_workspace: bool,
+
+    /// Initialize a git repository in the implementation directory, and commit the workspace.
+    ///
+    /// Nothing is done if the implementation directory is already in a git repository.
+    #[arg(long)]
+    pub git_init: bool,
+
+    /// Create a GitHub repository with this name, set it as "origin", and push the workspace.
+    ///
+    /// Implies `--git-init`. Requires a GitHub token, as `github.token` in the aoctool settings
+    /// or the environment variable `GITHUB_TOKEN`.
+    #[arg(long, value_name = "NAME")]
+    pub create_github_repo: Option<String>,
+
+    /// Make the GitHub repository private.
+    #[arg(long, requires = "create_github_repo")]
+    pub github_private: bool,
+}
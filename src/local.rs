@@ -0,0 +1,83 @@
+//! Project-local configuration, committed alongside a shared solutions repository.
+//!
+//! A `.aoctool.toml` in the current directory or any of its ancestors sets per-year paths over
+//! those of the user's configuration, so that everyone sharing the repository finds it the same
+//! way, while each keeps their own session key:
+//!
+//! ```toml
+//! [years.2024]
+//! implementation = "."
+//! input-files = "inputs"
+//! ```
+//!
+//! Relative paths are relative to the directory containing the file.
+
+use aoclib::config::Config;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{settings::PathSettings, Error};
+
+/// Name of the project-local configuration file.
+pub const LOCAL_CONFIG_FILE: &str = ".aoctool.toml";
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct LocalConfig {
+    /// Where the file was found.
+    #[serde(skip)]
+    pub path: PathBuf,
+
+    /// Per-year paths.
+    pub years: BTreeMap<u32, PathSettings>,
+}
+
+/// The nearest project-local configuration file, in `dir` or any of its ancestors.
+pub fn find(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(LOCAL_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+impl LocalConfig {
+    /// Load a project-local configuration file, resolving its relative paths.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|err| Error::Io("reading project-local config", err))?;
+        let mut local: Self = toml::from_str(&data)
+            .map_err(|err| Error::ParseLocalConfig(err, path.display().to_string()))?;
+        local.path = path.to_owned();
+
+        let root = path.parent().unwrap_or(Path::new("."));
+        let resolve = |path: &mut Option<PathBuf>| {
+            if let Some(path) = path {
+                if path.is_relative() {
+                    *path = root.join(&*path);
+                }
+            }
+        };
+        for paths in local.years.values_mut() {
+            resolve(&mut paths.implementation);
+            resolve(&mut paths.input_files);
+            resolve(&mut paths.day_template);
+        }
+        Ok(local)
+    }
+
+    /// Load the project-local configuration governing the current directory, if any.
+    pub fn discover() -> Result<Option<Self>, Error> {
+        let dir =
+            std::env::current_dir().map_err(|err| Error::Io("finding current directory", err))?;
+        find(&dir).map(|path| Self::load(&path)).transpose()
+    }
+
+    /// Set the configuration's paths to those of the project.
+    pub fn apply(&self, config: &mut Config) {
+        for (&year, paths) in &self.years {
+            paths.apply(config, year);
+        }
+    }
+}
@@ -1,8 +1,27 @@
 use aoclib::config::Config;
-use aoctool::PathOpts;
-use clap::{Args, Parser, Subcommand as DeriveSubcommand};
+use aoctool::{
+    answer::Part,
+    local::LocalConfig,
+    output,
+    settings::Settings,
+    templates::{self, TemplateSet},
+    DayOpts, Host, PathOpts, YearOpts,
+};
+use clap::{
+    parser::ValueSource, ArgAction, ArgGroup, Args, CommandFactory, Parser,
+    Subcommand as DeriveSubcommand,
+};
 use color_eyre::eyre::{bail, Result};
+use indicatif::{ProgressBar, ProgressStyle};
 use path_absolutize::Absolutize;
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    io::{IsTerminal, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::OnceLock,
+};
 use time::OffsetDateTime as DateTime;
 
 pub type Day = u8;
@@ -12,120 +31,2960 @@ fn local() -> DateTime {
     DateTime::now_local().expect("local system has determinable local offset")
 }
 
+/// The profile selected with `--profile`, if any.
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// The project-local configuration governing the current directory, if any.
+static LOCAL_CONFIG: OnceLock<Option<LocalConfig>> = OnceLock::new();
+
+/// Apply the project-local configuration, the selected profile, the environment, and the workspace
+/// of `cargo aoc` if running as one, to the configuration.
+fn with_workspace(mut config: Config) -> Config {
+    let settings = Settings::load().unwrap_or_default();
+    if let Some(local) = LOCAL_CONFIG.get().and_then(Option::as_ref) {
+        local.apply(&mut config);
+    }
+    if let Some(profile) = PROFILE
+        .get()
+        .and_then(Option::as_deref)
+        .and_then(|name| settings.profiles.get(name))
+    {
+        profile.apply(&mut config);
+    }
+    aoctool::env::apply(&mut config);
+    aoctool::cargo::apply_workspace(&mut config, &settings);
+    config
+}
+
+/// Load the configuration as saved, without the overlays of [`with_workspace`].
+///
+/// With the session key in the environment, there need be no configuration file.
+fn saved_config() -> Result<Config> {
+    match Config::load() {
+        Ok(config) => Ok(config),
+        Err(_) if aoctool::env::has_session() && !aoclib::config::path().exists() => {
+            Ok(Config::default())
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Load the configuration, apply [`with_workspace`] to it, and retrieve its session key from the
+/// keychain if it is kept there.
+///
+/// With the session key in the environment, there need be no configuration file.
+fn load_config() -> Result<Config> {
+    let mut config = with_workspace(saved_config()?);
+    aoctool::keychain::resolve_config(&mut config)?;
+    Ok(config)
+}
+
+/// The year and day implied by the current directory, where they can be inferred.
+fn inferred_date() -> (Option<Year>, Option<Day>) {
+    static INFERRED: OnceLock<(Option<Year>, Option<Day>)> = OnceLock::new();
+    *INFERRED.get_or_init(|| {
+        let config = with_workspace(Config::load().unwrap_or_default());
+        let Ok(settings) = Settings::load() else {
+            return (None, None);
+        };
+        std::env::current_dir()
+            .ok()
+            .and_then(|dir| aoctool::infer::infer_date(&config, &settings, &dir).ok())
+            .unwrap_or_default()
+    })
+}
+
+/// The default year: from `AOC_YEAR`, or inferred from the current directory, or the configured
+/// default year, or else this year.
+fn default_year() -> Year {
+    aoctool::env::year()
+        .ok()
+        .flatten()
+        .or_else(|| inferred_date().0)
+        .or_else(|| {
+            Settings::load()
+                .ok()
+                .and_then(|settings| settings.default_year)
+        })
+        .unwrap_or_else(|| local().year() as Year)
+}
+
+/// The default day: inferred from the current directory, or else today's date.
+fn default_day() -> Day {
+    inferred_date().1.unwrap_or_else(|| local().day() as Day)
+}
+
 #[derive(Args, Clone, Copy, Debug)]
 struct YearArg {
-    /// Year (default: this year)
+    /// Year (default: `AOC_YEAR`, or inferred from the current directory, or the configured
+    /// default year, or this year)
     #[arg(short, long)]
     year: Option<Year>,
 }
 
-impl YearArg {
-    fn year(self) -> Year {
-        self.year.unwrap_or_else(|| local().year() as Year)
+impl YearArg {
+    fn year(self) -> Year {
+        self.year.unwrap_or_else(default_year)
+    }
+}
+
+/// A selection of one year, or all configured years.
+#[derive(Clone, Copy, Debug)]
+enum YearSelection {
+    All,
+    Year(Year),
+}
+
+impl FromStr for YearSelection {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("all") {
+            Ok(Self::All)
+        } else {
+            s.parse().map(Self::Year)
+        }
+    }
+}
+
+#[derive(Args, Clone, Debug)]
+struct YearsArg {
+    /// Year; may be repeated, or `all` for every configured year (default: `AOC_YEAR`, or inferred
+    /// from the current directory, or the configured default year, or this year)
+    #[arg(short, long = "year", value_name = "YEAR")]
+    years: Vec<YearSelection>,
+}
+
+impl YearsArg {
+    fn years(&self, config: &Config) -> Vec<Year> {
+        if self.years.is_empty() {
+            return vec![default_year()];
+        }
+        let mut years = Vec::new();
+        for selection in &self.years {
+            match selection {
+                YearSelection::All => years.extend(config.paths.keys().copied()),
+                YearSelection::Year(year) => years.push(*year),
+            }
+        }
+        years.sort_unstable();
+        years.dedup();
+        years
+    }
+}
+
+/// Parse a `key=value` pair.
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, found `{s}`"))?;
+    if key.is_empty() {
+        return Err(format!("empty key in `{s}`"));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// An inclusive range of days, like `1-10`, or a single day.
+///
+/// Days are usually given as a comma-separated list of these, like `1-10,13`.
+#[derive(Clone, Copy, Debug)]
+struct DayRange {
+    first: Day,
+    last: Day,
+}
+
+impl DayRange {
+    const ALL: Self = Self { first: 1, last: 25 };
+}
+
+/// The days of a list of ranges, in order.
+fn expand_days(ranges: &[DayRange]) -> Vec<Day> {
+    let mut days = ranges
+        .iter()
+        .flat_map(|range| range.first..=range.last)
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+    days.dedup();
+    days
+}
+
+impl FromStr for DayRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |day: &str| {
+            day.trim()
+                .parse::<Day>()
+                .map_err(|err| format!("invalid day `{day}`: {err}"))
+        };
+        let (first, last) = match s.split_once('-') {
+            Some((first, last)) => (parse(first)?, parse(last)?),
+            None => (parse(s)?, parse(s)?),
+        };
+        if first == 0 || last > 25 || first > last {
+            return Err(format!("expected a range of days within 1-25, found `{s}`"));
+        }
+        Ok(Self { first, last })
+    }
+}
+
+/// A date given positionally: `2024/05`, `2024`, or `5`.
+///
+/// A lone number is a day if it could be one, and otherwise a year.
+#[derive(Clone, Copy, Debug)]
+struct PositionalDate {
+    year: Option<Year>,
+    day: Option<Day>,
+}
+
+impl FromStr for PositionalDate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let year = |year: &str| match year.trim().parse::<Year>() {
+            Ok(year) if year >= 2015 => Ok(year),
+            _ => Err(format!("invalid year `{year}`")),
+        };
+        let day = |day: &str| match day.trim().parse::<Day>() {
+            Ok(day) if (1..=25).contains(&day) => Ok(day),
+            _ => Err(format!("invalid day `{day}`")),
+        };
+        match s.split_once('/') {
+            Some((y, d)) => Ok(Self {
+                year: Some(year(y)?),
+                day: Some(day(d)?),
+            }),
+            None => match day(s) {
+                Ok(day) => Ok(Self {
+                    year: None,
+                    day: Some(day),
+                }),
+                Err(_) => Ok(Self {
+                    year: Some(year(s).map_err(|_| format!("invalid date `{s}`"))?),
+                    day: None,
+                }),
+            },
+        }
+    }
+}
+
+/// A date given by flags.
+///
+/// Subcommands whose positional arguments mean something else take this rather than [`Date`].
+#[derive(Args, Clone, Copy, Debug)]
+struct DateFlags {
+    /// Day (default: inferred from the current directory, or today's date)
+    #[arg(short, long)]
+    day: Option<Day>,
+
+    #[command(flatten)]
+    year: YearArg,
+}
+
+impl DateFlags {
+    fn day(self) -> Day {
+        self.day.unwrap_or_else(default_day)
+    }
+
+    fn year(self) -> Year {
+        self.year.year()
+    }
+}
+
+/// A date given by flags, or positionally: `2024 5`, `2024/05`, `2024`, or `5`.
+#[derive(Args, Clone, Copy, Debug)]
+struct Date {
+    /// Date, as `YEAR/DAY`, `YEAR`, or `DAY`, instead of `--year` and `--day`
+    #[arg(value_name = "DATE", conflicts_with_all = ["day", "year"])]
+    date: Option<PositionalDate>,
+
+    /// Day, following a year given positionally
+    #[arg(value_name = "DAY", requires = "date")]
+    date_day: Option<Day>,
+
+    #[command(flatten)]
+    flags: DateFlags,
+}
+
+impl Date {
+    fn day(self) -> Day {
+        self.date_day
+            .or(self.date.and_then(|date| date.day))
+            .unwrap_or_else(|| self.flags.day())
+    }
+
+    fn year(self) -> Year {
+        self.date
+            .and_then(|date| date.year)
+            .unwrap_or_else(|| self.flags.year())
+    }
+}
+
+/// A date, as [`Date`], of which the year may be repeated, or `all`, for commands which can cover
+/// several years.
+#[derive(Args, Clone, Debug)]
+struct Dates {
+    /// Date, as `YEAR/DAY`, `YEAR`, or `DAY`, instead of `--year` and `--day`
+    #[arg(value_name = "DATE", conflicts_with_all = ["day", "years"])]
+    date: Option<PositionalDate>,
+
+    /// Day, following a year given positionally
+    #[arg(value_name = "DAY", requires = "date")]
+    date_day: Option<Day>,
+
+    /// Day (default: inferred from the current directory, or today's date)
+    #[arg(short, long)]
+    day: Option<Day>,
+
+    #[command(flatten)]
+    years: YearsArg,
+}
+
+impl Dates {
+    fn day(&self) -> Day {
+        self.date_day
+            .or(self.date.and_then(|date| date.day))
+            .or(self.day)
+            .unwrap_or_else(default_day)
+    }
+
+    fn years(&self, config: &Config) -> Vec<Year> {
+        match self.date.and_then(|date| date.year) {
+            Some(year) => vec![year],
+            None => self.years.years(config),
+        }
+    }
+
+    /// The one year selected, for what covers only one.
+    fn year(&self, config: &Config) -> Result<Year> {
+        match self.years(config).as_slice() {
+            [year] => Ok(*year),
+            [] => bail!("no years are configured"),
+            _ => {
+                bail!("several years were given, but only one day is run; add `--all` or `--days`")
+            }
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+#[clap(about = "advent of code tool")]
+struct Cli {
+    /// Use the session and paths of this named profile
+    ///
+    /// Profiles are kept in the aoctool settings; create one with `config set --profile`.
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Print results as JSON, for scripts
+    ///
+    /// Each command prints one JSON document. `setup`, `completions`, `remove-day`, `init-year`,
+    /// `config edit`, `config set`, and `config clear` have no results to print, and refuse it.
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Log network requests, file writes, and manifest edits; repeat for more detail
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Log nothing but errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Never prompt or wait: fail instead, and print no colors or progress bars, for CI
+    #[arg(long, global = true)]
+    non_interactive: bool,
+
+    #[command(subcommand)]
+    cmd: Subcommand,
+}
+
+#[derive(DeriveSubcommand, Debug)]
+enum Subcommand {
+    /// Manage configuration
+    Config {
+        #[command(subcommand)]
+        cmd: ConfigOpts,
+    },
+    /// Walk through configuring the session key and paths, for a first run
+    Setup,
+    /// Emit a shell completion script
+    ///
+    /// The configured years are completed as those at the time the script is generated, so
+    /// regenerate it after `init-year`.
+    Completions {
+        /// Shell for which to emit the script
+        shell: clap_complete::Shell,
+    },
+    /// Emit the URL to a specified puzzle
+    Url {
+        #[command(flatten)]
+        date: Date,
+    },
+    /// Download the input for a puzzle
+    FetchInput {
+        /// Day (default: inferred from the current directory, or today's date)
+        #[arg(short, long)]
+        day: Option<Day>,
+
+        /// Days, as a comma-separated list of days and ranges, like `1-10,13`
+        #[arg(
+            long,
+            value_name = "DAYS",
+            value_delimiter = ',',
+            conflicts_with = "day"
+        )]
+        days: Vec<DayRange>,
+
+        #[command(flatten)]
+        years: YearsArg,
+
+        /// Use the session of this named account instead of the default session
+        ///
+        /// The input is stored alongside the default input, with the account name inserted
+        /// before the extension.
+        #[arg(short, long)]
+        account: Option<String>,
+
+        /// If the puzzle is not yet unlocked, wait until it is, then download the input
+        #[arg(short, long)]
+        wait: bool,
+    },
+    /// Show the status of each day of a year
+    List {
+        #[command(flatten)]
+        year: YearArg,
+    },
+    /// Summarize the configuration, today's puzzle, and any problems: where am I?
+    ///
+    /// With several years, each year's progress is summarized instead of today's puzzle.
+    Status {
+        #[command(flatten)]
+        years: YearsArg,
+    },
+    /// Show the time remaining until the next puzzle unlocks, at midnight US/Eastern
+    Countdown {
+        /// Keep updating the countdown until the puzzle unlocks
+        #[arg(long)]
+        watch: bool,
+    },
+    /// Render a badge of a year's stars, for embedding in a README
+    ///
+    /// Stars are those recorded in the year's progress; `stars sync` fetches them from the
+    /// website.
+    Badge {
+        #[command(flatten)]
+        year: YearArg,
+
+        /// Kind of badge
+        #[arg(long, value_enum, default_value = "svg")]
+        format: aoctool::badge::Format,
+
+        /// Write the badge to this file, rather than printing it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show the stars earned on each day of a year, as on the website's calendar
+    ///
+    /// Stars are those recorded in the year's progress; `stars sync` fetches them from the
+    /// website.
+    Calendar {
+        #[command(flatten)]
+        year: YearArg,
+    },
+    /// Show a private leaderboard
+    Leaderboard {
+        #[command(flatten)]
+        year: YearArg,
+
+        /// Id of the leaderboard, the number at the end of its URL (default: `leaderboard` in the
+        /// aoctool settings)
+        #[arg(long)]
+        id: Option<u64>,
+    },
+    /// Show how long after part 1 each day's part 2 was solved, from a leaderboard's star times
+    Deltas {
+        #[command(flatten)]
+        year: YearArg,
+
+        /// Id of the leaderboard (default: `leaderboard` in the aoctool settings)
+        ///
+        /// Everyone has a leaderboard of their own, whose id is their user id.
+        #[arg(long)]
+        id: Option<u64>,
+
+        /// Id of the member whose times to show (default: the leaderboard's owner)
+        #[arg(long)]
+        member: Option<u64>,
+    },
+    /// Submit an answer
+    ///
+    /// Answers computed from anything other than the real input, or which match a recorded
+    /// example answer, are refused.
+    Submit {
+        #[command(flatten)]
+        date: DateFlags,
+
+        /// Puzzle part
+        #[arg(short, long)]
+        part: Part,
+
+        /// Answer to submit (default: the answer reported by running the solution)
+        answer: Option<String>,
+
+        /// Input file from which the answer was computed (default: the real input)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Build and run a day's solution
+    #[command(group(ArgGroup::new("batch").args(["all", "days"])))]
+    Run {
+        #[command(flatten)]
+        date: Dates,
+
+        /// Run part 2 as well
+        #[arg(long)]
+        part2: bool,
+
+        /// Skip part 1
+        #[arg(long)]
+        no_part1: bool,
+
+        /// Build with optimizations, as `--profile release`
+        #[arg(long, conflicts_with = "profile")]
+        release: bool,
+
+        #[command(flatten)]
+        cargo_opts: aoctool::run::CargoOpts,
+
+        /// Input file on which to run (default: the real input)
+        #[arg(short, long, conflicts_with = "all")]
+        input: Option<PathBuf>,
+
+        /// Run on a recorded example (default: the first), and compare with its expected answers
+        #[arg(
+            long,
+            value_name = "K",
+            num_args = 0..=1,
+            default_missing_value = "1",
+            conflicts_with = "input"
+        )]
+        example: Option<usize>,
+
+        /// Run each part this many times, and report the best time
+        #[arg(long, value_name = "N", default_value_t = 1)]
+        min_runs: usize,
+
+        /// Run in both the debug and release profiles, and flag any difference in the answers
+        ///
+        /// This catches assumptions about integer overflow, which panics only in debug builds.
+        #[arg(long, conflicts_with_all = ["release", "profile", "all"])]
+        compare_profiles: bool,
+
+        /// With `--all` or `--days`, run this many days at once
+        ///
+        /// Every day is built first. Times are less reliable with more than one job.
+        #[arg(short, long, value_name = "N", default_value_t = 1, requires = "batch")]
+        jobs: usize,
+
+        /// Run every initialized day of the year, and summarize the results
+        ///
+        /// Answers are checked against those recorded as correct.
+        #[arg(long, conflicts_with = "day")]
+        all: bool,
+
+        /// Run these days, as with `--all`: a comma-separated list of days and ranges, like
+        /// `1-10,13`
+        #[arg(long, value_name = "DAYS", value_delimiter = ',', conflicts_with_all = ["day", "all"])]
+        days: Vec<DayRange>,
+
+        /// Submit the answer of each part run, as `submit` would, stopping at the first which
+        /// isn't correct
+        ///
+        /// Parts which are already solved are skipped.
+        #[arg(long, conflicts_with_all = ["all", "days", "compare_profiles"])]
+        submit: bool,
+    },
+    /// Run both parts of every initialized day, and check their answers against those recorded as
+    /// correct
+    ///
+    /// This is `run --all --part2`, across years: after a toolchain upgrade, say.
+    Verify {
+        #[command(flatten)]
+        years: YearsArg,
+
+        /// Verify only these days: a comma-separated list of days and ranges, like `1-10,13`
+        #[arg(long, value_name = "DAYS", value_delimiter = ',')]
+        days: Vec<DayRange>,
+
+        /// Build with optimizations, as `--profile release`
+        #[arg(long, conflicts_with = "profile")]
+        release: bool,
+
+        #[command(flatten)]
+        cargo_opts: aoctool::run::CargoOpts,
+
+        /// Run this many days at once
+        #[arg(short, long, value_name = "N", default_value_t = 1)]
+        jobs: usize,
+    },
+    /// Profile a day's solution on the real input, and write a flamegraph into its directory
+    ///
+    /// This requires cargo-flamegraph, and perf on Linux or DTrace on macOS.
+    Profile {
+        #[command(flatten)]
+        date: Date,
+
+        /// Profile part 2 as well
+        #[arg(long)]
+        part2: bool,
+
+        /// Skip part 1
+        #[arg(long)]
+        no_part1: bool,
+
+        #[command(flatten)]
+        cargo_opts: aoctool::run::CargoOpts,
+    },
+    /// Run a day's criterion benches, and record the results in the year's benchmark report
+    ///
+    /// The report is kept in "$implementation/benchmarks/$year.toml".
+    Bench {
+        #[command(flatten)]
+        date: Date,
+
+        #[command(flatten)]
+        cargo_opts: aoctool::run::CargoOpts,
+
+        /// Benchmark every day of the year which has benches
+        #[arg(long, conflicts_with = "day")]
+        all: bool,
+
+        /// Benchmark these days: a comma-separated list of days and ranges, like `1-10,13`
+        #[arg(long, value_name = "DAYS", value_delimiter = ',', conflicts_with_all = ["day", "all"])]
+        days: Vec<DayRange>,
+
+        /// Flag parts which slowed down by more than this percentage since they were last
+        /// recorded (default: `bench.regression-threshold` in the aoctool settings, or 5)
+        #[arg(long, value_name = "PERCENT")]
+        threshold: Option<f64>,
+
+        /// Record the results of regressed parts too, making them the new baseline
+        #[arg(long)]
+        accept: bool,
+    },
+    /// Initialize a puzzle
+    Init {
+        #[command(flatten)]
+        date: Date,
+
+        /// Do not create a sub-crate for the requested day
+        #[arg(long)]
+        skip_create_crate: bool,
+
+        /// Do not attempt to fetch the input for the requested day
+        #[arg(long)]
+        skip_get_input: bool,
+
+        /// Never download templates; use the built-in copies of any missing stock templates
+        #[arg(long)]
+        local_templates: bool,
+
+        /// Initialize every day of the year
+        ///
+        /// Inputs are not downloaded for days which have not yet unlocked.
+        #[arg(long, conflicts_with_all = ["day", "days"])]
+        all: bool,
+
+        /// Initialize these days: a comma-separated list of days and ranges, like `1-10,13`
+        ///
+        /// Inputs are not downloaded for days which have not yet unlocked.
+        #[arg(
+            long,
+            value_name = "DAYS",
+            value_delimiter = ',',
+            conflicts_with = "day"
+        )]
+        days: Vec<DayRange>,
+
+        /// Define a template variable, overriding any of the same name in the settings
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        vars: Vec<(String, String)>,
+
+        /// Print what would be created, without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(flatten)]
+        day_opts: DayOpts,
+    },
+    /// Remove a day's crate, undoing `init`
+    RemoveDay {
+        #[command(flatten)]
+        date: Date,
+
+        /// Also delete the day's input file
+        #[arg(long)]
+        input: bool,
+
+        /// Remove a day in this language, rather than a Rust crate
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Remove the day without prompting
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Initialize a repository for a year's solutions
+    InitYear {
+        #[command(flatten)]
+        year: YearArg,
+        #[command(flatten)]
+        path_opts: PathOpts,
+        #[command(flatten)]
+        year_opts: YearOpts,
+    },
+    /// Initialize every day of a range of years, downloading their inputs
+    ///
+    /// Each day which needs the website waits for `--delay` first, to go easy on it.
+    Backfill {
+        /// First year to initialize
+        #[arg(long, default_value_t = 2015)]
+        from: Year,
+
+        /// Last year to initialize (default: this year)
+        #[arg(long)]
+        to: Option<Year>,
+
+        /// Directory in which each year's workspace is created, as "$path/$year"
+        /// (default: the current directory)
+        ///
+        /// Years with a configured implementation directory keep it.
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Share one workspace, "$path", between every year
+        #[arg(long)]
+        shared_workspace: bool,
+
+        /// Seconds to wait before each day which needs the website
+        #[arg(long, default_value_t = 5)]
+        delay: u64,
+    },
+    /// Start managing an existing workspace, inferring its layout
+    Adopt {
+        #[command(flatten)]
+        year: YearArg,
+
+        /// The workspace to adopt (default: the current directory)
+        path: Option<PathBuf>,
+
+        /// Show what was inferred, without changing the configuration
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Move an existing repository's day binaries and inputs into the configured layout
+    Migrate {
+        #[command(flatten)]
+        year: YearArg,
+
+        /// The repository to migrate (default: the current directory)
+        path: Option<PathBuf>,
+    },
+    /// Track how long each part takes to solve
+    Timer {
+        #[command(subcommand)]
+        cmd: TimerOpts,
+    },
+    /// Track the stars earned on the website
+    Stars {
+        #[command(subcommand)]
+        cmd: StarsOpts,
+    },
+    /// Export the stars, answers, solve times, and benchmark results recorded for each part of
+    /// each day
+    ///
+    /// Each part about which anything is recorded is one row or object.
+    Export {
+        #[command(flatten)]
+        years: YearsArg,
+
+        /// Format of the export
+        #[arg(long, value_enum, default_value = "csv")]
+        format: aoctool::export::Format,
+
+        /// Write the export to this file, rather than printing it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Render reports from recorded results
+    Report {
+        #[command(subcommand)]
+        cmd: ReportOpts,
+    },
+    /// Inspect templates
+    Templates {
+        #[command(subcommand)]
+        cmd: TemplatesOpts,
+    },
+    /// Download the latest stock templates and review their differences from the local copies
+    UpdateTemplates {
+        #[command(flatten)]
+        year: YearArg,
+
+        /// Accept every update without prompting
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Clear templates, backing them up first.
+    ClearTemplates {
+        #[command(flatten)]
+        year: YearArg,
+
+        /// Clear only this file, relative to the day templates directory; may be repeated
+        #[arg(long, value_name = "PATH")]
+        only: Vec<PathBuf>,
+
+        /// Clear the templates without prompting
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+impl Subcommand {
+    /// The name of this subcommand, if it has no results to print as JSON: it asks questions,
+    /// opens an editor, prints a script, or does its work without a word.
+    fn without_json(&self) -> Option<&'static str> {
+        match self {
+            Self::Setup => Some("setup"),
+            Self::Completions { .. } => Some("completions"),
+            Self::RemoveDay { .. } => Some("remove-day"),
+            Self::InitYear { .. } => Some("init-year"),
+            Self::Config {
+                cmd: ConfigOpts::Edit { .. },
+            } => Some("config edit"),
+            Self::Config {
+                cmd: ConfigOpts::Set { .. },
+            } => Some("config set"),
+            Self::Config {
+                cmd: ConfigOpts::Clear { .. },
+            } => Some("config clear"),
+            _ => None,
+        }
+    }
+
+    fn run(self, host: &Host) -> Result<()> {
+        match self {
+            Self::Config { cmd } => cmd.run(host)?,
+            Self::Setup => setup(host)?,
+            Self::Completions { shell } => {
+                let config = with_workspace(Config::load().unwrap_or_default());
+                let years = config
+                    .paths
+                    .keys()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                let mut command = with_year_values(Cli::command(), &years);
+                clap_complete::generate(shell, &mut command, "aoc", &mut std::io::stdout());
+            }
+            Self::Url { date } => {
+                let url = aoclib::website::url_for_day(date.year(), date.day());
+                output::emit(&serde_json::json!({ "url": url }), |_| url.clone())?;
+            }
+            Self::FetchInput {
+                day,
+                days,
+                years,
+                account,
+                wait,
+            } => {
+                let config = load_config()?;
+                let settings = Settings::load()?;
+                let days = if days.is_empty() {
+                    vec![day.unwrap_or_else(default_day)]
+                } else {
+                    expand_days(&days)
+                };
+                let years = years.years(&config);
+                if years.is_empty() {
+                    bail!("no years are configured");
+                }
+                if let ([year], [day]) = (years.as_slice(), days.as_slice()) {
+                    let path = fetch_input(
+                        host,
+                        &config,
+                        &settings,
+                        *year,
+                        *day,
+                        account.as_deref(),
+                        wait,
+                    )?;
+                    output::emit(&path, |path| path.display().to_string())?;
+                } else {
+                    let puzzles = years
+                        .iter()
+                        .flat_map(|&year| days.iter().map(move |&day| (year, day)))
+                        .collect::<Vec<_>>();
+                    let bar = progress_bar(puzzles.len());
+                    let fetched = if wait {
+                        puzzles
+                            .iter()
+                            .map(|&(year, day)| {
+                                // the countdown while waiting would scribble over the bar
+                                let result = bar.suspend(|| {
+                                    fetch_input(
+                                        host,
+                                        &config,
+                                        &settings,
+                                        year,
+                                        day,
+                                        account.as_deref(),
+                                        wait,
+                                    )
+                                });
+                                bar.inc(1);
+                                result
+                            })
+                            .collect::<Vec<_>>()
+                    } else {
+                        aoctool::nonblocking::block_on(aoctool::nonblocking::get_inputs(
+                            host,
+                            &config,
+                            &settings,
+                            &puzzles,
+                            account.as_deref(),
+                            |_, _| bar.inc(1),
+                        ))
+                        .into_iter()
+                        .map(|result| result.map_err(Into::into))
+                        .collect()
+                    };
+                    bar.finish_and_clear();
+
+                    let mut results = Vec::new();
+                    let mut lines = Vec::new();
+                    let mut failures = 0;
+                    for (&(year, day), result) in puzzles.iter().zip(fetched) {
+                        let label = if days.len() > 1 {
+                            format!("{year}/{day:02}")
+                        } else {
+                            year.to_string()
+                        };
+                        match result {
+                            Ok(path) => {
+                                lines.push(format!("{label}: {}", path.display()));
+                                results.push(serde_json::json!({
+                                    "year": year,
+                                    "day": day,
+                                    "path": path,
+                                }));
+                            }
+                            Err(err) => {
+                                failures += 1;
+                                lines.push(format!("{label}: error: {err}"));
+                                results.push(serde_json::json!({
+                                    "year": year,
+                                    "day": day,
+                                    "error": err.to_string(),
+                                }));
+                            }
+                        }
+                    }
+                    output::emit(&results, |_| lines.join("\n"))?;
+                    if failures > 0 {
+                        bail!(
+                            "failed to fetch {failures} of {} inputs",
+                            years.len() * days.len()
+                        );
+                    }
+                }
+            }
+            Self::List { year } => {
+                let config = load_config()?;
+                let settings = Settings::load()?;
+                let statuses = aoctool::progress::year_status(
+                    host.fs.as_ref(),
+                    &config,
+                    &settings,
+                    year.year(),
+                )?;
+                output::emit(&statuses, |statuses| status_table(statuses))?;
+            }
+            Self::Status { years } => status(host, &years)?,
+            Self::Countdown { watch } => {
+                let (year, day) = aoctool::unlock::next_unlock()?;
+                let unlock = aoctool::unlock::unlock_time(year, day)?.to_offset(local().offset());
+                let unlocks_at = format!(
+                    "day {day} of {year} unlocks at {:02}:{:02} local time on {} {}",
+                    unlock.hour(),
+                    unlock.minute(),
+                    unlock.month(),
+                    unlock.day(),
+                );
+                let url = aoclib::website::url_for_day(year, day);
+                if watch {
+                    refuse_to_wait(year, day)?;
+                    if !output::is_json() {
+                        println!("{unlocks_at}");
+                    }
+                    aoctool::unlock::wait_for_unlock(year, day, |remaining| {
+                        if !output::is_json() {
+                            print!("\r{}", aoctool::unlock::format_countdown(remaining));
+                            let _ = std::io::stdout().flush();
+                        }
+                    })?;
+                }
+                let remaining = aoctool::unlock::time_until_unlock(year, day)?;
+                let countdown = serde_json::json!({
+                    "year": year,
+                    "day": day,
+                    "unlock": unlock.unix_timestamp(),
+                    "remaining_seconds": remaining.map(|remaining| remaining.as_secs()),
+                    "url": url,
+                });
+                output::emit(&countdown, |_| match remaining {
+                    _ if watch => format!("\rday {day} is unlocked: {url}"),
+                    Some(remaining) => format!(
+                        "{unlocks_at}\n{}",
+                        aoctool::unlock::format_countdown(remaining)
+                    ),
+                    None => unlocks_at.clone(),
+                })?;
+            }
+            Self::Badge {
+                year,
+                format,
+                output: path,
+            } => {
+                let config = load_config()?;
+                let year = year.year();
+                let badge = aoctool::badge::render(host.fs.as_ref(), &config, year, format)?;
+                if let Some(path) = &path {
+                    host.fs
+                        .write(path, badge.as_bytes())
+                        .map_err(|err| aoctool::Error::Io("writing badge", err))?;
+                }
+                let value = serde_json::json!({
+                    "year": year,
+                    "badge": badge,
+                    "path": path,
+                });
+                // written to a file, there is nothing to say
+                output::emit(&value, |_| match &path {
+                    Some(_) => String::new(),
+                    None => badge.clone(),
+                })?;
+            }
+            Self::Calendar { year } => {
+                let config = load_config()?;
+                let year = year.year();
+                let progress = aoctool::progress::Progress::load(host.fs.as_ref(), &config, year)?;
+                let total = (1..=25)
+                    .map(|day| u32::from(progress.day(day).stars()))
+                    .sum::<u32>();
+                let days = (1..=25)
+                    .map(|day| {
+                        let locked = aoctool::unlock::time_until_unlock(year, day)?.is_some();
+                        Ok((day, locked, progress.day(day).stars().min(2)))
+                    })
+                    .collect::<Result<Vec<_>, aoctool::Error>>()?;
+                let calendar = serde_json::json!({
+                    "year": year,
+                    "stars": total,
+                    "days": days
+                        .iter()
+                        .map(|(day, locked, stars)| {
+                            serde_json::json!({ "day": day, "locked": locked, "stars": stars })
+                        })
+                        .collect::<Vec<_>>(),
+                });
+                output::emit(&calendar, |_| {
+                    let mut calendar = format!("Advent of Code {year}: {total} stars\n");
+                    for &(day, locked, stars) in &days {
+                        if locked {
+                            calendar.push_str(&format!("{day:>3}\n"));
+                            continue;
+                        }
+                        let stars = usize::from(stars);
+                        calendar.push_str(&format!(
+                            "{day:>3}  {}{}\n",
+                            "*".repeat(stars),
+                            ".".repeat(2 - stars)
+                        ));
+                    }
+                    calendar
+                })?;
+            }
+            Self::Leaderboard { year, id } => {
+                let config = load_config()?;
+                let year = year.year();
+                let Some(id) = id.or(Settings::load()?.leaderboard) else {
+                    bail!("no leaderboard id; pass --id, or set `leaderboard` in the aoctool settings");
+                };
+                let json = aoctool::leaderboard::fetch_json(host.http.as_ref(), &config, year, id)?;
+                let leaderboard = aoctool::leaderboard::parse(&json)?;
+                // as the website serves it
+                let value = serde_json::from_str::<serde_json::Value>(&json)
+                    .map_err(aoctool::Error::ParseLeaderboard)?;
+                output::emit(&value, |_| {
+                    let mut table = format!(
+                        "{:>4}  {:>5}  {:>5}  {:<16}  name\n",
+                        "rank", "score", "stars", "last star"
+                    );
+                    let offset = local().offset();
+                    for (rank, member) in leaderboard.ranked().into_iter().enumerate() {
+                        let last_star = match DateTime::from_unix_timestamp(member.last_star_ts) {
+                            Ok(time) if member.last_star_ts > 0 => {
+                                let time = time.to_offset(offset);
+                                format!(
+                                    "{}-{:02}-{:02} {:02}:{:02}",
+                                    time.year(),
+                                    time.month() as u8,
+                                    time.day(),
+                                    time.hour(),
+                                    time.minute(),
+                                )
+                            }
+                            _ => "-".to_string(),
+                        };
+                        table.push_str(&format!(
+                            "{:>4}  {:>5}  {:>5}  {:<16}  {}\n",
+                            rank + 1,
+                            member.local_score,
+                            member.stars,
+                            last_star,
+                            member.display_name(),
+                        ));
+                    }
+                    table
+                })?;
+            }
+            Self::Deltas { year, id, member } => {
+                let config = load_config()?;
+                let year = year.year();
+                let Some(id) = id.or(Settings::load()?.leaderboard) else {
+                    bail!("no leaderboard id; pass --id, or set `leaderboard` in the aoctool settings");
+                };
+                let leaderboard =
+                    aoctool::leaderboard::fetch(host.http.as_ref(), &config, year, id)?;
+                let Some(member) = leaderboard.member(member) else {
+                    bail!("no such member of leaderboard {id}");
+                };
+                let deltas = member.part_deltas();
+                let mut sorted = deltas.iter().map(|(_, delta)| *delta).collect::<Vec<_>>();
+                sorted.sort_unstable();
+                let median = sorted.get(sorted.len() / 2).copied();
+                let mean = (!sorted.is_empty())
+                    .then(|| sorted.iter().sum::<std::time::Duration>() / sorted.len() as u32);
+                let value = serde_json::json!({
+                    "member": member.display_name(),
+                    "deltas": deltas
+                        .iter()
+                        .map(|(day, delta)| serde_json::json!({ "day": day, "seconds": delta.as_secs() }))
+                        .collect::<Vec<_>>(),
+                    "median_seconds": median.map(|median| median.as_secs()),
+                    "mean_seconds": mean.map(|mean| mean.as_secs()),
+                });
+                output::emit(&value, |_| {
+                    let (Some(median), Some(mean)) = (median, mean) else {
+                        return format!("{} has no day with both stars", member.display_name());
+                    };
+                    const BAR_WIDTH: u64 = 40;
+                    let longest = sorted.last().map_or(0, |delta| delta.as_secs()).max(1);
+                    let mut table = format!("{:>3}  {:>12}\n", "day", "delta");
+                    for (day, delta) in &deltas {
+                        let bar = (delta.as_secs() * BAR_WIDTH).div_ceil(longest) as usize;
+                        table.push_str(&format!(
+                            "{day:>3}  {:>12}  {}\n",
+                            aoctool::progress::format_solve_duration(*delta),
+                            "#".repeat(bar),
+                        ));
+                    }
+                    table.push_str(&format!(
+                        "median {}, mean {}",
+                        aoctool::progress::format_solve_duration(median),
+                        aoctool::progress::format_solve_duration(mean),
+                    ));
+                    table
+                })?;
+            }
+            Self::Submit {
+                date,
+                part,
+                answer,
+                input,
+            } => {
+                let config = load_config()?;
+                let (year, day) = (date.year(), date.day());
+                let opts = aoctool::run::RunOpts {
+                    input,
+                    ..Default::default()
+                };
+                let provenance = aoctool::run::provenance(&config, year, day, &opts)?;
+                let answer = match answer {
+                    Some(answer) => answer,
+                    None => {
+                        // an answer which can't be submitted isn't worth running for
+                        aoctool::submit::check_provenance(&provenance)?;
+                        aoctool::run::answer(
+                            host,
+                            &config,
+                            &Settings::load()?,
+                            year,
+                            day,
+                            part,
+                            &opts,
+                        )?
+                    }
+                };
+                let outcome = aoctool::submit::submit(
+                    host,
+                    &config,
+                    year,
+                    day,
+                    part,
+                    answer.trim(),
+                    &provenance,
+                )?;
+                let submission = serde_json::json!({
+                    "year": year,
+                    "day": day,
+                    "part": part.level(),
+                    "answer": answer.trim(),
+                    "outcome": outcome,
+                });
+                output::emit(&submission, |_| outcome.to_string())?;
+            }
+            Self::Run {
+                date,
+                part2,
+                no_part1,
+                release,
+                mut cargo_opts,
+                input,
+                example,
+                min_runs,
+                compare_profiles,
+                jobs,
+                all,
+                days,
+                submit,
+            } => {
+                let config = load_config()?;
+                let settings = Settings::load()?;
+                let all = all || !days.is_empty();
+                if release {
+                    cargo_opts.profile = Some("release".to_string());
+                }
+                let opts = aoctool::run::RunOpts {
+                    part2,
+                    no_part1,
+                    cargo: cargo_opts.with_defaults(&settings.cargo),
+                    input,
+                    example,
+                    min_runs,
+                    jobs,
+                    days: expand_days(&days),
+                };
+                let years = date.years(&config);
+                if all && years.is_empty() {
+                    bail!("no years are configured");
+                }
+                // only `--all` and `--days` cover several years
+                let year = if all { years[0] } else { date.year(&config)? };
+                let day = date.day();
+                if compare_profiles {
+                    let comparisons =
+                        aoctool::run::compare_profiles(host, &config, &settings, year, day, &opts)?;
+                    let describe = |run: &aoctool::run::PartRun| match &run.answer {
+                        _ if !run.success => "error".to_string(),
+                        Some(answer) => answer.clone(),
+                        None => "?".to_string(),
+                    };
+                    let profile = |run: &aoctool::run::PartRun| serde_json::json!({ "success": run.success, "answer": run.answer });
+                    let records = comparisons
+                        .iter()
+                        .map(|comparison| {
+                            serde_json::json!({
+                                "part": comparison.debug.part.level(),
+                                "debug": profile(&comparison.debug),
+                                "release": profile(&comparison.release),
+                                "diverges": comparison.diverges(),
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    output::emit(&records, |_| {
+                        comparisons
+                            .iter()
+                            .map(|comparison| {
+                                format!(
+                                    "{}: debug {}, release {}: {}",
+                                    comparison.debug.part,
+                                    describe(&comparison.debug),
+                                    describe(&comparison.release),
+                                    if comparison.diverges() {
+                                        "DIFFERENT"
+                                    } else {
+                                        "same"
+                                    },
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })?;
+                    let divergences = comparisons
+                        .iter()
+                        .filter(|comparison| comparison.diverges())
+                        .count();
+                    if divergences > 0 {
+                        bail!("the debug and release profiles disagree on {divergences} parts");
+                    }
+                } else if submit {
+                    aoctool::submit::check_provenance(&aoctool::run::provenance(
+                        &config, year, day, &opts,
+                    )?)?;
+                    let run = aoctool::run::run_day(host, &config, &settings, year, day, &opts)?;
+                    let mut submissions = Vec::new();
+                    let mut lines = Vec::new();
+                    for part_run in &run.parts {
+                        report_failure(part_run);
+                        if !part_run.success {
+                            bail!("{} failed", part_run.part);
+                        }
+                        let answer = part_run
+                            .answer
+                            .as_deref()
+                            .ok_or(aoctool::Error::NoAnswer(part_run.part))?;
+                        lines.push(format!("{}: {answer}", part_run.part));
+                        let outcome = match aoctool::submit::submit(
+                            host,
+                            &config,
+                            year,
+                            day,
+                            part_run.part,
+                            answer,
+                            &part_run.provenance,
+                        ) {
+                            Ok(outcome) => Some(outcome),
+                            Err(aoctool::Error::AlreadySolved(..)) => None,
+                            Err(err) => return Err(err.into()),
+                        };
+                        lines.push(match &outcome {
+                            Some(outcome) => format!("    {outcome}"),
+                            None => "    already solved; not submitted".to_string(),
+                        });
+                        let stop = outcome
+                            .as_ref()
+                            .is_some_and(|outcome| *outcome != aoctool::submit::Outcome::Correct);
+                        submissions.push(serde_json::json!({
+                            "part": part_run.part.level(),
+                            "answer": answer,
+                            "outcome": outcome,
+                        }));
+                        if stop {
+                            break;
+                        }
+                    }
+                    let value = serde_json::json!({
+                        "runs": aoctool::run::records(std::slice::from_ref(&run)),
+                        "submissions": submissions,
+                    });
+                    output::emit(&value, |_| lines.join("\n"))?;
+                } else if all && output::is_json() {
+                    let mut runs = Vec::new();
+                    for &year in &years {
+                        runs.extend(aoctool::run::run_year(
+                            host,
+                            &config,
+                            &settings,
+                            year,
+                            &opts,
+                            |_| {},
+                        )?);
+                    }
+                    output::emit(&aoctool::run::records(&runs), |_| String::new())?;
+                } else if all {
+                    run_years(host, &config, &settings, &years, &opts)?;
+                } else if example.is_some() || output::is_json() {
+                    let run = aoctool::run::run_day(host, &config, &settings, year, day, &opts)?;
+                    run.parts.iter().for_each(report_failure);
+                    let records = aoctool::run::records(std::slice::from_ref(&run));
+                    output::emit(&records, |_| {
+                        run.parts
+                            .iter()
+                            .map(|part_run| {
+                                let answer = part_run.answer.as_deref().unwrap_or("?");
+                                let expected = part_run.expected.as_deref().unwrap_or("?");
+                                format!(
+                                    "{}: {answer} (expected {expected}): {} ({:.3}s)",
+                                    part_run.part,
+                                    part_run.verdict(),
+                                    part_run.duration.as_secs_f64(),
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })?;
+                    if example.is_some() && run.verdict() == aoctool::run::Verdict::Fail {
+                        bail!("wrong answer for the example");
+                    }
+                } else {
+                    let times = aoctool::run::run(host, &config, &settings, year, day, &opts)?;
+                    let best_of = if min_runs > 1 {
+                        format!(" (best of {min_runs})")
+                    } else {
+                        String::new()
+                    };
+                    for (part, time) in times {
+                        eprintln!("{part}: {:.3}s{best_of}", time.as_secs_f64());
+                    }
+                }
+            }
+            Self::Verify {
+                years,
+                days,
+                release,
+                mut cargo_opts,
+                jobs,
+            } => {
+                let config = load_config()?;
+                let settings = Settings::load()?;
+                if release {
+                    cargo_opts.profile = Some("release".to_string());
+                }
+                let opts = aoctool::run::RunOpts {
+                    part2: true,
+                    cargo: cargo_opts.with_defaults(&settings.cargo),
+                    jobs,
+                    days: expand_days(&days),
+                    ..Default::default()
+                };
+                let years = years.years(&config);
+                if years.is_empty() {
+                    bail!("no years are configured");
+                }
+                if output::is_json() {
+                    let mut runs = Vec::new();
+                    for &year in &years {
+                        runs.extend(aoctool::run::run_year(
+                            host,
+                            &config,
+                            &settings,
+                            year,
+                            &opts,
+                            |_| {},
+                        )?);
+                    }
+                    output::emit(&aoctool::run::records(&runs), |_| String::new())?;
+                    let failures = runs
+                        .iter()
+                        .filter(|run| run.verdict() == aoctool::run::Verdict::Fail)
+                        .count();
+                    if failures > 0 {
+                        bail!("{failures} of {} days failed", runs.len());
+                    }
+                } else {
+                    run_years(host, &config, &settings, &years, &opts)?;
+                }
+            }
+            Self::Profile {
+                date,
+                part2,
+                no_part1,
+                cargo_opts,
+            } => {
+                let config = load_config()?;
+                let settings = Settings::load()?;
+                let opts = aoctool::run::RunOpts {
+                    part2,
+                    no_part1,
+                    cargo: cargo_opts.with_defaults(&settings.cargo),
+                    ..Default::default()
+                };
+                let flamegraph = aoctool::run::profile(
+                    host,
+                    &config,
+                    &settings,
+                    date.year(),
+                    date.day(),
+                    &opts,
+                )?;
+                output::emit(&serde_json::json!({ "flamegraph": flamegraph }), |_| {
+                    flamegraph.display().to_string()
+                })?;
+            }
+            Self::Bench {
+                date,
+                cargo_opts,
+                all,
+                days,
+                threshold,
+                accept,
+            } => {
+                let config = load_config()?;
+                let settings = Settings::load()?;
+                let opts = aoctool::bench::BenchOpts {
+                    cargo: cargo_opts.with_defaults(&settings.cargo),
+                    threshold,
+                    accept,
+                };
+                let year = date.year();
+                let days = if all {
+                    (1..=25)
+                        .filter(|&day| aoctool::bench::has_bench(&config, &settings, year, day))
+                        .collect()
+                } else if !days.is_empty() {
+                    expand_days(&days)
+                } else {
+                    vec![date.day()]
+                };
+                let bar = progress_bar(days.len());
+                let mut benches = Vec::new();
+                for day in days {
+                    bar.set_message(format!("day {day}"));
+                    // criterion reports to the terminal as it goes
+                    benches.push(bar.suspend(|| {
+                        aoctool::bench::bench_day(&config, &settings, year, day, &opts)
+                    })?);
+                    bar.inc(1);
+                }
+                bar.finish_and_clear();
+
+                let result = |bench: &aoctool::bench::DayBench| {
+                    if bench.regressions.is_empty() {
+                        "ok"
+                    } else if accept {
+                        "accepted"
+                    } else {
+                        "REGRESSED"
+                    }
+                };
+                let records = benches
+                    .iter()
+                    .map(|bench| {
+                        let part = |part| {
+                            bench.results.get(part).map(|estimate| {
+                                serde_json::json!({
+                                    "mean_ns": estimate.mean_ns,
+                                    "median_ns": estimate.median_ns,
+                                    "change": bench.change(part),
+                                })
+                            })
+                        };
+                        serde_json::json!({
+                            "day": bench.day,
+                            "part1": part(Part::One),
+                            "part2": part(Part::Two),
+                            "regressions": bench
+                                .regressions
+                                .iter()
+                                .map(|part| part.level())
+                                .collect::<Vec<_>>(),
+                            "result": result(bench).to_lowercase(),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                output::emit(&records, |_| {
+                    let describe = |bench: &aoctool::bench::DayBench, part| {
+                        let Some(estimate) = bench.results.get(part) else {
+                            return "-".to_string();
+                        };
+                        let mean = aoctool::bench::format_nanos(estimate.mean_ns);
+                        match bench.change(part) {
+                            Some(change) => format!("{mean} ({:+.1}%)", change * 100.0),
+                            None => mean,
+                        }
+                    };
+                    let mut table =
+                        format!("{:>3}  {:<22}  {:<22}  result\n", "day", "part 1", "part 2");
+                    for bench in &benches {
+                        table.push_str(&format!(
+                            "{:>3}  {:<22}  {:<22}  {}\n",
+                            bench.day,
+                            describe(bench, Part::One),
+                            describe(bench, Part::Two),
+                            result(bench),
+                        ));
+                    }
+                    table
+                })?;
+                let regressions = benches
+                    .iter()
+                    .map(|bench| bench.regressions.len())
+                    .sum::<usize>();
+                if regressions > 0 && !accept {
+                    bail!("{regressions} parts regressed; fix them, or record them with --accept");
+                }
+            }
+            Self::Init {
+                date,
+                skip_create_crate,
+                skip_get_input,
+                local_templates,
+                all,
+                days,
+                vars,
+                dry_run,
+                day_opts,
+            } => {
+                let config = load_config()?;
+                let mut settings = Settings::load()?;
+                settings.local_templates |= local_templates;
+                settings.template.vars.extend(vars);
+                // scaffolding stays within the year's directories
+                let host = &host.rooted(aoctool::vfs::roots(
+                    &config,
+                    &settings,
+                    date.year(),
+                    &day_opts,
+                ));
+                let days = if all { vec![DayRange::ALL] } else { days };
+                if !days.is_empty() {
+                    let year = date.year();
+                    let days = expand_days(&days);
+                    let locked = |day| {
+                        aoctool::unlock::time_until_unlock(year, day)
+                            .map(|remaining| remaining.is_some())
+                    };
+                    let bar = progress_bar(days.len());
+                    let mut failures = 0;
+                    let mut records = Vec::new();
+                    let mut lines = Vec::new();
+                    if dry_run {
+                        for &day in &days {
+                            let result = aoctool::preview_initialize(
+                                host,
+                                &config,
+                                &settings,
+                                year,
+                                day,
+                                skip_create_crate,
+                                skip_get_input || locked(day)?,
+                                &day_opts,
+                            );
+                            match result {
+                                Ok(preview) => {
+                                    lines.push(
+                                        describe_init_preview(&preview).trim_end().to_string(),
+                                    );
+                                    records.push(serde_json::json!({
+                                        "day": day,
+                                        "preview": init_preview_value(&preview),
+                                    }));
+                                }
+                                Err(err) => {
+                                    failures += 1;
+                                    lines.push(format!("day {day}: error: {err}"));
+                                    records.push(serde_json::json!({
+                                        "day": day,
+                                        "error": err.to_string(),
+                                    }));
+                                }
+                            }
+                            bar.inc(1);
+                        }
+                    } else {
+                        aoctool::initialize_days(
+                            host,
+                            &config,
+                            &settings,
+                            year,
+                            &days,
+                            skip_create_crate,
+                            skip_get_input,
+                            &day_opts,
+                            |day, result| {
+                                let locked = locked(day).unwrap_or_default();
+                                let line = match result {
+                                    Ok(()) if locked => {
+                                        format!("day {day}: ok (not yet unlocked; no input)")
+                                    }
+                                    Ok(()) => format!("day {day}: ok"),
+                                    Err(err) => {
+                                        failures += 1;
+                                        format!("day {day}: error: {err}")
+                                    }
+                                };
+                                lines.push(line);
+                                records.push(serde_json::json!({
+                                    "day": day,
+                                    "locked": locked,
+                                    "error": result.as_ref().err().map(ToString::to_string),
+                                }));
+                                bar.inc(1);
+                            },
+                        );
+                    }
+                    bar.finish_and_clear();
+                    output::emit(&records, |_| lines.join("\n"))?;
+                    if failures > 0 {
+                        bail!("failed to initialize {failures} of {} days", days.len());
+                    }
+                    return Ok(());
+                }
+                if dry_run {
+                    let preview = aoctool::preview_initialize(
+                        host,
+                        &config,
+                        &settings,
+                        date.year(),
+                        date.day(),
+                        skip_create_crate,
+                        skip_get_input,
+                        &day_opts,
+                    )?;
+                    output::emit(&init_preview_value(&preview), |_| {
+                        describe_init_preview(&preview)
+                    })?;
+                    return Ok(());
+                }
+                aoctool::initialize(
+                    host,
+                    &config,
+                    &settings,
+                    date.year(),
+                    date.day(),
+                    skip_create_crate,
+                    skip_get_input,
+                    &day_opts,
+                )?;
+                let day_dir =
+                    aoctool::day_dir(&config, &settings, date.year(), date.day(), &day_opts);
+                let value = serde_json::json!({
+                    "year": date.year(),
+                    "day": date.day(),
+                    "day_dir": day_dir,
+                });
+                // a day initialized without a word, as ever
+                output::emit(&value, |_| String::new())?;
+            }
+            Self::RemoveDay {
+                date,
+                input,
+                lang,
+                yes,
+            } => {
+                let config = load_config()?;
+                let settings = Settings::load()?;
+                let (year, day) = (date.year(), date.day());
+                let day_opts = DayOpts {
+                    lang,
+                    ..DayOpts::default()
+                };
+                let day_dir = aoctool::day_dir(&config, &settings, year, day, &day_opts);
+                if yes || confirm(&format!("remove {}?", day_dir.display()))? {
+                    aoctool::remove_day(host, &config, &settings, year, day, input, &day_opts)?;
+                }
+            }
+            Self::InitYear {
+                year,
+                path_opts,
+                year_opts,
+            } => {
+                // the configuration as saved, without the overlays of `with_workspace`, so that
+                // neither a profile, the environment, nor a local config file is saved into it
+                let mut config = Config::load().unwrap_or_default();
+                let mut settings = Settings::load()?;
+                aoctool::initialize_year(
+                    host,
+                    &mut config,
+                    &mut settings,
+                    year.year(),
+                    path_opts,
+                    year_opts,
+                )?;
+                config.save()?;
+                settings.save()?;
+            }
+            Self::Backfill {
+                from,
+                to,
+                path,
+                shared_workspace,
+                delay,
+            } => {
+                let to = to.unwrap_or_else(|| local().year() as Year);
+                if from < 2015 || from > to {
+                    bail!("no years to backfill from {from} to {to}");
+                }
+                let root = match path {
+                    Some(path) => path,
+                    None => std::env::current_dir()?,
+                };
+                // years are initialized into the configuration as saved, and days downloaded with
+                // it as overlaid, so that neither a profile, the environment, a local config file,
+                // nor the session from the keychain is saved into it
+                let mut saved = saved_config()?;
+                let session = load_config()?.session;
+                let mut settings = Settings::load()?;
+                let delay = std::time::Duration::from_secs(delay);
+                let unlocked = |year: Year| -> Result<Vec<Day>> {
+                    let mut days = Vec::new();
+                    for day in 1..=25 {
+                        if aoctool::unlock::time_until_unlock(year, day)?.is_some() {
+                            break;
+                        }
+                        days.push(day);
+                    }
+                    Ok(days)
+                };
+                let total = (from..=to)
+                    .map(|year| unlocked(year).map(|days| days.len()))
+                    .sum::<Result<usize>>()?;
+                let bar = progress_bar(total);
+                let mut failures = 0;
+                let mut first_request = true;
+                let mut records = Vec::new();
+                let mut lines = Vec::new();
+                for year in from..=to {
+                    let configured = saved
+                        .paths
+                        .get(&year)
+                        .is_some_and(|paths| paths.implementation.is_some());
+                    let implementation = match (configured, shared_workspace) {
+                        (true, _) => None,
+                        (false, true) => Some(root.clone()),
+                        (false, false) => Some(root.join(year.to_string())),
+                    };
+                    let path_opts = PathOpts {
+                        input_files: None,
+                        implementation,
+                        day_templates: None,
+                    };
+                    let year_opts = YearOpts {
+                        shared_workspace,
+                        ..YearOpts::default()
+                    };
+                    aoctool::initialize_year(
+                        host,
+                        &mut saved,
+                        &mut settings,
+                        year,
+                        path_opts,
+                        year_opts,
+                    )?;
+                    saved.save()?;
+                    settings.save()?;
+
+                    let mut config = with_workspace(saved.clone());
+                    config.session = session.clone();
+                    let day_opts = DayOpts::default();
+                    // scaffolding stays within the year's directories, as with `init`
+                    let host =
+                        &host.rooted(aoctool::vfs::roots(&config, &settings, year, &day_opts));
+                    for day in unlocked(year)? {
+                        bar.set_message(format!("{year} day {day}"));
+                        bar.inc(1);
+                        let day_dir = aoctool::day_dir(&config, &settings, year, day, &day_opts);
+                        let result = if day_dir.exists() && config.input_for(year, day).exists() {
+                            "already initialized".to_string()
+                        } else {
+                            if !std::mem::take(&mut first_request) {
+                                std::thread::sleep(delay);
+                            }
+                            match aoctool::initialize(
+                                host, &config, &settings, year, day, false, false, &day_opts,
+                            ) {
+                                Ok(()) => "ok".to_string(),
+                                Err(err) => {
+                                    failures += 1;
+                                    format!("error: {err}")
+                                }
+                            }
+                        };
+                        lines.push(format!("{year} day {day}: {result}"));
+                        records.push(serde_json::json!({
+                            "year": year,
+                            "day": day,
+                            "result": result,
+                        }));
+                    }
+                }
+                bar.finish_and_clear();
+                output::emit(&records, |_| lines.join("\n"))?;
+                if failures > 0 {
+                    bail!("failed to initialize {failures} days");
+                }
+            }
+            Self::Adopt {
+                year,
+                path,
+                dry_run,
+            } => {
+                let path = match path {
+                    Some(path) => path,
+                    None => std::env::current_dir()?,
+                };
+                let path = path.canonicalize()?;
+                let adoption = if dry_run {
+                    aoctool::adopt::inspect(host.fs.as_ref(), &path, year.year())?
+                } else {
+                    let mut config = Config::load().unwrap_or_default();
+                    let mut settings = Settings::load()?;
+                    let adoption = aoctool::adopt::adopt(
+                        host,
+                        &mut config,
+                        &mut settings,
+                        &path,
+                        year.year(),
+                    )?;
+                    config.save()?;
+                    settings.save()?;
+                    adoption
+                };
+                output::emit(&adoption, |adoption| {
+                    let mut text =
+                        format!("implementation: {}\n", adoption.implementation.display());
+                    match &adoption.input_files {
+                        Some(input_files) => {
+                            text.push_str(&format!("inputs: {}\n", input_files.display()))
+                        }
+                        None => text.push_str("inputs: not found; using the default\n"),
+                    }
+                    match &adoption.day_name {
+                        Some(day_name) => text.push_str(&format!("day crate names: {day_name}\n")),
+                        None => text.push_str("day crate names: not inferred; using the default\n"),
+                    }
+                    let days = adoption
+                        .days
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>();
+                    text.push_str(&format!("existing days: {}", days.join(", ")));
+                    text
+                })?;
+            }
+            Self::Migrate { year, path } => {
+                let path = match path {
+                    Some(path) => path,
+                    None => std::env::current_dir()?,
+                };
+                let path = path.canonicalize()?;
+                let mut config = Config::load().unwrap_or_default();
+                let mut settings = Settings::load()?;
+                let migration = aoctool::migrate::migrate(
+                    host,
+                    &mut config,
+                    &mut settings,
+                    &path,
+                    year.year(),
+                )?;
+                config.save()?;
+                settings.save()?;
+                output::emit(&migration, |migration| {
+                    let mut text = String::new();
+                    for aoctool::migrate::Move { from, to } in &migration.moves {
+                        text.push_str(&format!("moved {} to {}\n", from.display(), to.display()));
+                    }
+                    for package_name in &migration.crates {
+                        text.push_str(&format!("added {package_name} to the workspace\n"));
+                    }
+                    if migration.moves.is_empty() {
+                        text.push_str("nothing to migrate");
+                    }
+                    text
+                })?;
+            }
+            Self::Timer { cmd } => cmd.run(host)?,
+            Self::Stars { cmd } => cmd.run(host)?,
+            Self::Export {
+                years,
+                format,
+                output: path,
+            } => {
+                let config = load_config()?;
+                let mut records = Vec::new();
+                for year in years.years(&config) {
+                    records.extend(aoctool::export::records(host.fs.as_ref(), &config, year)?);
+                }
+                let export = aoctool::export::render(&records, format)?;
+                match &path {
+                    Some(path) => {
+                        host.fs
+                            .write(path, export.as_bytes())
+                            .map_err(|err| aoctool::Error::Io("writing export", err))?;
+                        // written to a file, there is nothing to say
+                        let value = serde_json::json!({ "path": path, "records": records.len() });
+                        output::emit(&value, |_| String::new())?;
+                    }
+                    None => output::emit(&records, |_| export.clone())?,
+                }
+            }
+            Self::Report { cmd } => cmd.run(host)?,
+            Self::Templates { cmd } => cmd.run(host)?,
+            Self::UpdateTemplates { year, yes } => {
+                let config = with_workspace(Config::load().unwrap_or_default());
+                let settings = Settings::load()?;
+                let bar = progress_bar(0);
+                bar.set_message("downloading templates");
+                let updates = templates::template_updates(
+                    host,
+                    &config,
+                    &settings,
+                    year.year(),
+                    |done, total| {
+                        bar.set_length(total as u64);
+                        bar.set_position(done as u64);
+                    },
+                )?;
+                bar.finish_and_clear();
+                let template_dir = templates::stock_template_dir(&config, &settings, year.year());
+                let mut records = Vec::new();
+                for update in updates {
+                    let diff = update.diff();
+                    let apply = yes || {
+                        explain(&diff);
+                        confirm(&format!("update {}?", update.path))?
+                    };
+                    if apply {
+                        update.apply(host.fs.as_ref(), &template_dir)?;
+                    }
+                    records.push(serde_json::json!({
+                        "path": update.path,
+                        "diff": diff,
+                        "applied": apply,
+                    }));
+                }
+                output::emit(&records, |records| {
+                    if records.is_empty() {
+                        "templates are up to date".to_string()
+                    } else if yes {
+                        // not yet shown, as no question was asked
+                        records
+                            .iter()
+                            .map(|record| record["diff"].as_str().unwrap_or_default())
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    } else {
+                        String::new()
+                    }
+                })?;
+            }
+            Self::ClearTemplates { year, only, yes } => {
+                let config = with_workspace(Config::load().unwrap_or_default());
+                let template_dir = config.day_template(year.year());
+                let question = if only.is_empty() {
+                    format!("remove {}?", template_dir.display())
+                } else {
+                    let files = only
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("remove {files} from {}?", template_dir.display())
+                };
+                let backup = if yes || confirm(&question)? {
+                    Some(aoctool::clear_templates(host, &config, year.year(), &only)?)
+                } else {
+                    None
+                };
+                output::emit(
+                    &serde_json::json!({ "backup": backup }),
+                    |_| match &backup {
+                        Some(backup) => format!("backed up to {}", backup.display()),
+                        None => String::new(),
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveSubcommand, Debug)]
+enum TemplatesOpts {
+    /// Show the resolved template directories, their files, and the available variables
+    Show {
+        #[command(flatten)]
+        year: YearArg,
+    },
+    /// Check that the templates parse and refer only to known variables
+    Validate {
+        #[command(flatten)]
+        year: YearArg,
+    },
+}
+
+impl TemplatesOpts {
+    fn run(self, host: &Host) -> Result<()> {
+        match self {
+            Self::Show { year } => {
+                let year = year.year();
+                let config = with_workspace(Config::load().unwrap_or_default());
+                let settings = Settings::load()?;
+
+                let dirs = templates::template_dirs(&config, &settings, year);
+                let source = settings.day_templates_git(year);
+                let template_set = TemplateSet::load_layered(
+                    host.fs.as_ref(),
+                    &dirs.iter().map(PathBuf::as_path).collect::<Vec<_>>(),
+                )?;
+                let missing =
+                    templates::missing_stock_templates(host.fs.as_ref(), &config, &settings, year);
+                let value = serde_json::json!({
+                    "directories": dirs
+                        .iter()
+                        .map(|dir| serde_json::json!({ "path": dir, "exists": host.fs.is_dir(dir) }))
+                        .collect::<Vec<_>>(),
+                    "git": source.as_ref().map(ToString::to_string),
+                    "engine": template_set.engine.to_string(),
+                    "files": template_set
+                        .files
+                        .iter()
+                        .map(|file| {
+                            serde_json::json!({
+                                "path": file.path(),
+                                "destination": file.destination_template(),
+                                "verbatim": template_set.is_verbatim(file),
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                    "would_download": missing,
+                    "variables": templates::BUILTIN_VARIABLES
+                        .iter()
+                        .copied()
+                        .collect::<BTreeMap<_, _>>(),
+                    "user_variables": settings.template.vars,
+                });
+                output::emit(&value, |_| {
+                    let mut text =
+                        "template directories (later directories take precedence):\n".to_string();
+                    for dir in &dirs {
+                        let missing = if host.fs.is_dir(dir) {
+                            ""
+                        } else {
+                            " (missing)"
+                        };
+                        text.push_str(&format!("  {}{missing}\n", dir.display()));
+                    }
+                    if let Some(source) = &source {
+                        text.push_str(&format!("cloned from: {source}\n"));
+                    }
+                    text.push_str(&format!("engine: {}\n", template_set.engine));
+                    text.push_str("files:\n");
+                    for file in &template_set.files {
+                        let verbatim = if template_set.is_verbatim(file) {
+                            " (verbatim)"
+                        } else {
+                            ""
+                        };
+                        text.push_str(&format!(
+                            "  {} -> {}{verbatim}\n",
+                            file.path().display(),
+                            file.destination_template()
+                        ));
+                    }
+                    if !missing.is_empty() {
+                        text.push_str("would download:\n");
+                        for path in &missing {
+                            text.push_str(&format!("  {}\n", path.display()));
+                        }
+                    }
+                    text.push_str("variables:\n");
+                    for (name, description) in templates::BUILTIN_VARIABLES {
+                        text.push_str(&format!("  {name:<16} {description}\n"));
+                    }
+                    for (name, value) in &settings.template.vars {
+                        text.push_str(&format!("  {name:<16} {value:?} (user-defined)\n"));
+                    }
+                    text
+                })?;
+            }
+            Self::Validate { year } => {
+                let year = year.year();
+                let config = with_workspace(Config::load().unwrap_or_default());
+                let settings = Settings::load()?;
+
+                let dirs = templates::template_dirs(&config, &settings, year);
+                let dirs = dirs.iter().map(PathBuf::as_path).collect::<Vec<_>>();
+                let template_set = TemplateSet::load_layered(host.fs.as_ref(), &dirs)?;
+                let context = templates::Context::placeholder(&config, &settings, year);
+                let problems = template_set.validate(host.fs.as_ref(), &context)?;
+                let value = serde_json::json!({
+                    "templates": template_set.files.len(),
+                    "problems": problems.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                });
+                output::emit(&value, |_| {
+                    if problems.is_empty() {
+                        format!("{} templates ok", template_set.files.len())
+                    } else {
+                        problems
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    }
+                })?;
+                if !problems.is_empty() {
+                    bail!("found {} problems in templates", problems.len());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveSubcommand, Debug)]
+enum TimerOpts {
+    /// Start timing a day now, replacing the start recorded when it was initialized
+    ///
+    /// Each part's time runs until its correct answer is submitted.
+    Start {
+        #[command(flatten)]
+        date: Date,
+    },
+}
+
+impl TimerOpts {
+    fn run(self, host: &Host) -> Result<()> {
+        match self {
+            Self::Start { date } => {
+                let config = load_config()?;
+                let (year, day) = (date.year(), date.day());
+                let mut progress =
+                    aoctool::progress::Progress::load(host.fs.as_ref(), &config, year)?;
+                progress.record_start(day, true);
+                progress.save(host.fs.as_ref(), &config, year)?;
+                output::emit(&serde_json::json!({ "year": year, "day": day }), |_| {
+                    format!("timing day {day} of {year}")
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveSubcommand, Debug)]
+enum StarsOpts {
+    /// Record the stars earned on each day, from the website's calendar, in each year's progress
+    Sync {
+        #[command(flatten)]
+        years: YearsArg,
+    },
+}
+
+impl StarsOpts {
+    fn run(self, host: &Host) -> Result<()> {
+        match self {
+            Self::Sync { years } => {
+                let config = load_config()?;
+                let mut totals = Vec::new();
+                for year in years.years(&config) {
+                    let stars = aoctool::website::fetch_stars(host.http.as_ref(), &config, year)?;
+                    let mut progress =
+                        aoctool::progress::Progress::load(host.fs.as_ref(), &config, year)?;
+                    let total = progress.sync_stars(&stars);
+                    progress.save(host.fs.as_ref(), &config, year)?;
+                    totals.push(serde_json::json!({ "year": year, "stars": total }));
+                }
+                output::emit(&totals, |totals| {
+                    totals
+                        .iter()
+                        .map(|total| format!("{}: {} stars", total["year"], total["stars"]))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveSubcommand, Debug)]
+enum ReportOpts {
+    /// Render a table of each day's benchmarked times into the workspace README
+    ///
+    /// The table goes between "<!-- timings:start -->" and "<!-- timings:end -->", which are
+    /// appended in a new section if the README lacks them.
+    Timings {
+        #[command(flatten)]
+        year: YearArg,
+
+        /// Print the table, rather than updating the README
+        #[arg(long)]
+        stdout: bool,
+    },
+    /// Render a table of each day's puzzle, crate, and stars into the workspace README
+    ///
+    /// The table goes between "<!-- progress:start -->" and "<!-- progress:end -->", which are
+    /// appended in a new section if the README lacks them.
+    Progress {
+        #[command(flatten)]
+        year: YearArg,
+
+        /// Print the table, rather than updating the README
+        #[arg(long)]
+        stdout: bool,
+    },
+}
+
+impl ReportOpts {
+    fn run(self, host: &Host) -> Result<()> {
+        match self {
+            Self::Timings { year, stdout } => {
+                let year = year.year();
+                let config = load_config()?;
+                if stdout {
+                    let report = aoctool::bench::Report::load(&config, year)?;
+                    let table = aoctool::bench::timings_table(&report);
+                    output::emit(&serde_json::json!({ "table": table }), |_| table.clone())?;
+                } else {
+                    let readme =
+                        aoctool::bench::update_readme_timings(host.fs.as_ref(), &config, year)?;
+                    output::emit(&serde_json::json!({ "readme": readme }), |_| {
+                        format!("updated {}", readme.display())
+                    })?;
+                }
+            }
+            Self::Progress { year, stdout } => {
+                let year = year.year();
+                let config = load_config()?;
+                let settings = Settings::load()?;
+                if stdout {
+                    let table = aoctool::progress::progress_table(
+                        host.fs.as_ref(),
+                        &config,
+                        &settings,
+                        year,
+                    )?;
+                    output::emit(&serde_json::json!({ "table": table }), |_| table.clone())?;
+                } else {
+                    let readme = aoctool::progress::update_readme_progress(
+                        host.fs.as_ref(),
+                        &config,
+                        &settings,
+                        year,
+                    )?;
+                    output::emit(&serde_json::json!({ "readme": readme }), |_| {
+                        format!("updated {}", readme.display())
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn describe_init_preview(preview: &aoctool::InitPreview) -> String {
+    let mut text = String::new();
+    if !preview.manifest_diff.is_empty() {
+        text.push_str(&format!("{}\n", preview.manifest_diff));
+    }
+    for path in &preview.missing_templates {
+        text.push_str(&format!("would download template: {}\n", path.display()));
+    }
+    for path in &preview.existing {
+        text.push_str(&format!(
+            "would keep existing: {}\n",
+            preview.day_dir.join(path).display()
+        ));
+    }
+    for file in &preview.files {
+        let path = preview.day_dir.join(&file.destination);
+        text.push_str(&format!("==> {} <==\n", path.display()));
+        match std::str::from_utf8(&file.contents) {
+            Ok(contents) => text.push_str(&format!("{contents}\n")),
+            Err(_) => text.push_str(&format!("({} bytes of binary data)\n", file.contents.len())),
+        }
+    }
+    if let Some(input) = &preview.input {
+        text.push_str(&format!("would download input: {}\n", input.display()));
+    }
+    for hook in &preview.hooks {
+        text.push_str(&format!("would run: {hook}\n"));
+    }
+    if let Some(message) = &preview.commit_message {
+        text.push_str(&format!("would commit: {message}\n"));
+    }
+    text
+}
+
+/// What `init --dry-run` would do for a day, for `--json`. Binary files have no `contents`.
+fn init_preview_value(preview: &aoctool::InitPreview) -> serde_json::Value {
+    serde_json::json!({
+        "day_dir": preview.day_dir,
+        "files": preview
+            .files
+            .iter()
+            .map(|file| {
+                serde_json::json!({
+                    "path": preview.day_dir.join(&file.destination),
+                    "contents": std::str::from_utf8(&file.contents).ok(),
+                })
+            })
+            .collect::<Vec<_>>(),
+        "existing": preview
+            .existing
+            .iter()
+            .map(|path| preview.day_dir.join(path))
+            .collect::<Vec<_>>(),
+        "missing_templates": preview.missing_templates,
+        "manifest_diff": preview.manifest_diff,
+        "input": preview.input,
+        "hooks": preview.hooks,
+        "commit_message": preview.commit_message,
+    })
+}
+
+/// The user's editor: `$VISUAL`, or `$EDITOR`, or else a platform default.
+///
+/// The variable may hold arguments as well as the program, as in `code --wait`.
+fn editor() -> Result<std::process::Command> {
+    if !output::is_interactive() {
+        bail!("cannot open an editor with --non-interactive");
     }
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let mut words = editor.split_whitespace();
+    let Some(program) = words.next() else {
+        bail!("no editor configured; set $VISUAL or $EDITOR");
+    };
+    let mut command = std::process::Command::new(program);
+    command.args(words);
+    Ok(command)
 }
 
-#[derive(Args, Clone, Copy, Debug)]
-struct Date {
-    /// Day (default: today's date)
-    #[arg(short, long)]
-    day: Option<Day>,
+/// Render a table of the status of each day of a year.
+fn status_table(statuses: &[aoctool::progress::DayStatus]) -> String {
+    let mark = |present: bool| if present { "yes" } else { "-" };
+    let mut table = format!(
+        "{:>3}  {:<8} {:<5} {:>8}  {:<7} {:<5}  {:<10} {:<10}\n",
+        "day", "solution", "input", "examples", "answers", "stars", "part 1", "part 2"
+    );
+    for status in statuses {
+        let answers = [Part::One, Part::Two]
+            .into_iter()
+            .filter(|part| status.progress.answers.get(*part).is_some())
+            .map(|part| part.level().to_string())
+            .collect::<Vec<_>>();
+        let answers = if answers.is_empty() {
+            "-".to_string()
+        } else {
+            answers.join(",")
+        };
+        let solve_duration = |part| {
+            status
+                .progress
+                .times
+                .solve_duration(part)
+                .map(aoctool::progress::format_solve_duration)
+                .unwrap_or_else(|| "-".to_string())
+        };
+        table.push_str(&format!(
+            "{:>3}  {:<8} {:<5} {:>8}  {:<7} {:<5}  {:<10} {:<10}\n",
+            status.day,
+            mark(status.solution.is_some()),
+            mark(status.input),
+            status.examples,
+            answers,
+            "*".repeat(status.progress.stars().into()),
+            solve_duration(Part::One),
+            solve_duration(Part::Two),
+        ));
+    }
+    table
+}
 
-    #[command(flatten)]
-    year: YearArg,
+/// Ask the user a yes/no question on stdin. Anything but "y" or "yes" is a no.
+/// Show what a question [asked](confirm) next is about, where the question is asked.
+fn explain(text: &str) {
+    if output::is_json() {
+        eprintln!("{text}");
+    } else {
+        println!("{text}");
+    }
 }
 
-impl Date {
-    fn day(self) -> Day {
-        self.day.unwrap_or_else(|| local().day() as Day)
+fn confirm(question: &str) -> Result<bool> {
+    if !output::is_interactive() {
+        bail!("cannot ask \"{question}\" with --non-interactive; pass --yes to proceed");
+    }
+    // with `--json`, stdout holds only the results
+    if output::is_json() {
+        eprint!("{question} [y/N] ");
+    } else {
+        print!("{question} [y/N] ");
+        std::io::stdout().flush()?;
     }
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes"))
+}
 
-    fn year(self) -> Year {
-        self.year.year()
+/// Ask the user for a line of input on stdin, with a default for an empty answer.
+fn prompt(question: &str, default: Option<&str>) -> Result<String> {
+    if !output::is_interactive() {
+        bail!("cannot ask for {question} with --non-interactive");
+    }
+    match default {
+        Some(default) => print!("{question} [{default}]: "),
+        None => print!("{question}: "),
     }
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer)? == 0 {
+        bail!("no answer given for {question}");
+    }
+    let answer = answer.trim();
+    Ok(match default {
+        Some(default) if answer.is_empty() => default.to_string(),
+        _ => answer.to_string(),
+    })
 }
 
-#[derive(Parser, Debug)]
-#[clap(about = "advent of code tool")]
-enum Subcommand {
-    /// Manage configuration
-    Config {
-        #[command(subcommand)]
-        cmd: ConfigOpts,
-    },
-    /// Emit the URL to a specified puzzle
-    Url {
-        #[command(flatten)]
-        date: Date,
-    },
-    /// Initialize a puzzle
-    Init {
-        #[command(flatten)]
-        date: Date,
+/// Interactively configure the session key and a year's paths, and save the configuration.
+fn setup(host: &Host) -> Result<()> {
+    let config_path = aoclib::config::path();
+    if config_path.exists()
+        && !confirm(&format!(
+            "{} exists already; set it up again?",
+            config_path.display()
+        ))?
+    {
+        return Ok(());
+    }
+    let mut config = Config::load().unwrap_or_default();
 
-        /// Do not create a sub-crate for the requested day
-        #[arg(long)]
-        skip_create_crate: bool,
+    println!("Log in to adventofcode.com, and copy the value of its `session` cookie.");
+    loop {
+        let session = prompt("session key", None)?;
+        if session.is_empty() {
+            continue;
+        }
+        match aoctool::website::whoami(host.http.as_ref(), &session) {
+            Ok(name) => println!("logged in as {name}"),
+            Err(aoctool::Error::NotLoggedIn) => {
+                println!("that session key is invalid or has expired");
+                continue;
+            }
+            Err(err) => {
+                println!("could not check the session key: {err}");
+                if !confirm("use it anyway?")? {
+                    continue;
+                }
+            }
+        }
+        config.session = session;
+        break;
+    }
 
-        /// Do not attempt to fetch the input for the requested day
-        #[arg(long)]
-        skip_get_input: bool,
-    },
-    /// Initialize a repository for a year's solutions
-    InitYear {
-        #[command(flatten)]
-        year: YearArg,
-        #[command(flatten)]
-        path_opts: PathOpts,
-    },
-    /// Clear templates.
-    ClearTemplates {
-        #[command(flatten)]
-        year: YearArg,
-    },
-}
+    let year = loop {
+        let year = prompt("year", Some(&default_year().to_string()))?;
+        match year.parse::<Year>() {
+            Ok(year) if year >= 2015 => break year,
+            _ => println!("{year} is not a year of Advent of Code"),
+        }
+    };
 
-impl Subcommand {
-    fn run(self) -> Result<()> {
-        match self {
-            Self::Config { cmd } => cmd.run()?,
-            Self::Url { date } => {
-                println!("{}", aoclib::website::url_for_day(date.year(), date.day()));
-            }
-            Self::Init {
-                date,
-                skip_create_crate,
-                skip_get_input,
-            } => {
-                let config = Config::load()?;
-                aoctool::initialize(
-                    &config,
-                    date.year(),
-                    date.day(),
-                    skip_create_crate,
-                    skip_get_input,
-                )?;
+    let directory = |question: &str, default: PathBuf| -> Result<PathBuf> {
+        loop {
+            let path = PathBuf::from(prompt(question, Some(&default.display().to_string()))?);
+            if path.exists() && !path.is_dir() {
+                println!("{} is not a directory", path.display());
+                continue;
             }
-            Self::InitYear { year, path_opts } => {
-                let mut config = Config::load().unwrap_or_default();
-                aoctool::initialize_year(&mut config, year.year(), path_opts)?;
-                config.save()?;
+            return Ok(path.absolutize()?.into_owned());
+        }
+    };
+    let implementation = directory("implementation directory", config.implementation(year))?;
+    let input_files = directory("input files directory", config.input_files(year))?;
+    config.set_implementation(year, implementation);
+    config.set_input_files(year, input_files);
+
+    config.save()?;
+    println!("wrote {}", config_path.display());
+    println!("next, `aoc init-year --year {year}`, and then `aoc init {year} 1`");
+    Ok(())
+}
+
+/// Print the configuration for the inferred year, the state of the inferred day's puzzle, and the
+/// problems which `config doctor` would find.
+///
+/// With several years, each year's paths and progress are printed instead of the day's puzzle.
+fn status(host: &Host, years: &YearsArg) -> Result<()> {
+    let config = with_workspace(Config::load().unwrap_or_default());
+    let settings = Settings::load().unwrap_or_default();
+    let years = years.years(&config);
+
+    let config_path = aoclib::config::path();
+    let profile = PROFILE.get().and_then(Option::as_deref);
+    let local = LOCAL_CONFIG
+        .get()
+        .and_then(Option::as_ref)
+        .map(|local| &local.path);
+    let mut human = String::new();
+    let profile_note = match profile {
+        Some(profile) => format!(" (profile {profile})"),
+        None => String::new(),
+    };
+    if config_path.exists() {
+        human.push_str(&format!(
+            "config:         {}{profile_note}\n",
+            config_path.display()
+        ));
+    } else {
+        human.push_str(&format!(
+            "config:         none; run `aoc setup` to create it{profile_note}\n"
+        ));
+    }
+    if let Some(local) = local {
+        human.push_str(&format!("project config: {}\n", local.display()));
+    }
+
+    let mut summaries = Vec::new();
+    let mut today = None;
+    if let [year] = years.as_slice() {
+        let year = *year;
+        human.push_str(&format!("year:           {year}\n"));
+        human.push_str(&format!(
+            "implementation: {}\n",
+            config.implementation(year).display()
+        ));
+        human.push_str(&format!(
+            "input files:    {}\n",
+            config.input_files(year).display()
+        ));
+        summaries.push(serde_json::json!({
+            "year": year,
+            "implementation": config.implementation(year),
+            "input_files": config.input_files(year),
+        }));
+        if let Some((value, text)) = today_status(host, &config, &settings, year, default_day())? {
+            today = Some(value);
+            human.push('\n');
+            human.push_str(&text);
+        }
+    } else {
+        for &year in &years {
+            let statuses =
+                aoctool::progress::year_status(host.fs.as_ref(), &config, &settings, year)?;
+            let solutions = statuses
+                .iter()
+                .filter(|status| status.solution.is_some())
+                .count();
+            let stars = statuses
+                .iter()
+                .map(|status| u32::from(status.progress.stars()))
+                .sum::<u32>();
+            human.push_str(&format!("\n{year}:\n"));
+            human.push_str(&format!(
+                "  implementation: {}\n",
+                config.implementation(year).display()
+            ));
+            human.push_str(&format!(
+                "  input files:    {}\n",
+                config.input_files(year).display()
+            ));
+            human.push_str(&format!("  solutions:      {solutions} of 25\n"));
+            human.push_str(&format!("  stars:          {stars} of 50\n"));
+            summaries.push(serde_json::json!({
+                "year": year,
+                "implementation": config.implementation(year),
+                "input_files": config.input_files(year),
+                "solutions": solutions,
+                "stars": stars,
+            }));
+        }
+    }
+
+    human.push('\n');
+    let problems = aoctool::doctor::diagnose(host, &config)
+        .into_iter()
+        .filter_map(|check| match check.outcome {
+            aoctool::doctor::Outcome::Fail { problem, fix } => Some((check.subject, problem, fix)),
+            aoctool::doctor::Outcome::Pass(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if problems.is_empty() {
+        human.push_str("no problems found\n");
+    }
+    for (subject, problem, fix) in &problems {
+        human.push_str(&format!("problem: {subject}: {problem}\n"));
+        human.push_str(&format!("    fix: {fix}\n"));
+    }
+
+    let value = serde_json::json!({
+        "config": config_path.exists().then_some(&config_path),
+        "profile": profile,
+        "project_config": local,
+        "years": summaries,
+        "today": today,
+        "problems": problems
+            .iter()
+            .map(|(subject, problem, fix)| {
+                serde_json::json!({ "subject": subject, "problem": problem, "fix": fix })
+            })
+            .collect::<Vec<_>>(),
+    });
+    output::emit(&value, |_| human)?;
+    Ok(())
+}
+
+/// The state of a day's puzzle, if it is a day of Advent, and its description.
+fn today_status(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: Year,
+    day: Day,
+) -> Result<Option<(serde_json::Value, String)>> {
+    if !(1..=25).contains(&day) {
+        return Ok(None);
+    }
+    let label = format!("day {day}:");
+    if let Some(remaining) = aoctool::unlock::time_until_unlock(year, day)? {
+        let value = serde_json::json!({
+            "day": day,
+            "unlocked": false,
+            "remaining_seconds": remaining.as_secs(),
+        });
+        let text = format!(
+            "{label:<16}unlocks in {}\n",
+            aoctool::unlock::format_countdown(remaining)
+        );
+        return Ok(Some((value, text)));
+    }
+
+    let status = aoctool::progress::year_status(host.fs.as_ref(), config, settings, year)?
+        .into_iter()
+        .find(|status| status.day == day)
+        .expect("every day has a status");
+    let mark = |present: bool| if present { "yes" } else { "no" };
+    let mut text = format!("{label:<16}unlocked\n");
+    match &status.solution {
+        Some(solution) => text.push_str(&format!("  solution:     {}\n", solution.display())),
+        None => text.push_str(&format!(
+            "  solution:     none; `aoc init {year} {day}` creates it\n"
+        )),
+    }
+    text.push_str(&format!("  input:        {}\n", mark(status.input)));
+    text.push_str(&format!("  stars:        {}\n", status.progress.stars()));
+    let value = serde_json::json!({
+        "day": day,
+        "unlocked": true,
+        "solution": status.solution,
+        "input": status.input,
+        "stars": status.progress.stars(),
+    });
+    Ok(Some((value, text)))
+}
+
+/// Fail if a day's puzzle is still locked and we may not wait for it.
+fn refuse_to_wait(year: Year, day: Day) -> Result<()> {
+    if !output::is_interactive() && aoctool::unlock::time_until_unlock(year, day)?.is_some() {
+        bail!("day {day} of {year} is still locked, and --non-interactive never waits");
+    }
+    Ok(())
+}
+
+/// A progress bar of `len` steps, drawn on stderr.
+///
+/// When stdout isn't a terminal, or results are printed as JSON, the bar is hidden, leaving only
+/// the plain output. Lines printed while the bar is shown should go through
+/// [`ProgressBar::suspend`], which prints them even while it is hidden.
+fn progress_bar(len: usize) -> ProgressBar {
+    if output::is_json()
+        || !output::is_interactive()
+        || !std::io::stdout().is_terminal()
+        || !std::io::stderr().is_terminal()
+    {
+        return ProgressBar::hidden();
+    }
+    ProgressBar::new(len as u64).with_style(
+        ProgressStyle::with_template("{bar:30} {pos}/{len} {msg}")
+            .expect("progress bar template is valid"),
+    )
+}
+
+/// Print the end of a failed part's standard error, if it has any.
+fn report_failure(part_run: &aoctool::run::PartRun) {
+    if part_run.success || part_run.stderr.is_empty() {
+        return;
+    }
+    match part_run.exit_code {
+        Some(code) => eprintln!("{} failed (exit code {code}):", part_run.part),
+        None => eprintln!("{} failed:", part_run.part),
+    }
+    for line in part_run.stderr.lines() {
+        eprintln!("    {line}");
+    }
+}
+
+/// Run every initialized day of each year, printing a table of the results of each, and then a
+/// summary of each year.
+fn run_years(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    years: &[Year],
+    opts: &aoctool::run::RunOpts,
+) -> Result<()> {
+    if let [year] = years {
+        let (failures, days) = run_year(host, config, settings, *year, opts)?;
+        if failures > 0 {
+            bail!("{failures} of {days} days failed");
+        }
+        return Ok(());
+    }
+
+    let mut summaries = Vec::new();
+    for &year in years {
+        println!("{year}:");
+        summaries.push((year, run_year(host, config, settings, year, opts)?));
+        println!();
+    }
+    println!("{:>4}  {:>4}  {:>6}", "year", "days", "failed");
+    for (year, (failures, days)) in &summaries {
+        println!("{year:>4}  {days:>4}  {failures:>6}");
+    }
+    let failures = summaries
+        .iter()
+        .map(|(_, (failures, _))| failures)
+        .sum::<usize>();
+    if failures > 0 {
+        let days = summaries.iter().map(|(_, (_, days))| days).sum::<usize>();
+        bail!("{failures} of {days} days failed");
+    }
+    Ok(())
+}
+
+/// Run every initialized day of a year, printing a table of the results.
+///
+/// Returns how many days failed, of how many were run.
+fn run_year(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: Year,
+    opts: &aoctool::run::RunOpts,
+) -> Result<(usize, usize)> {
+    println!(
+        "{:>3}  {:<20} {:<20} {:>9}  {:>10}  result",
+        "day", "part 1", "part 2", "time", "memory"
+    );
+    let answer = |run: &aoctool::run::DayRun, part: Part| match run.part(part) {
+        None => "-".to_string(),
+        Some(part_run) if !part_run.success => "error".to_string(),
+        Some(part_run) => part_run.answer.clone().unwrap_or_else(|| "?".to_string()),
+    };
+    let bar = progress_bar(aoctool::run::year_days(config, settings, year, opts).len());
+    bar.set_message("building");
+    let runs = aoctool::run::run_year(host, config, settings, year, opts, |run| {
+        bar.suspend(|| {
+            println!(
+                "{:>3}  {:<20} {:<20} {:>8.3}s  {:>10}  {}",
+                run.day,
+                answer(run, Part::One),
+                answer(run, Part::Two),
+                run.duration().as_secs_f64(),
+                run.peak_rss()
+                    .map(aoctool::run::format_bytes)
+                    .unwrap_or_else(|| "-".to_string()),
+                run.verdict(),
+            );
+            for part_run in &run.parts {
+                report_failure(part_run);
             }
-            Self::ClearTemplates { year } => {
-                let config = Config::load().unwrap_or_default();
-                aoctool::clear_templates(&config, year.year())?;
+        });
+        bar.set_message("running");
+        bar.inc(1);
+    })?;
+    bar.finish_and_clear();
+    let failures = runs
+        .iter()
+        .filter(|run| run.verdict() == aoctool::run::Verdict::Fail)
+        .count();
+    Ok((failures, runs.len()))
+}
+
+fn fetch_input(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: Year,
+    day: Day,
+    account: Option<&str>,
+    wait: bool,
+) -> Result<PathBuf> {
+    if wait {
+        refuse_to_wait(year, day)?;
+        let mut waited = false;
+        aoctool::unlock::wait_for_unlock(year, day, |remaining| {
+            waited = true;
+            eprint!(
+                "\rpuzzle unlocks in {}",
+                aoctool::unlock::format_countdown(remaining)
+            );
+        })?;
+        if waited {
+            eprintln!();
+        }
+    }
+    let fetch = || -> Result<PathBuf> {
+        Ok(match account {
+            Some(account) => {
+                aoctool::website::get_input_for_account(host, config, settings, year, day, account)?
             }
+            None => aoctool::website::get_input(host, config, year, day)?,
+        })
+    };
+    // the input may not be served for a moment after the unlock
+    let mut attempts = if wait { 10 } else { 1 };
+    loop {
+        attempts -= 1;
+        match fetch() {
+            Ok(path) => return Ok(path),
+            Err(err) if attempts == 0 => return Err(err),
+            Err(_) => std::thread::sleep(std::time::Duration::from_secs(1)),
         }
-        Ok(())
     }
 }
 
 #[derive(DeriveSubcommand, Debug)]
 enum ConfigOpts {
     /// Emit the path to the configuration file
-    Path,
+    Path {
+        /// Emit the path to the aoctool settings file instead
+        #[arg(long)]
+        settings: bool,
+
+        /// Emit the path to the project-local config file governing the current directory instead
+        #[arg(long, conflicts_with = "settings")]
+        local: bool,
+    },
     /// Display the contents of the configuration file, if they exist
-    Show,
+    ///
+    /// Session keys and tokens are redacted, so that the output can be shared.
+    Show {
+        /// Display the aoctool settings file instead
+        #[arg(long)]
+        settings: bool,
+
+        /// Reveal session keys and tokens
+        #[arg(long)]
+        show_secrets: bool,
+    },
+    /// Open the configuration file in `$VISUAL` or `$EDITOR`, and check it once saved
+    Edit {
+        /// Edit the aoctool settings file instead
+        #[arg(long)]
+        settings: bool,
+    },
+    /// Diagnose problems with the configuration, and suggest fixes
+    ///
+    /// Checks that the configured paths are writable, the session key authenticates, the day
+    /// templates render, and the clock agrees with the website's.
+    Doctor,
+    /// Report which user the session key belongs to, and whether it is still valid
+    Whoami {
+        /// Check the session key of this named account instead of the default
+        #[arg(short, long)]
+        account: Option<String>,
+    },
     /// Set configuration
     Set {
         #[command(flatten)]
@@ -137,6 +2996,35 @@ enum ConfigOpts {
         #[arg(short, long)]
         session: Option<String>,
 
+        /// Store the session key for this named account instead of the default
+        #[arg(short, long, requires = "session")]
+        account: Option<String>,
+
+        /// Keep the session key in the platform keychain, storing only a reference to it here
+        #[arg(long, requires = "session")]
+        keychain: bool,
+
+        /// Store the session key and paths in this named profile, creating it if necessary
+        ///
+        /// Defaults to the profile selected with `aoc --profile`, if any.
+        #[arg(long, value_name = "NAME", conflicts_with = "account")]
+        profile: Option<String>,
+
+        /// Clone this year's day templates from a git repository: `<url>[#<ref>]`
+        ///
+        /// The repository is cloned into the day templates path, and updated on each `init`.
+        #[arg(long)]
+        day_templates_git: Option<String>,
+
+        /// Day templates used for every year, beneath any per-year day templates
+        #[arg(long)]
+        global_day_templates: Option<PathBuf>,
+
+        /// Year used by commands when none is given, and none can be inferred from the current
+        /// directory
+        #[arg(long, value_name = "YEAR")]
+        default_year: Option<Year>,
+
         #[command(flatten)]
         path_opts: PathOpts,
     },
@@ -156,20 +3044,162 @@ enum ConfigOpts {
         /// Clear path to this year's day template files.
         #[arg(long)]
         day_template: bool,
+
+        /// Clear the git repository for this year's day templates.
+        #[arg(long)]
+        day_templates_git: bool,
+
+        /// Clear the global day templates path.
+        #[arg(long)]
+        global_day_templates: bool,
+
+        /// Clear the default year, so that commands default to this year.
+        #[arg(long)]
+        default_year: bool,
     },
 }
 
 impl ConfigOpts {
-    fn run(self) -> Result<()> {
+    fn run(self, host: &Host) -> Result<()> {
         match self {
-            Self::Path => println!("{}", aoclib::config::path().display()),
-            Self::Show => {
-                let data = std::fs::read_to_string(aoclib::config::path())?;
-                println!("{}", data);
+            Self::Path { settings, local } => {
+                let path = if local {
+                    match LOCAL_CONFIG.get().and_then(Option::as_ref) {
+                        Some(local) => local.path.clone(),
+                        None => bail!(
+                            "no {} in the current directory or its ancestors",
+                            aoctool::local::LOCAL_CONFIG_FILE
+                        ),
+                    }
+                } else if settings {
+                    aoctool::settings::path()
+                } else {
+                    aoclib::config::path()
+                };
+                output::emit(&serde_json::json!({ "path": path }), |_| {
+                    path.display().to_string()
+                })?;
+            }
+            Self::Show {
+                settings,
+                show_secrets,
+            } => {
+                let path = if settings {
+                    aoctool::settings::path()
+                } else {
+                    aoclib::config::path()
+                };
+                let mut data = std::fs::read_to_string(path)?;
+                if !show_secrets {
+                    data = aoctool::settings::redact_secrets(&data)?;
+                }
+                if output::is_json() {
+                    output::emit(&toml::from_str::<toml::Value>(&data)?, |_| String::new())?;
+                } else {
+                    println!("{}", data);
+                }
+            }
+            Self::Edit { settings } => {
+                let path = if settings {
+                    aoctool::settings::path()
+                } else {
+                    aoclib::config::path()
+                };
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                loop {
+                    let status = editor()?.arg(&path).status()?;
+                    if !status.success() {
+                        bail!("editor exited with {status}");
+                    }
+                    if !path.exists() {
+                        break;
+                    }
+                    let checked: Result<()> = if settings {
+                        Settings::load().map(drop).map_err(Into::into)
+                    } else {
+                        Config::load().map(drop).map_err(Into::into)
+                    };
+                    let Err(err) = checked else {
+                        break;
+                    };
+                    eprintln!("{} is invalid: {err:#}", path.display());
+                    if !confirm("edit it again?")? {
+                        bail!("left {} invalid", path.display());
+                    }
+                }
+            }
+            Self::Doctor => {
+                let config = with_workspace(Config::load().unwrap_or_default());
+                let checks = aoctool::doctor::diagnose(host, &config);
+                let records = checks
+                    .iter()
+                    .map(|check| match &check.outcome {
+                        aoctool::doctor::Outcome::Pass(note) => serde_json::json!({
+                            "subject": check.subject,
+                            "passed": true,
+                            "note": note,
+                        }),
+                        aoctool::doctor::Outcome::Fail { problem, fix } => serde_json::json!({
+                            "subject": check.subject,
+                            "passed": false,
+                            "problem": problem,
+                            "fix": fix,
+                        }),
+                    })
+                    .collect::<Vec<_>>();
+                output::emit(&records, |_| {
+                    let mut text = String::new();
+                    for check in &checks {
+                        match &check.outcome {
+                            aoctool::doctor::Outcome::Pass(note) => {
+                                text.push_str(&format!("ok    {}: {note}\n", check.subject))
+                            }
+                            aoctool::doctor::Outcome::Fail { problem, fix } => {
+                                text.push_str(&format!("FAIL  {}: {problem}\n", check.subject));
+                                text.push_str(&format!("      fix: {fix}\n"));
+                            }
+                        }
+                    }
+                    text
+                })?;
+                let failures = checks.iter().filter(|check| !check.passed()).count();
+                if failures > 0 {
+                    bail!("found {failures} problems");
+                }
+            }
+            Self::Whoami { account } => {
+                let session = match account {
+                    Some(account) => Settings::load()?
+                        .accounts
+                        .get(&account)
+                        .map(|session| aoctool::keychain::resolve(session))
+                        .ok_or(aoctool::Error::UnknownAccount(account))??,
+                    None => load_config()?.session,
+                };
+                if session.is_empty() {
+                    bail!("no session key configured; see `aoc config set --session`");
+                }
+                match aoctool::website::whoami(host.http.as_ref(), &session) {
+                    Ok(name) => output::emit(&serde_json::json!({ "name": name }), |_| {
+                        format!("logged in as {name}")
+                    })?,
+                    Err(aoctool::Error::NotLoggedIn) => {
+                        bail!("the session key is invalid or has expired; log in again to get a new one")
+                    }
+                    Err(err) => return Err(err.into()),
+                }
             }
             Self::Set {
                 year,
                 session,
+                account,
+                keychain,
+                profile,
+                day_templates_git,
+                global_day_templates,
+                default_year,
                 path_opts:
                     PathOpts {
                         input_files,
@@ -178,29 +3208,117 @@ impl ConfigOpts {
                     },
             } => {
                 let mut config = Config::load().unwrap_or_default();
-                if let Some(session) = session {
+                if let Some(session) = &session {
                     if session.is_empty() {
                         bail!("session key must not be empty");
                     }
-                    config.session = session;
                 }
-                if let Some(path) = input_files {
+                if let Some(path) = &input_files {
                     if path.exists() && !path.is_dir() {
                         bail!("input_files must be a directory");
                     }
-                    config.set_input_files(year.year(), path.absolutize()?.into_owned());
                 }
-                if let Some(path) = implementation {
+                if let Some(path) = &implementation {
                     if path.exists() && !path.is_dir() {
                         bail!("implementation must be a directory");
                     }
-                    config.set_implementation(year.year(), path.absolutize()?.into_owned());
                 }
-                if let Some(path) = day_templates {
+                if let Some(path) = &day_templates {
                     if path.exists() && !path.is_dir() {
                         bail!("day-templates must be a directory");
                     }
-                    config.set_day_template(year.year(), path.absolutize()?.into_owned());
+                }
+                let absolute = |path: Option<PathBuf>| -> Result<Option<PathBuf>> {
+                    Ok(match path {
+                        Some(path) => Some(path.absolutize()?.into_owned()),
+                        None => None,
+                    })
+                };
+                let (input_files, implementation, day_templates) = (
+                    absolute(input_files)?,
+                    absolute(implementation)?,
+                    absolute(day_templates)?,
+                );
+
+                let profile = profile.or_else(|| PROFILE.get().cloned().flatten());
+                let session = match session {
+                    Some(session) if keychain => {
+                        let name = match (&profile, &account) {
+                            (Some(profile), _) => format!("profile:{profile}"),
+                            (None, Some(account)) => format!("account:{account}"),
+                            (None, None) => "default".to_string(),
+                        };
+                        aoctool::keychain::store(&name, &session)?;
+                        Some(aoctool::keychain::reference(&name))
+                    }
+                    session => session,
+                };
+                if let Some(profile) = profile {
+                    let mut settings = Settings::load()?;
+                    let profile = settings.profiles.entry(profile).or_default();
+                    if session.is_some() {
+                        profile.session = session;
+                    }
+                    let paths = profile.years.entry(year.year()).or_default();
+                    if input_files.is_some() {
+                        paths.input_files = input_files;
+                    }
+                    if implementation.is_some() {
+                        paths.implementation = implementation;
+                    }
+                    if day_templates.is_some() {
+                        paths.day_template = day_templates;
+                    }
+                    // an empty entry would otherwise be saved for the year
+                    let empty = paths.input_files.is_none()
+                        && paths.implementation.is_none()
+                        && paths.day_template.is_none();
+                    if empty {
+                        profile.years.remove(&year.year());
+                    }
+                    settings.save()?;
+                } else {
+                    if let Some(session) = session {
+                        match account {
+                            Some(account) => {
+                                let mut settings = Settings::load()?;
+                                settings.accounts.insert(account, session);
+                                settings.save()?;
+                            }
+                            None => config.session = session,
+                        }
+                    }
+                    if let Some(path) = input_files {
+                        config.set_input_files(year.year(), path);
+                    }
+                    if let Some(path) = implementation {
+                        config.set_implementation(year.year(), path);
+                    }
+                    if let Some(path) = day_templates {
+                        config.set_day_template(year.year(), path);
+                    }
+                }
+                if let Some(path) = global_day_templates {
+                    if path.exists() && !path.is_dir() {
+                        bail!("global-day-templates must be a directory");
+                    }
+                    let mut settings = Settings::load()?;
+                    settings.day_templates = Some(path.absolutize()?.into_owned());
+                    settings.save()?;
+                }
+                if let Some(default_year) = default_year {
+                    let mut settings = Settings::load()?;
+                    settings.default_year = Some(default_year);
+                    settings.save()?;
+                }
+                if let Some(source) = day_templates_git {
+                    let mut settings = Settings::load()?;
+                    settings
+                        .years
+                        .entry(year.year())
+                        .or_default()
+                        .day_templates_git = Some(source);
+                    settings.save()?;
                 }
                 config.save()?;
             }
@@ -209,6 +3327,9 @@ impl ConfigOpts {
                 input_files,
                 implementation,
                 day_template,
+                day_templates_git,
+                global_day_templates,
+                default_year,
             } => {
                 let mut config = Config::load().unwrap_or_default();
                 let paths = config.paths.entry(year.year()).or_default();
@@ -221,6 +3342,21 @@ impl ConfigOpts {
                 if day_template {
                     paths.day_template = None;
                 }
+                if day_templates_git || global_day_templates || default_year {
+                    let mut settings = Settings::load()?;
+                    if day_templates_git {
+                        if let Some(year_settings) = settings.years.get_mut(&year.year()) {
+                            year_settings.day_templates_git = None;
+                        }
+                    }
+                    if global_day_templates {
+                        settings.day_templates = None;
+                    }
+                    if default_year {
+                        settings.default_year = None;
+                    }
+                    settings.save()?;
+                }
                 config.save()?;
             }
         }
@@ -228,8 +3364,401 @@ impl ConfigOpts {
     }
 }
 
+/// Offer the configured years as the values of every `--year` argument, for completion.
+fn with_year_values(mut command: clap::Command, years: &[String]) -> clap::Command {
+    let subcommands = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect::<Vec<_>>();
+    for name in subcommands {
+        command = command.mut_subcommand(name, |subcommand| with_year_values(subcommand, years));
+    }
+    let has_arg = |command: &clap::Command, id: &str| {
+        command
+            .get_arguments()
+            .any(|arg| arg.get_id().as_str() == id)
+    };
+    if has_arg(&command, "year") {
+        command = command.mut_arg("year", |arg| {
+            arg.value_parser(clap::builder::PossibleValuesParser::new(years.to_vec()))
+        });
+    }
+    if has_arg(&command, "years") {
+        let values = years.iter().cloned().chain(["all".to_string()]);
+        command = command.mut_arg("years", |arg| {
+            arg.value_parser(clap::builder::PossibleValuesParser::new(values))
+        });
+    }
+    command
+}
+
+/// Insert the flags configured in `[defaults.<subcommand>]` after the subcommand's name, except
+/// those given already, and those which conflict with one given. The defaults of a nested
+/// subcommand are a table within its parent's, as in `[defaults.stars.sync]`.
+///
+/// This runs before logging is set up, so warnings about the defaults are returned for the caller
+/// to log.
+fn with_default_flags(
+    mut args: Vec<OsString>,
+    settings: &Settings,
+) -> (Vec<OsString>, Vec<String>) {
+    let cli = Cli::command();
+    // invalid arguments are left for clap to report
+    let Ok(cli_matches) = cli.clone().try_get_matches_from(&args) else {
+        return (args, Vec::new());
+    };
+    let Some(defaults) = cli_matches
+        .subcommand_name()
+        .and_then(|name| settings.defaults.get(name))
+    else {
+        return (args, Vec::new());
+    };
+    let mut defaults = defaults.clone().into_iter().collect::<toml::Table>();
+
+    let mut warnings = Vec::new();
+    let mut insertions = Vec::new();
+    let mut path = Vec::new();
+    let (mut command, mut matches) = (&cli, &cli_matches);
+    while let Some((name, sub_matches)) = matches.subcommand() {
+        let subcommand = command
+            .find_subcommand(name)
+            .expect("matched subcommand exists");
+        path.push(name);
+        let Some(position) = subcommand_position(&cli, &args, &path) else {
+            break;
+        };
+        let (flags, nested) = default_flags(
+            subcommand,
+            sub_matches,
+            &path.join(" "),
+            &defaults,
+            &mut warnings,
+        );
+        insertions.push((position + 1, flags));
+        match nested {
+            Some(nested) => defaults = nested,
+            None => break,
+        }
+        (command, matches) = (subcommand, sub_matches);
+    }
+
+    // from the last, so that the positions of the earlier insertions still hold
+    for (position, flags) in insertions.into_iter().rev() {
+        args.splice(position..position, flags.into_iter().map(OsString::from));
+    }
+    (args, warnings)
+}
+
+/// The position in `args` of the name of the last subcommand of `path`, where each is a
+/// subcommand of the one before: the end of the shortest prefix of `args` which clap parses as
+/// running them all.
+fn subcommand_position(cli: &clap::Command, args: &[OsString], path: &[&str]) -> Option<usize> {
+    (1..args.len()).find(|&end| {
+        let Ok(matches) = cli
+            .clone()
+            .ignore_errors(true)
+            .try_get_matches_from(&args[..=end])
+        else {
+            return false;
+        };
+        let mut matches = &matches;
+        path.iter().all(|name| match matches.subcommand() {
+            Some((found, sub_matches)) if found == *name => {
+                matches = sub_matches;
+                true
+            }
+            _ => false,
+        })
+    })
+}
+
+/// The flags configured in `defaults` for a subcommand, which `matches` are of, and the defaults
+/// of the nested subcommand it runs, if any.
+fn default_flags(
+    subcommand: &clap::Command,
+    matches: &clap::ArgMatches,
+    name: &str,
+    defaults: &toml::Table,
+    warnings: &mut Vec<String>,
+) -> (Vec<String>, Option<toml::Table>) {
+    let given = subcommand
+        .get_arguments()
+        .filter(|arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .collect::<Vec<_>>();
+    let conflicting = |arg: &clap::Arg, other: &clap::Arg| {
+        let conflicts = |arg: &clap::Arg, other: &clap::Arg| {
+            subcommand
+                .get_arg_conflicts_with(arg)
+                .iter()
+                .any(|conflict| conflict.get_id() == other.get_id())
+        };
+        conflicts(arg, other) || conflicts(other, arg)
+    };
+    let literal = |value: &toml::Value| match value {
+        toml::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    };
+
+    let mut flags = Vec::new();
+    let mut nested = None;
+    for (key, value) in defaults {
+        if let toml::Value::Table(table) = value {
+            if subcommand.find_subcommand(key).is_some() {
+                if matches.subcommand_name() == Some(key.as_str()) {
+                    nested = Some(table.clone());
+                }
+                continue;
+            }
+        }
+        let long = key.replace('_', "-");
+        let Some(arg) = subcommand
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(long.as_str()))
+        else {
+            warnings.push(format!(
+                "ignoring default for unknown flag `--{long}` of `{name}`"
+            ));
+            continue;
+        };
+        if given.iter().any(|given| given.get_id() == arg.get_id()) {
+            continue;
+        }
+        if given.iter().any(|given| conflicting(arg, given)) {
+            continue;
+        }
+        match value {
+            toml::Value::Boolean(true) => flags.push(format!("--{long}")),
+            toml::Value::Boolean(false) => {}
+            toml::Value::Array(values) => flags.extend(
+                values
+                    .iter()
+                    .map(|value| format!("--{long}={}", literal(value))),
+            ),
+            value => flags.push(format!("--{long}={}", literal(value))),
+        }
+    }
+    (flags, nested)
+}
+
+/// Log to stderr: warnings by default, nothing but errors when `quiet`, and more with each
+/// `verbose`; in color, if `ansi`.
+fn init_logging(verbose: u8, quiet: bool, ansi: bool) {
+    use tracing::level_filters::LevelFilter;
+    let level = match (quiet, verbose) {
+        (true, _) => LevelFilter::ERROR,
+        (false, 0) => LevelFilter::WARN,
+        (false, 1) => LevelFilter::INFO,
+        (false, 2) => LevelFilter::DEBUG,
+        (false, _) => LevelFilter::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .with_ansi(ansi)
+        .without_time()
+        .init();
+}
+
 fn main() -> Result<()> {
-    color_eyre::install()?;
-    let opt = Subcommand::parse();
-    opt.run()
+    let (args, warnings) = with_default_flags(
+        std::env::args_os().collect(),
+        &Settings::load().unwrap_or_default(),
+    );
+    let cli = Cli::parse_from(args);
+    let theme = if cli.non_interactive {
+        color_eyre::config::Theme::new()
+    } else {
+        color_eyre::config::Theme::dark()
+    };
+    color_eyre::config::HookBuilder::default()
+        .theme(theme)
+        .install()?;
+    init_logging(cli.verbose, cli.quiet, !cli.non_interactive);
+    for warning in warnings {
+        tracing::warn!("{warning}");
+    }
+    if let Some(name) = cli.cmd.without_json().filter(|_| cli.json) {
+        bail!("`{name}` has no results to print as JSON; run it without --json");
+    }
+    output::set_json(cli.json);
+    output::set_interactive(!cli.non_interactive);
+    aoctool::env::year()?;
+    LOCAL_CONFIG
+        .set(LocalConfig::discover()?)
+        .expect("project-local config is discovered only once");
+    if let Some(profile) = &cli.profile {
+        Settings::load()?.profile(profile)?;
+    }
+    PROFILE
+        .set(cli.profile)
+        .expect("profile is selected only once");
+    cli.cmd.run(&Host::real())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("aoc").chain(args.iter().copied()))
+    }
+
+    fn days(list: &str) -> Result<Vec<Day>, String> {
+        let ranges = list
+            .split(',')
+            .map(DayRange::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(expand_days(&ranges))
+    }
+
+    #[test]
+    fn day_ranges() {
+        assert_eq!(
+            days("1-10,13").unwrap(),
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 13]
+        );
+        assert_eq!(days("13,1-3,2").unwrap(), [1, 2, 3, 13]);
+        assert_eq!(days("5-5").unwrap(), [5]);
+        assert_eq!(days("25").unwrap(), [25]);
+        for list in [
+            "10-1", "0", "26", "0-3", "20-26", "1-10,,13", "", "-3", "3-", "x",
+        ] {
+            assert!(days(list).is_err(), "{list}");
+        }
+    }
+
+    #[test]
+    fn positional_dates() {
+        let date = |s: &str| PositionalDate::from_str(s).map(|date| (date.year, date.day));
+        assert_eq!(date("2024/05").unwrap(), (Some(2024), Some(5)));
+        assert_eq!(date("2024/5").unwrap(), (Some(2024), Some(5)));
+        // a lone number is a day if it can be one, and otherwise a year
+        assert_eq!(date("5").unwrap(), (None, Some(5)));
+        assert_eq!(date("25").unwrap(), (None, Some(25)));
+        assert_eq!(date("2024").unwrap(), (Some(2024), None));
+        for s in [
+            "0", "26", "2014", "2024/0", "2024/26", "5/2024", "2024/", "",
+        ] {
+            assert!(date(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn dates_given_positionally() {
+        let date = |args: &[&str]| {
+            let Subcommand::Url { date } = parse(args).unwrap().cmd else {
+                unreachable!("parsed a url command");
+            };
+            (date.year(), date.day())
+        };
+        assert_eq!(date(&["url", "2024", "5"]), (2024, 5));
+        assert_eq!(date(&["url", "2024/05"]), (2024, 5));
+        assert_eq!(date(&["url", "--year", "2023", "--day", "7"]), (2023, 7));
+        assert!(parse(&["url", "2024", "5", "--day", "6"]).is_err());
+        assert!(parse(&["url", "26"]).is_err());
+    }
+
+    /// The arguments with default flags from these settings, and the warnings about them.
+    fn with_defaults(args: &[&str], defaults: &str) -> (Vec<String>, Vec<String>) {
+        let settings = Settings {
+            defaults: toml::from_str(defaults).unwrap(),
+            ..Settings::default()
+        };
+        let args = std::iter::once("aoc")
+            .chain(args.iter().copied())
+            .map(OsString::from)
+            .collect();
+        let (args, warnings) = with_default_flags(args, &settings);
+        let args = args
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect();
+        (args, warnings)
+    }
+
+    #[test]
+    fn default_flags_follow_their_subcommand() {
+        let defaults = "[run]\nrelease = true\nmin-runs = 3\n";
+        let (args, warnings) = with_defaults(&["--json", "run", "--day", "5"], defaults);
+        assert_eq!(
+            args,
+            [
+                "aoc",
+                "--json",
+                "run",
+                "--min-runs=3",
+                "--release",
+                "--day",
+                "5"
+            ]
+        );
+        assert!(warnings.is_empty(), "{warnings:?}");
+
+        // the profile is named like the subcommand, which is the second `run`
+        let (args, _) = with_defaults(&["--profile", "run", "run"], defaults);
+        assert_eq!(
+            args,
+            [
+                "aoc",
+                "--profile",
+                "run",
+                "run",
+                "--min-runs=3",
+                "--release"
+            ]
+        );
+
+        // positional arguments after `--` stay after it
+        let (args, _) = with_defaults(&["run", "--", "2024/05"], defaults);
+        assert_eq!(
+            args,
+            ["aoc", "run", "--min-runs=3", "--release", "--", "2024/05"]
+        );
+    }
+
+    #[test]
+    fn default_flags_yield_to_those_given() {
+        let defaults = "[run]\nrelease = true\nmin-runs = 3\nno-part1 = false\n";
+        let (args, _) = with_defaults(&["run", "--min-runs", "10"], defaults);
+        assert_eq!(args, ["aoc", "run", "--release", "--min-runs", "10"]);
+
+        // `--compare-profiles` conflicts with `--release`
+        let (args, _) = with_defaults(&["run", "--compare-profiles"], defaults);
+        assert_eq!(args, ["aoc", "run", "--min-runs=3", "--compare-profiles"]);
+    }
+
+    #[test]
+    fn default_flags_of_nested_subcommands() {
+        let defaults = "[stars.sync]\nyear = \"all\"\n";
+        let (args, _) = with_defaults(&["stars", "sync"], defaults);
+        assert_eq!(args, ["aoc", "stars", "sync", "--year=all"]);
+        let (args, _) = with_defaults(&["stars", "sync", "--year", "2024"], defaults);
+        assert_eq!(args, ["aoc", "stars", "sync", "--year", "2024"]);
+    }
+
+    #[test]
+    fn default_flags_which_cant_apply() {
+        let (args, warnings) = with_defaults(&["run"], "[run]\nbogus = true\n");
+        assert_eq!(args, ["aoc", "run"]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("--bogus"), "{warnings:?}");
+
+        // invalid arguments are left alone, for clap to report
+        let (args, warnings) = with_defaults(&["run", "--not-a-flag"], "[run]\nrelease = true\n");
+        assert_eq!(args, ["aoc", "run", "--not-a-flag"]);
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+
+    #[test]
+    fn jobs_need_several_days() {
+        parse(&["run", "--all", "-j", "4"]).unwrap();
+        parse(&["run", "--days", "1-10", "-j", "4"]).unwrap();
+        let err = parse(&["run", "--day", "5", "-j", "4"]).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            clap::error::ErrorKind::MissingRequiredArgument,
+            "{err}"
+        );
+    }
 }
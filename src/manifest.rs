@@ -0,0 +1,321 @@
+//! Editing the workspace manifest, the `Cargo.toml` of the implementation directory.
+//!
+//! Edits are made to a parsed [`Document`] in memory, so that formatting and comments survive, and
+//! written back with [`write`].
+
+use aoclib::config::Config;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use toml_edit::{Array, Document, Item, Table, Value};
+
+use crate::{
+    templates::{License, PackageField},
+    vfs::Fs,
+    Error,
+};
+
+/// Package fields, with their values, which a day crate sets for itself where `[workspace.package]`
+/// doesn't provide them: without an `edition`, cargo builds a crate as edition 2015.
+pub(crate) const PACKAGE_DEFAULTS: &[(&str, &str)] = &[("version", "0.1.0"), ("edition", "2021")];
+
+/// Dependencies of the stock day template, declared once in `[workspace.dependencies]` by
+/// `init-year`.
+const WORKSPACE_DEPENDENCIES: &str = r#"
+aoclib = { git = "https://github.com/coriolinus/aoclib.git" }
+clap = { version = "4.4.11", features = ["derive"] }
+color-eyre = "0.5.10"
+thiserror = "1.0.50"
+"#;
+
+/// Get `Cargo.toml` of the implementation directory.
+///
+/// Return its path and the parsed `Document`.
+pub(crate) fn get_cargo_toml(
+    fs: &dyn Fs,
+    config: &Config,
+    year: u32,
+) -> Result<(PathBuf, Document), Error> {
+    // parse the local Cargo.toml to discover if we're in the right place
+    let cargo_toml_path = config.implementation(year).join("Cargo.toml");
+    if !fs.exists(&cargo_toml_path) {
+        Err(Error::NoCargoToml)?;
+    }
+    let manifest = fs
+        .read(&cargo_toml_path)
+        .map_err(|err| Error::Io("reading Cargo.toml", err))?;
+    let manifest = Document::from_str(&String::from_utf8_lossy(&manifest))?;
+
+    Ok((cargo_toml_path, manifest))
+}
+
+/// Write a manifest back to its `Cargo.toml`.
+pub(crate) fn write(fs: &dyn Fs, cargo_toml_path: &Path, manifest: &Document) -> Result<(), Error> {
+    tracing::info!(path = %cargo_toml_path.display(), "editing manifest");
+    fs.write(cargo_toml_path, manifest.to_string().as_bytes())
+        .map_err(|err| Error::Io("writing updated Cargo.toml", err))
+}
+
+/// A table within `[workspace]` of a manifest, created if it does not exist.
+fn workspace_table<'a>(manifest: &'a mut Document, name: &str) -> Result<&'a mut Table, Error> {
+    manifest
+        .as_table_mut()
+        .entry("workspace")
+        .or_insert(Item::Table(Table::new()))
+        .as_table_mut()
+        .ok_or(Error::MalformedToml)?
+        .entry(name)
+        .or_insert(Item::Table(Table::new()))
+        .as_table_mut()
+        .ok_or(Error::MalformedToml)
+}
+
+/// Add a crate to the workspace members of a manifest, in memory.
+pub(crate) fn add_crate_to_manifest(
+    manifest: &mut Document,
+    crate_name: &str,
+) -> Result<(), Error> {
+    let root_table = manifest.as_table_mut();
+
+    let workspace = root_table
+        .entry("workspace")
+        .or_insert(Item::Table(Table::new()));
+    let workspace = workspace.as_table_mut().ok_or(Error::MalformedToml)?;
+
+    let members = workspace
+        .entry("members")
+        .or_insert(Item::Value(Value::Array(Array::new())));
+    let members = members
+        .as_value_mut()
+        .ok_or(Error::MalformedToml)?
+        .as_array_mut()
+        .ok_or(Error::MalformedToml)?;
+
+    if members.iter().any(|item| {
+        item.as_str()
+            .map(|item_str| item_str == crate_name)
+            .unwrap_or_default()
+    }) {
+        Err(Error::CrateAlreadyExists(crate_name.to_string()))?;
+    }
+
+    // a crate matched by a glob member is already a member; listing it as well would be redundant
+    let covered = members
+        .iter()
+        .filter_map(|item| item.as_str())
+        .any(|member| glob_matches(member, crate_name));
+    if !covered {
+        insert_sorted(members, crate_name);
+    }
+    Ok(())
+}
+
+/// Insert a member into a members array before the first which sorts after it, keeping the array's
+/// formatting.
+///
+/// In an array with one member per line, the new member is indented like its neighbours.
+fn insert_sorted(members: &mut Array, member: &str) {
+    let index = members
+        .iter()
+        .position(|item| item.as_str().is_some_and(|existing| existing > member))
+        .unwrap_or(members.len());
+    let prefix = |item: &Value| {
+        item.decor()
+            .prefix()
+            .and_then(|prefix| prefix.as_str())
+            .unwrap_or_default()
+            .to_owned()
+    };
+    let multiline = members.iter().any(|item| prefix(item).contains('\n'));
+
+    if multiline {
+        // only the indentation: any comment above the neighbour belongs to it
+        let indentation = members
+            .get(index)
+            .or_else(|| members.get(members.len().saturating_sub(1)))
+            .map(|item| {
+                let prefix = prefix(item);
+                prefix[prefix.rfind('\n').map_or(0, |newline| newline + 1)..].to_owned()
+            })
+            .unwrap_or_default();
+        let mut value = Value::from(member);
+        value.decor_mut().set_prefix(format!("\n{indentation}"));
+        members.insert_formatted(index, value);
+    } else {
+        members.insert(index, member);
+        members.fmt();
+    }
+}
+
+/// Whether a workspace member glob, like `"day*"`, matches a path.
+///
+/// As in cargo, `*` matches any run of characters and `?` any one character, within a single path
+/// component. A member without either only matches itself.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    if !glob.contains(['*', '?']) {
+        return glob == path;
+    }
+    let pattern = regex::escape(glob)
+        .replace(r"\*", "[^/]*")
+        .replace(r"\?", "[^/]");
+    regex::Regex::new(&format!("^{pattern}$"))
+        .map(|pattern| pattern.is_match(path))
+        .unwrap_or_default()
+}
+
+/// Replace the workspace members of a manifest matched by a glob with the glob itself, in memory.
+///
+/// Returns whether the members changed.
+pub(crate) fn glob_members(manifest: &mut Document, glob: &str) -> Result<bool, Error> {
+    let members = manifest
+        .as_table_mut()
+        .entry("workspace")
+        .or_insert(Item::Table(Table::new()))
+        .as_table_mut()
+        .ok_or(Error::MalformedToml)?
+        .entry("members")
+        .or_insert(Item::Value(Value::Array(Array::new())))
+        .as_array_mut()
+        .ok_or(Error::MalformedToml)?;
+
+    let before = members.len();
+    members.retain(|item| {
+        item.as_str()
+            .map(|member| member == glob || !glob_matches(glob, member))
+            .unwrap_or(true)
+    });
+    let removed = members.len() != before;
+    let present = members.iter().any(|item| item.as_str() == Some(glob));
+    if !present {
+        members.push(glob);
+    }
+    Ok(removed || !present)
+}
+
+/// Remove a crate from the workspace members of a manifest, in memory.
+///
+/// Returns whether it was a member.
+pub(crate) fn remove_crate_from_manifest(
+    manifest: &mut Document,
+    crate_name: &str,
+) -> Result<bool, Error> {
+    let Some(members) = manifest
+        .as_table_mut()
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.get_mut("members"))
+    else {
+        return Ok(false);
+    };
+    let members = members.as_array_mut().ok_or(Error::MalformedToml)?;
+    let before = members.len();
+    members.retain(|item| item.as_str() != Some(crate_name));
+    Ok(members.len() != before)
+}
+
+pub(crate) fn add_crate_to_workspace(
+    fs: &dyn Fs,
+    cargo_toml_path: &Path,
+    manifest: &mut Document,
+    crate_name: &str,
+) -> Result<(), Error> {
+    add_crate_to_manifest(manifest, crate_name)?;
+    write(fs, cargo_toml_path, manifest)
+}
+
+/// Add any of [`WORKSPACE_DEPENDENCIES`] missing from `[workspace.dependencies]` of a manifest,
+/// in memory.
+///
+/// Returns whether any were added.
+pub(crate) fn add_workspace_dependencies(manifest: &mut Document) -> Result<bool, Error> {
+    let defaults = Document::from_str(WORKSPACE_DEPENDENCIES)?;
+    let dependencies = workspace_table(manifest, "dependencies")?;
+
+    let mut added = false;
+    for (name, dependency) in defaults.iter() {
+        if !dependencies.contains_key(name) {
+            dependencies.insert(name, dependency.clone());
+            added = true;
+        }
+    }
+    Ok(added)
+}
+
+/// Add any missing metadata to `[workspace.package]` of a manifest, in memory, for day crates to
+/// inherit.
+///
+/// The `version` and `edition` are always set. The `authors` are set from the git configuration
+/// if it has a `user.name`, and the `license` if one was chosen.
+///
+/// Returns whether any were added.
+pub(crate) fn add_workspace_package(
+    manifest: &mut Document,
+    license: Option<License>,
+) -> Result<bool, Error> {
+    let author = crate::git::config_value("user.name").map(|name| {
+        match crate::git::config_value("user.email") {
+            Some(email) => format!("{name} <{email}>"),
+            None => name,
+        }
+    });
+
+    let mut fields = PACKAGE_DEFAULTS
+        .iter()
+        .map(|&(key, value)| (key, Value::from(value)))
+        .collect::<Vec<_>>();
+    if let Some(author) = author {
+        fields.push(("authors", Value::Array(Array::from_iter([author]))));
+    }
+    if let Some(license) = license {
+        fields.push(("license", Value::from(license.spdx())));
+    }
+
+    let package = workspace_table(manifest, "package")?;
+    let mut added = false;
+    for (key, value) in fields {
+        if !package.contains_key(key) {
+            package.insert(key, Item::Value(value));
+            added = true;
+        }
+    }
+    Ok(added)
+}
+
+/// The keys of `[workspace.package]` in a workspace's `Cargo.toml`, which day crates may inherit.
+pub(crate) fn workspace_package_keys(fs: &dyn Fs, workspace: &Path) -> Vec<String> {
+    fs.read_to_string(&workspace.join("Cargo.toml"))
+        .ok()
+        .and_then(|manifest| toml::from_str::<toml::Table>(&manifest).ok())
+        .and_then(|manifest| {
+            let package = manifest.get("workspace")?.get("package")?.as_table()?;
+            Some(package.keys().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+/// The [default](PACKAGE_DEFAULTS) package fields which a day crate can't inherit, because
+/// `[workspace.package]` lacks them.
+pub(crate) fn package_defaults(inherited: &[String]) -> Vec<PackageField> {
+    PACKAGE_DEFAULTS
+        .iter()
+        .filter(|(key, _)| !inherited.iter().any(|inherited| inherited == key))
+        .map(|&(key, value)| PackageField {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+        .collect()
+}
+
+/// Whether a workspace's `Cargo.toml` has `[workspace.dependencies]`.
+pub(crate) fn has_workspace_dependencies(fs: &dyn Fs, workspace: &Path) -> bool {
+    fs.read_to_string(&workspace.join("Cargo.toml"))
+        .ok()
+        .and_then(|manifest| toml::from_str::<toml::Table>(&manifest).ok())
+        .and_then(|manifest| {
+            manifest
+                .get("workspace")?
+                .get("dependencies")
+                .map(toml::Value::is_table)
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,217 @@
+//! Move an existing repository's solutions and inputs into the layout `aoctool` expects.
+//!
+//! Where [`adopt`](crate::adopt) configures `aoctool` to fit the repository, this changes the
+//! repository to fit `aoctool`.
+
+use aoclib::config::Config;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use toml_edit::{Document, Item};
+
+use crate::{
+    adopt::{infer_day_name, INPUT_DIRS},
+    day_dir,
+    manifest::{self, add_crate_to_manifest},
+    settings::Settings,
+    vfs::Fs,
+    DayOpts, Error, Host, PathOpts, YearOpts,
+};
+
+/// A file moved by [`migrate`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Move {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// What [`migrate`] changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Migration {
+    pub moves: Vec<Move>,
+    /// Day crates added to the workspace.
+    pub crates: Vec<String>,
+}
+
+/// Migrate a repository of a year's solutions into the configured layout.
+///
+/// - The repository is configured as the year's implementation, as with `init-year`.
+/// - Each day binary in `src/bin`, such as `src/bin/day05.rs`, becomes the `src/main.rs` of its
+///   own day crate, with the dependencies of the root package, and any `[[bin]]` entry for it is
+///   removed from the root manifest.
+/// - Each day's input in `inputs/`, `input/`, or `data/`, or their subdirectory for the year, such
+///   as `inputs/2020/05.txt`, moves to where `aoctool` expects to find it.
+///
+/// Files are never overwritten: if a destination already exists, its source is left in place.
+pub fn migrate(
+    host: &Host,
+    config: &mut Config,
+    settings: &mut Settings,
+    repository: &Path,
+    year: u32,
+) -> Result<Migration, Error> {
+    let cargo_toml_path = repository.join("Cargo.toml");
+    let fs = host.fs.as_ref();
+    if !fs.exists(&cargo_toml_path) {
+        return Err(Error::NoCargoToml);
+    }
+    let path_opts = PathOpts {
+        input_files: None,
+        implementation: Some(repository.to_owned()),
+        day_templates: None,
+    };
+    crate::initialize_year(host, config, settings, year, path_opts, YearOpts::default())?;
+
+    let mut migration = Migration::default();
+    migrate_binaries(fs, config, settings, year, &cargo_toml_path, &mut migration)?;
+    migrate_inputs(fs, config, repository, year, &mut migration)?;
+    Ok(migration)
+}
+
+/// Move each day binary into its own crate.
+fn migrate_binaries(
+    fs: &dyn Fs,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    cargo_toml_path: &Path,
+    migration: &mut Migration,
+) -> Result<(), Error> {
+    let repository = config.implementation(year);
+    let bins = day_files(fs, &repository.join("src").join("bin"), year)?;
+    if bins.is_empty() {
+        return Ok(());
+    }
+
+    let mut manifest: Document = fs
+        .read_to_string(cargo_toml_path)
+        .map_err(|err| Error::Io("reading Cargo.toml", err))?
+        .parse()?;
+    let edition = manifest
+        .get("package")
+        .and_then(|package| package.get("edition"))
+        .and_then(Item::as_str)
+        .unwrap_or("2021")
+        .to_string();
+    let dependencies = manifest.get("dependencies").cloned();
+
+    let day_opts = DayOpts::default();
+    for (day, from) in bins {
+        let package_name = settings.day_name(year, day);
+        let dir = day_dir(config, settings, year, day, &day_opts);
+        let to = dir.join("src").join("main.rs");
+        if fs.exists(&to) {
+            continue;
+        }
+        fs.create_dir_all(&dir.join("src"))
+            .map_err(|err| Error::Io("creating day crate", err))?;
+        fs.rename(&from, &to)
+            .map_err(|err| Error::Io("moving day binary", err))?;
+
+        let day_cargo_toml = dir.join("Cargo.toml");
+        if !fs.exists(&day_cargo_toml) {
+            let mut day_manifest = Document::new();
+            day_manifest["package"] = toml_edit::table();
+            day_manifest["package"]["name"] = toml_edit::value(package_name.as_str());
+            day_manifest["package"]["version"] = toml_edit::value("0.1.0");
+            day_manifest["package"]["edition"] = toml_edit::value(edition.as_str());
+            if let Some(dependencies) = &dependencies {
+                day_manifest["dependencies"] = dependencies.clone();
+            }
+            fs.write(&day_cargo_toml, day_manifest.to_string().as_bytes())
+                .map_err(|err| Error::Io("writing day Cargo.toml", err))?;
+        }
+
+        let member = day_opts.day_dir(&package_name);
+        match add_crate_to_manifest(&mut manifest, &member.to_string_lossy()) {
+            Ok(()) | Err(Error::CrateAlreadyExists(_)) => {}
+            Err(err) => return Err(err),
+        }
+        remove_bin_target(&mut manifest, &repository, &from);
+
+        migration.crates.push(package_name);
+        migration.moves.push(Move { from, to });
+    }
+
+    manifest::write(fs, cargo_toml_path, &manifest)
+}
+
+/// Remove the `[[bin]]` entry of a binary from a manifest, if it has one.
+fn remove_bin_target(manifest: &mut Document, repository: &Path, bin: &Path) {
+    let Some(targets) = manifest
+        .get_mut("bin")
+        .and_then(Item::as_array_of_tables_mut)
+    else {
+        return;
+    };
+    let relative = bin.strip_prefix(repository).unwrap_or(bin);
+    let stem = bin.file_stem().and_then(|stem| stem.to_str());
+    targets.retain(|target| match target.get("path").and_then(Item::as_str) {
+        Some(path) => Path::new(path) != relative,
+        None => target.get("name").and_then(Item::as_str) != stem,
+    });
+    if targets.is_empty() {
+        manifest.remove("bin");
+    }
+}
+
+/// Move each day's input to where it is expected.
+fn migrate_inputs(
+    fs: &dyn Fs,
+    config: &Config,
+    repository: &Path,
+    year: u32,
+    migration: &mut Migration,
+) -> Result<(), Error> {
+    let dirs = INPUT_DIRS.iter().flat_map(|dir| {
+        let dir = repository.join(dir);
+        [dir.join(year.to_string()), dir]
+    });
+    for dir in dirs {
+        for (day, from) in day_files(fs, &dir, year)? {
+            let to = config.input_for(year, day);
+            if fs.exists(&to) {
+                continue;
+            }
+            if let Some(parent) = to.parent() {
+                fs.create_dir_all(parent)
+                    .map_err(|err| Error::Io("creating inputs dir", err))?;
+            }
+            fs.rename(&from, &to)
+                .map_err(|err| Error::Io("moving input", err))?;
+            migration.moves.push(Move { from, to });
+        }
+    }
+    Ok(())
+}
+
+/// The files directly within a directory whose names identify a day, in order of day.
+///
+/// Files whose names mention a year other than `year` are ignored. A missing directory has no such
+/// files.
+fn day_files(fs: &dyn Fs, dir: &Path, year: u32) -> Result<Vec<(u8, PathBuf)>, Error> {
+    if !fs.is_dir(dir) {
+        return Ok(Vec::new());
+    }
+    let other_year = Regex::new(r"20\d\d").expect("year pattern is valid");
+    let mut files = Vec::new();
+    for path in fs
+        .read_dir(dir)
+        .map_err(|err| Error::Io("reading dir", err))?
+    {
+        if !fs.is_file(&path) {
+            continue;
+        }
+        let day = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| infer_day_name(stem, year))
+            .filter(|(pattern, _)| !other_year.is_match(pattern))
+            .map(|(_, day)| day);
+        if let Some(day) = day {
+            files.push((day, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
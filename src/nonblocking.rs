@@ -0,0 +1,258 @@
+//! Async requests of adventofcode.com, for embedding in async applications, and for making many
+//! requests at once.
+//!
+//! The blocking functions of [`website`](crate::website) and [`leaderboard`](crate::leaderboard)
+//! wrap these, with [`block_on`]. Every request goes through the given [`HttpClient`].
+
+use aoclib::config::Config;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use std::{collections::BTreeMap, future::Future, path::PathBuf, sync::OnceLock};
+
+use crate::{http::HttpClient, settings::Settings, website, Error, Host};
+
+/// How many requests [`get_inputs`] makes at once; the website is run by one person, and asks to
+/// be treated gently.
+pub const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// The runtime on which [`block_on`] runs futures, built once.
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        // the futures run on the threads which block on them; the worker drives their IO
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("async runtime can be built")
+    })
+}
+
+/// Run a future to completion on aoctool's own runtime, blocking this thread until it is done.
+///
+/// This may be called from several threads at once, and from within an async context: a runtime
+/// can't be blocked on from a thread which is running one, so the future is then run on another
+/// thread. That still blocks the calling thread, so async code should prefer the functions of
+/// this module where they exist, or else call blocking functions from `spawn_blocking`.
+pub fn block_on<F>(future: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    if tokio::runtime::Handle::try_current().is_err() {
+        return runtime().block_on(future);
+    }
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| runtime().block_on(future))
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// The cookie by which the website knows the user of a session.
+pub(crate) fn session_cookie(session: &str) -> String {
+    format!("session={session}")
+}
+
+/// Request a page of the website, as the user of a session if given, returning its text.
+async fn get_text(
+    http: &dyn HttpClient,
+    url: &str,
+    session: Option<&str>,
+) -> Result<String, Error> {
+    let cookie = session.map(session_cookie);
+    let response = http.get(url, cookie.as_deref()).await?;
+    if !response.is_success() {
+        return Err(Error::HttpStatus(response.status, url.to_string()));
+    }
+    Ok(response.text())
+}
+
+/// Scrape a year's calendar for the stars earned on each day.
+///
+/// Days without stars are omitted. A session kept in the keychain is retrieved from it.
+pub async fn fetch_stars(
+    http: &dyn HttpClient,
+    config: &Config,
+    year: u32,
+) -> Result<BTreeMap<u8, u8>, Error> {
+    let url = format!("https://adventofcode.com/{year}");
+    tracing::info!(%url, "requesting calendar");
+    let session = crate::keychain::resolve(&config.session)?;
+    let body = get_text(http, &url, Some(&session)).await?;
+    // without a valid session, the calendar has no link to log out
+    if !body.contains("/auth/logout") {
+        return Err(Error::NotLoggedIn);
+    }
+
+    let day_pattern = Regex::new(r"calendar-day(\d+) calendar-(very)?complete\b")
+        .expect("calendar pattern is valid");
+    Ok(day_pattern
+        .captures_iter(&body)
+        .filter_map(|captures| {
+            let day = captures[1].parse().ok()?;
+            let stars = if captures.get(2).is_some() { 2 } else { 1 };
+            Some((day, stars))
+        })
+        .collect())
+}
+
+/// The name of the user to whom a session belongs.
+///
+/// Anonymous users are named as the website shows them, like "(anonymous user #123456)".
+pub async fn whoami(http: &dyn HttpClient, session: &str) -> Result<String, Error> {
+    tracing::info!("requesting events");
+    let body = get_text(http, "https://adventofcode.com/events", Some(session)).await?;
+    if !body.contains("/auth/logout") {
+        return Err(Error::NotLoggedIn);
+    }
+
+    let user_pattern = Regex::new(r#"<div class="user">([^<]*)"#).expect("user pattern is valid");
+    user_pattern
+        .captures(&body)
+        .map(|captures| captures[1].trim().to_string())
+        .filter(|name| !name.is_empty())
+        .ok_or(Error::NotLoggedIn)
+}
+
+/// Download a puzzle's input to `path` as the user of a session, unless it exists already.
+async fn download_input(
+    host: &Host,
+    session: &str,
+    year: u32,
+    day: u8,
+    path: PathBuf,
+) -> Result<PathBuf, Error> {
+    if host.fs.exists(&path) {
+        return Ok(path);
+    }
+    if let Some(parent) = path.parent() {
+        host.fs
+            .create_dir_all(parent)
+            .map_err(|err| Error::Io("creating inputs dir", err))?;
+    }
+
+    let url = format!("https://adventofcode.com/{year}/day/{day}/input");
+    tracing::info!(%url, "downloading input");
+    let response = host.http.get(&url, Some(&session_cookie(session))).await?;
+    if !response.is_success() {
+        return Err(Error::HttpStatus(response.status, url));
+    }
+    host.fs
+        .write(&path, &response.body)
+        .map_err(|err| Error::Io("writing input file", err))?;
+    Ok(path)
+}
+
+/// Download the input for a puzzle, unless it exists already.
+///
+/// A session kept in the keychain is retrieved from it.
+pub async fn get_input(host: &Host, config: &Config, year: u32, day: u8) -> Result<PathBuf, Error> {
+    let session = crate::keychain::resolve(&config.session)?;
+    download_input(host, &session, year, day, config.input_for(year, day)).await
+}
+
+/// Download the input for a puzzle using the session of a named account.
+///
+/// Like [`get_input`], this does nothing if the input file already exists.
+pub async fn get_input_for_account(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    account: &str,
+) -> Result<PathBuf, Error> {
+    let session = settings
+        .accounts
+        .get(account)
+        .ok_or_else(|| Error::UnknownAccount(account.to_string()))?;
+    let session = crate::keychain::resolve(session)?;
+    let path = website::input_for_account(config, year, day, account);
+    download_input(host, &session, year, day, path).await
+}
+
+/// Download the inputs for many puzzles, [a few](MAX_CONCURRENT_REQUESTS) at a time, using the
+/// session of a named account if given.
+///
+/// `report` is called with each `(year, day)` and its result as it finishes, so perhaps out of
+/// order; the results returned are in the order of `puzzles`.
+pub async fn get_inputs(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    puzzles: &[(u32, u8)],
+    account: Option<&str>,
+    mut report: impl FnMut((u32, u8), &Result<PathBuf, Error>),
+) -> Vec<Result<PathBuf, Error>> {
+    let mut downloads = stream::iter(puzzles.iter().copied().enumerate())
+        .map(|(index, (year, day))| async move {
+            let result = match account {
+                Some(account) => {
+                    get_input_for_account(host, config, settings, year, day, account).await
+                }
+                None => get_input(host, config, year, day).await,
+            };
+            (index, result)
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS);
+
+    let mut results = std::iter::repeat_with(|| None)
+        .take(puzzles.len())
+        .collect::<Vec<_>>();
+    while let Some((index, result)) = downloads.next().await {
+        report(puzzles[index], &result);
+        results[index] = Some(result);
+    }
+    results
+        .into_iter()
+        .map(|result| result.expect("every download finishes"))
+        .collect()
+}
+
+/// Fetch the title of a puzzle from its page.
+///
+/// Returns `Ok(None)` if the page has no recognizable title, e.g. because the puzzle has not
+/// yet unlocked.
+pub async fn puzzle_title(
+    http: &dyn HttpClient,
+    year: u32,
+    day: u8,
+) -> Result<Option<String>, Error> {
+    tracing::info!(year, day, "requesting puzzle title");
+    let body = get_text(http, &aoclib::website::url_for_day(year, day), None).await?;
+    let re = Regex::new(r"<h2>--- Day \d+: (.*?) ---</h2>").expect("title pattern is valid");
+    Ok(re
+        .captures(&body)
+        .and_then(|captures| captures.get(1))
+        .map(|title| website::unescape_html(title.as_str())))
+}
+
+/// Fetch the titles of many puzzles of a year, [a few](MAX_CONCURRENT_REQUESTS) at a time.
+///
+/// Puzzles whose titles could not be fetched are missing from the map.
+pub async fn puzzle_titles(http: &dyn HttpClient, year: u32, days: &[u8]) -> BTreeMap<u8, String> {
+    stream::iter(days.iter().copied())
+        .map(|day| async move { (day, puzzle_title(http, year, day).await) })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .filter_map(|(day, title)| async move { Some((day, title.ok()??)) })
+        .collect()
+        .await
+}
+
+/// Download a private leaderboard's JSON.
+///
+/// The website asks that this be done at most once every 15 minutes. A session kept in the
+/// keychain is retrieved from it.
+pub async fn leaderboard_json(
+    http: &dyn HttpClient,
+    config: &Config,
+    year: u32,
+    id: u64,
+) -> Result<String, Error> {
+    let url = format!("https://adventofcode.com/{year}/leaderboard/private/view/{id}.json");
+    tracing::info!(%url, "requesting leaderboard");
+    let session = crate::keychain::resolve(&config.session)?;
+    get_text(http, &url, Some(&session)).await
+}
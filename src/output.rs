@@ -0,0 +1,68 @@
+//! Output for people or, with `--json`, for scripts.
+//!
+//! Subcommands emit their results through [`emit`] rather than printing them directly, so that the
+//! choice between them is made in one place, and `--json` prints a single JSON document. Those
+//! which report progress as they go print it only for people, and emit their results at the end.
+
+use serde::Serialize;
+use std::{
+    process::Stdio,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::Error;
+
+static JSON: AtomicBool = AtomicBool::new(false);
+static INTERACTIVE: AtomicBool = AtomicBool::new(true);
+
+/// Emit results as JSON from now on.
+pub fn set_json(json: bool) {
+    JSON.store(json, Ordering::Relaxed);
+}
+
+/// Whether results are emitted as JSON.
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Allow prompts, editors, countdowns, and progress bars from now on, or else fail rather than
+/// wait on a person.
+pub fn set_interactive(interactive: bool) {
+    INTERACTIVE.store(interactive, Ordering::Relaxed);
+}
+
+/// Whether a person may be asked to respond, or waited on.
+pub fn is_interactive() -> bool {
+    INTERACTIVE.load(Ordering::Relaxed)
+}
+
+/// Where the standard output of a tool we run for its effect, like a hook, git, or criterion,
+/// should go: to ours, or to our stderr with `--json`, so that the JSON is all stdout holds.
+pub(crate) fn child_stdout() -> Stdio {
+    if is_json() {
+        std::io::stderr().into()
+    } else {
+        Stdio::inherit()
+    }
+}
+
+/// Print a result: as JSON, or else as `human` renders it.
+///
+/// Nothing is printed for people if `human` renders nothing, as when it has been printed already.
+pub fn emit<T: Serialize + ?Sized>(
+    value: &T,
+    human: impl FnOnce(&T) -> String,
+) -> Result<(), Error> {
+    if is_json() {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(value).map_err(Error::SerializeOutput)?
+        );
+    } else {
+        let human = human(value);
+        if !human.trim_end().is_empty() {
+            println!("{}", human.trim_end());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,319 @@
+//! Local record of progress through a year: correct answers, stars, and solve times.
+//!
+//! Progress is kept in `progress.toml` within the year's inputs directory, alongside the inputs
+//! for the same session:
+//!
+//! ```toml
+//! [days.1]
+//! part1 = "142"
+//! part2 = "281"
+//!
+//! [days.1.times]
+//! started = 1701406800
+//! part1 = 1701408012
+//! part2 = 1701409230
+//!
+//! [days.2]
+//! part1 = "8"
+//! stars = 2
+//! ```
+//!
+//! Correct answers are recorded when they are submitted. `stars` is only present when it is known
+//! from elsewhere, as by `stars sync`, which scrapes the website's calendar; otherwise, each
+//! recorded answer counts as a star.
+//!
+//! `times` are Unix timestamps: when work on the day started, which is when it was initialized
+//! unless `timer start` says otherwise, and when each part's correct answer was submitted.
+
+use aoclib::config::Config;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+use crate::{answer::Part, examples, settings::Settings, vfs::Fs, Error};
+
+/// Path to a year's progress file.
+pub fn path(config: &Config, year: u32) -> PathBuf {
+    config.input_files(year).join("progress.toml")
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progress {
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub days: BTreeMap<u8, DayProgress>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayProgress {
+    /// Correct answers.
+    #[serde(flatten)]
+    pub answers: examples::Answers,
+    /// Stars earned, if known independently of the recorded answers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stars: Option<u8>,
+    /// When work on the day started, and each part was solved.
+    #[serde(skip_serializing_if = "SolveTimes::is_empty")]
+    pub times: SolveTimes,
+}
+
+/// Unix timestamps of the milestones of solving a day.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolveTimes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<i64>,
+}
+
+impl SolveTimes {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// When a part was solved.
+    pub fn solved(&self, part: Part) -> Option<i64> {
+        match part {
+            Part::One => self.part1,
+            Part::Two => self.part2,
+        }
+    }
+
+    /// How long a part took to solve: part 1 from the start, and part 2 from part 1.
+    pub fn solve_duration(&self, part: Part) -> Option<Duration> {
+        let from = match part {
+            Part::One => self.started?,
+            Part::Two => self.part1?,
+        };
+        let seconds = self.solved(part)?.checked_sub(from)?;
+        u64::try_from(seconds).ok().map(Duration::from_secs)
+    }
+}
+
+/// The current Unix timestamp.
+fn now() -> i64 {
+    time::OffsetDateTime::now_utc().unix_timestamp()
+}
+
+/// Format a solve duration as `[Nd ]H:MM:SS`.
+pub fn format_solve_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours) = (seconds / 86_400, seconds / 3600 % 24);
+    let clock = format!("{hours}:{:02}:{:02}", seconds / 60 % 60, seconds % 60);
+    if days > 0 {
+        format!("{days}d {clock}")
+    } else {
+        clock
+    }
+}
+
+impl DayProgress {
+    /// Stars earned: as recorded, or else one per recorded answer.
+    pub fn stars(&self) -> u8 {
+        self.stars.unwrap_or_else(|| {
+            [Part::One, Part::Two]
+                .into_iter()
+                .filter(|part| self.answers.get(*part).is_some())
+                .count() as u8
+        })
+    }
+}
+
+impl Progress {
+    /// Load a year's progress.
+    ///
+    /// If it has not been recorded, there is none.
+    pub fn load(fs: &dyn Fs, config: &Config, year: u32) -> Result<Self, Error> {
+        let path = path(config, year);
+        if !fs.exists(&path) {
+            return Ok(Self::default());
+        }
+        let data = fs
+            .read_to_string(&path)
+            .map_err(|err| Error::Io("reading progress file", err))?;
+        toml::from_str(&data).map_err(|err| Error::ParseProgress(err, path.display().to_string()))
+    }
+
+    /// Save a year's progress.
+    pub fn save(&self, fs: &dyn Fs, config: &Config, year: u32) -> Result<(), Error> {
+        let path = path(config, year);
+        if let Some(parent) = path.parent() {
+            fs.create_dir_all(parent)
+                .map_err(|err| Error::Io("creating progress directory", err))?;
+        }
+        let data = toml::to_string_pretty(self).map_err(Error::SerializeProgress)?;
+        tracing::debug!(path = %path.display(), "writing progress file");
+        fs.write(&path, data.as_bytes())
+            .map_err(|err| Error::Io("writing progress file", err))
+    }
+
+    /// A day's progress.
+    pub fn day(&self, day: u8) -> DayProgress {
+        self.days.get(&day).cloned().unwrap_or_default()
+    }
+
+    /// Replace the stars recorded for each day with those earned on the website.
+    ///
+    /// Returns the total number of stars.
+    pub fn sync_stars(&mut self, stars: &BTreeMap<u8, u8>) -> u32 {
+        for (day, progress) in self.days.iter_mut() {
+            progress.stars = Some(stars.get(day).copied().unwrap_or_default());
+        }
+        for (day, stars) in stars {
+            self.days.entry(*day).or_default().stars = Some(*stars);
+        }
+        stars.values().map(|&stars| u32::from(stars)).sum()
+    }
+
+    /// Record a correct answer, submitted just now.
+    pub fn record_answer(&mut self, day: u8, part: Part, answer: &str) {
+        let day_progress = self.days.entry(day).or_default();
+        let (slot, solved) = match part {
+            Part::One => (
+                &mut day_progress.answers.part1,
+                &mut day_progress.times.part1,
+            ),
+            Part::Two => (
+                &mut day_progress.answers.part2,
+                &mut day_progress.times.part2,
+            ),
+        };
+        *slot = Some(answer.to_string());
+        solved.get_or_insert_with(now);
+        // stars recorded from elsewhere don't know about this one yet
+        if let Some(stars) = &mut day_progress.stars {
+            *stars = (*stars).max(part.level());
+        }
+    }
+
+    /// Record that work on a day started just now.
+    ///
+    /// Unless `restart`, an earlier start is kept.
+    pub fn record_start(&mut self, day: u8, restart: bool) {
+        let started = &mut self.days.entry(day).or_default().times.started;
+        if restart || started.is_none() {
+            *started = Some(now());
+        }
+    }
+}
+
+/// Everything known locally about a day.
+#[derive(Debug, Clone, Serialize)]
+pub struct DayStatus {
+    pub day: u8,
+    /// Directory of the day's solution, if it exists.
+    pub solution: Option<PathBuf>,
+    /// Whether the input has been downloaded.
+    pub input: bool,
+    /// Number of recorded examples.
+    pub examples: usize,
+    pub progress: DayProgress,
+}
+
+/// Render a Markdown table of each day with a solution or a star, linking to its puzzle and its
+/// solution, with a star emoji for each star earned and the time each part took to solve.
+///
+/// Solutions are linked relative to the implementation directory.
+pub fn progress_table(
+    fs: &dyn Fs,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+) -> Result<String, Error> {
+    let implementation_dir = config.implementation(year);
+    let mut table = String::from(
+        "| Day | Puzzle | Solution | Stars | Part 1 | Part 2 |\n| --- | --- | --- | --- | ---: | ---: |\n",
+    );
+    for status in year_status(fs, config, settings, year)? {
+        let stars = status.progress.stars();
+        if status.solution.is_none() && stars == 0 {
+            continue;
+        }
+        let puzzle_url = aoclib::website::url_for_day(year, status.day);
+        let solution = status
+            .solution
+            .as_ref()
+            .and_then(|dir| pathdiff::diff_paths(dir, &implementation_dir))
+            .map(|dir| {
+                let dir = dir.display();
+                let name = settings.day_name(year, status.day);
+                format!("[{name}]({dir})")
+            })
+            .unwrap_or_default();
+        let solve_duration = |part| {
+            status
+                .progress
+                .times
+                .solve_duration(part)
+                .map(format_solve_duration)
+                .unwrap_or_default()
+        };
+        table.push_str(&format!(
+            "| {} | [puzzle]({puzzle_url}) | {solution} | {} | {} | {} |\n",
+            status.day,
+            "⭐".repeat(stars.into()),
+            solve_duration(Part::One),
+            solve_duration(Part::Two),
+        ));
+    }
+    Ok(table)
+}
+
+/// Update the progress table in the workspace's `README.md`.
+///
+/// The table is kept between `<!-- progress:start -->` and `<!-- progress:end -->`, as in the
+/// README rendered by `init-year`; if the README lacks them, a section containing them is
+/// appended. Returns the path of the README.
+pub fn update_readme_progress(
+    fs: &dyn Fs,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+) -> Result<PathBuf, Error> {
+    let table = progress_table(fs, config, settings, year)?;
+    let readme_path = config.implementation(year).join("README.md");
+    crate::update_marked(fs, &readme_path, "progress", "Progress", &table)?;
+    Ok(readme_path)
+}
+
+/// Gather the status of every day of a year.
+///
+/// A day's solution is found in the implementation directory, or in a language subdirectory of it.
+pub fn year_status(
+    fs: &dyn Fs,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+) -> Result<Vec<DayStatus>, Error> {
+    let implementation_dir = config.implementation(year);
+    let language_dirs = fs
+        .read_dir(&implementation_dir)
+        .map(|entries| {
+            entries
+                .into_iter()
+                .filter(|path| fs.is_dir(path))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let progress = Progress::load(fs, config, year)?;
+
+    (1..=25)
+        .map(|day| {
+            let day_name = settings.day_name(year, day);
+            let solution = std::iter::once(&implementation_dir)
+                .chain(&language_dirs)
+                .map(|dir| dir.join(&day_name))
+                .find(|dir| fs.is_dir(dir));
+            Ok::<_, Error>(DayStatus {
+                day,
+                solution,
+                input: fs.exists(&config.input_for(year, day)),
+                examples: examples::examples_for(config, year, day)?.len(),
+                progress: progress.day(day),
+            })
+        })
+        .collect()
+}
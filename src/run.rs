@@ -0,0 +1,887 @@
+//! Build and run a day's solution.
+//!
+//! Day crates are built with cargo from the implementation directory, so there's no need to change
+//! directory between them, and then their binaries are run directly.
+
+use aoclib::config::Config;
+use clap::Args;
+use serde::Serialize;
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Output, Stdio},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answer::{self, Part},
+    day_dir, examples, output,
+    progress::Progress,
+    settings::{CargoSettings, Settings},
+    submit::InputProvenance,
+    templates, DayOpts, Error, Host,
+};
+
+/// How to run a day's solution.
+#[derive(Debug, Clone, Default)]
+pub struct RunOpts {
+    /// Run part 2 as well.
+    pub part2: bool,
+    /// Skip part 1.
+    pub no_part1: bool,
+    /// How cargo builds the solution.
+    pub cargo: CargoOpts,
+    /// Input on which to run, rather than the real input.
+    pub input: Option<PathBuf>,
+    /// Run on this [example](crate::examples) instead, comparing with its expected answers.
+    pub example: Option<usize>,
+    /// Number of times to run each part, keeping the best time.
+    pub min_runs: usize,
+    /// Number of days to run at once, when running a whole year.
+    pub jobs: usize,
+    /// Days to run, when running a whole year; when empty, every initialized day is run.
+    pub days: Vec<u8>,
+}
+
+/// How cargo builds solutions.
+#[derive(Args, Debug, Clone, Default)]
+pub struct CargoOpts {
+    /// Build with this cargo profile, like "release" or a custom one.
+    ///
+    /// Defaults to `cargo.profile` in the aoctool settings, or else "dev".
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Enable these features of the day crate.
+    #[arg(long, value_delimiter = ',')]
+    pub features: Vec<String>,
+
+    /// Build in this target directory.
+    #[arg(long)]
+    pub target_dir: Option<PathBuf>,
+
+    /// Extra flags for rustc, appended to any in RUSTFLAGS, like "-C target-cpu=native".
+    #[arg(long, allow_hyphen_values = true)]
+    pub rustflags: Option<String>,
+}
+
+impl CargoOpts {
+    /// Fill in anything unset from the settings.
+    pub fn with_defaults(mut self, settings: &CargoSettings) -> Self {
+        self.profile = self.profile.or_else(|| settings.profile.clone());
+        if self.features.is_empty() {
+            self.features = settings.features.clone();
+        }
+        self.target_dir = self.target_dir.or_else(|| settings.target_dir.clone());
+        self.rustflags = self.rustflags.or_else(|| settings.rustflags.clone());
+        self
+    }
+
+    fn profile_name(&self) -> &str {
+        self.profile.as_deref().unwrap_or("dev")
+    }
+
+    /// Pass these options to a `cargo build` or `cargo run`.
+    pub(crate) fn apply(&self, command: &mut Command) {
+        command.args(["--profile", self.profile_name()]);
+        if !self.features.is_empty() {
+            command.arg("--features").arg(self.features.join(","));
+        }
+        if let Some(target_dir) = &self.target_dir {
+            command.arg("--target-dir").arg(target_dir);
+        }
+        if let Some(rustflags) = &self.rustflags {
+            let rustflags = match std::env::var("RUSTFLAGS") {
+                Ok(existing) if !existing.is_empty() => format!("{existing} {rustflags}"),
+                _ => rustflags.clone(),
+            };
+            command.env("RUSTFLAGS", rustflags);
+        }
+    }
+
+    /// Where cargo builds in a workspace.
+    pub(crate) fn target_dir(&self, workspace: &Path) -> PathBuf {
+        self.target_dir
+            .clone()
+            .or_else(|| std::env::var_os("CARGO_TARGET_DIR").map(PathBuf::from))
+            .map(|dir| workspace.join(dir))
+            .unwrap_or_else(|| workspace.join("target"))
+    }
+
+    /// Where cargo puts the binary of a package in a workspace.
+    fn binary(&self, workspace: &Path, package_name: &str) -> PathBuf {
+        let target_dir = self.target_dir(workspace);
+        // the dev profile is the exception to profiles building into directories of their names
+        let profile_dir = match self.profile_name() {
+            "dev" | "test" => "debug",
+            "bench" => "release",
+            profile => profile,
+        };
+        target_dir
+            .join(profile_dir)
+            .join(format!("{package_name}{}", std::env::consts::EXE_SUFFIX))
+    }
+}
+
+/// The outcome of running one part of a day's solution.
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    pub part: Part,
+    /// The answer found in the output, if the solution succeeded and the answer could be found.
+    pub answer: Option<String>,
+    /// The answer recorded as correct in the year's [progress](crate::progress), or the expected
+    /// answer of the example, if any.
+    pub expected: Option<String>,
+    /// Wall time of the run, excluding the build.
+    pub duration: Duration,
+    /// Whether the solution exited successfully.
+    pub success: bool,
+    /// The solution's exit code, if it ran to completion.
+    pub exit_code: Option<i32>,
+    /// Peak resident memory of the solution's process, in bytes, where the platform reports it.
+    pub peak_rss: Option<u64>,
+    /// The end of the solution's standard error, at most its last [`STDERR_TAIL`] lines, if it
+    /// failed.
+    pub stderr: String,
+    /// Where the input on which it ran came from.
+    pub provenance: InputProvenance,
+}
+
+/// How many lines at the end of a failed solution's standard error a [`PartRun`] keeps.
+pub const STDERR_TAIL: usize = 20;
+
+impl PartRun {
+    pub fn verdict(&self) -> Verdict {
+        match (&self.answer, &self.expected) {
+            _ if !self.success => Verdict::Fail,
+            (Some(answer), Some(expected)) if answer == expected => Verdict::Pass,
+            (_, Some(_)) => Verdict::Fail,
+            (_, None) => Verdict::Unknown,
+        }
+    }
+}
+
+/// Whether a run produced the right answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verdict {
+    Pass,
+    /// No correct answer is recorded to compare against.
+    Unknown,
+    /// The solution failed, or its answer is not the recorded one.
+    Fail,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Unknown => write!(f, "?"),
+            Self::Fail => write!(f, "FAIL"),
+        }
+    }
+}
+
+/// The outcome of running a day's solution, part by part.
+#[derive(Debug, Clone)]
+pub struct DayRun {
+    pub year: u32,
+    pub day: u8,
+    pub parts: Vec<PartRun>,
+}
+
+impl DayRun {
+    /// The run of a particular part, if it was run.
+    pub fn part(&self, part: Part) -> Option<&PartRun> {
+        self.parts.iter().find(|run| run.part == part)
+    }
+
+    /// Peak resident memory of any part, in bytes, where the platform reports it.
+    pub fn peak_rss(&self) -> Option<u64> {
+        self.parts.iter().filter_map(|run| run.peak_rss).max()
+    }
+
+    /// Total wall time of the parts.
+    pub fn duration(&self) -> Duration {
+        self.parts.iter().map(|run| run.duration).sum()
+    }
+
+    /// The worst verdict of the parts.
+    pub fn verdict(&self) -> Verdict {
+        self.parts
+            .iter()
+            .map(PartRun::verdict)
+            .max()
+            .unwrap_or(Verdict::Unknown)
+    }
+}
+
+/// A [`PartRun`] of a day, as reported by `run --json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Record<'a> {
+    pub year: u32,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<&'a str>,
+    pub expected: Option<&'a str>,
+    /// Wall time, in seconds.
+    pub duration: f64,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    /// Peak resident memory, in bytes.
+    pub peak_rss: Option<u64>,
+    pub verdict: Verdict,
+    /// The end of the standard error of a failed solution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<&'a str>,
+}
+
+/// A record of each part of each run.
+pub fn records(runs: &[DayRun]) -> Vec<Record<'_>> {
+    runs.iter()
+        .flat_map(|run| {
+            run.parts.iter().map(|part_run| Record {
+                year: run.year,
+                day: run.day,
+                part: part_run.part.level(),
+                answer: part_run.answer.as_deref(),
+                expected: part_run.expected.as_deref(),
+                duration: part_run.duration.as_secs_f64(),
+                success: part_run.success,
+                exit_code: part_run.exit_code,
+                peak_rss: part_run.peak_rss,
+                verdict: part_run.verdict(),
+                stderr: (!part_run.stderr.is_empty()).then_some(part_run.stderr.as_str()),
+            })
+        })
+        .collect()
+}
+
+/// The command which runs a day's solution.
+///
+/// Once the solution is [built](build), its binary is run directly, so that cargo's overhead isn't
+/// timed; if it can't be found, `cargo run` is used instead. Without an input, the solution runs
+/// on the real input, downloading it if necessary.
+pub fn command(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    opts: &RunOpts,
+) -> Result<Command, Error> {
+    let package_name = settings.day_name(year, day);
+    rust_day_dir(config, settings, year, day)?;
+
+    let implementation_dir = config.implementation(year);
+    let binary = opts.cargo.binary(&implementation_dir, &package_name);
+    let mut command = if binary.exists() {
+        Command::new(binary)
+    } else {
+        let mut command = Command::new(cargo());
+        command.args(["run", "--quiet", "--package", &package_name]);
+        opts.cargo.apply(&mut command);
+        command.arg("--");
+        command
+    };
+    command.current_dir(implementation_dir);
+    // the solution would otherwise find its input from the default configuration, ignoring any
+    // profile or workspace applied to ours
+    let input = match choose_input(config, year, day, opts)?.0 {
+        Some(input) => input,
+        None => crate::website::get_input(host, config, year, day)?,
+    };
+    command.arg(input);
+    if opts.no_part1 {
+        command.arg("--no-part1");
+    }
+    if opts.part2 {
+        command.arg("--part2");
+    }
+    tracing::debug!(?command, "prepared solution command");
+    Ok(command)
+}
+
+/// The input chosen by `opts`, if not the real input, and where it came from.
+///
+/// The real input is left to the caller to download, if it must.
+fn choose_input(
+    config: &Config,
+    year: u32,
+    day: u8,
+    opts: &RunOpts,
+) -> Result<(Option<PathBuf>, InputProvenance), Error> {
+    match (opts.example, &opts.input) {
+        (Some(index), _) => {
+            let input = examples::example_input(config, year, day, index);
+            if !input.exists() {
+                return Err(Error::NoSuchExample(day, index));
+            }
+            Ok((Some(input), InputProvenance::Example(index)))
+        }
+        (None, Some(input)) => Ok((
+            Some(input.clone()),
+            InputProvenance::of(config, year, day, input),
+        )),
+        (None, None) => Ok((None, InputProvenance::Real)),
+    }
+}
+
+/// Where the input on which `opts` would run a day's solution comes from.
+///
+/// This is what each [`PartRun`] reports, so that an answer can be refused for submission before
+/// the solution is even run.
+pub fn provenance(
+    config: &Config,
+    year: u32,
+    day: u8,
+    opts: &RunOpts,
+) -> Result<InputProvenance, Error> {
+    choose_input(config, year, day, opts).map(|(_, provenance)| provenance)
+}
+
+/// The cargo executable: the one running us, if any.
+pub(crate) fn cargo() -> std::ffi::OsString {
+    std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into())
+}
+
+/// The parts to run, as requested in `opts`.
+fn parts(opts: &RunOpts) -> Vec<Part> {
+    [(Part::One, !opts.no_part1), (Part::Two, opts.part2)]
+        .into_iter()
+        .filter_map(|(part, run)| run.then_some(part))
+        .collect()
+}
+
+/// The options which run only one part.
+fn part_opts(opts: &RunOpts, part: Part) -> RunOpts {
+    RunOpts {
+        part2: part == Part::Two,
+        no_part1: part == Part::Two,
+        ..opts.clone()
+    }
+}
+
+/// Build and run a day's solution, streaming its output.
+///
+/// Each part is run separately, and timed. With `opts.min_runs` above 1, each part is run that
+/// many times, and the best time is kept; only the first run's output is shown.
+pub fn run(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    opts: &RunOpts,
+) -> Result<Vec<(Part, Duration)>, Error> {
+    let package_name = settings.day_name(year, day);
+    if !build(config, settings, year, &[day], &opts.cargo)? {
+        return Err(Error::BuildFailed(package_name));
+    }
+
+    let mut times = Vec::new();
+    for part in parts(opts) {
+        let part_opts = part_opts(opts, part);
+        let mut best = Duration::MAX;
+        for attempt in 0..opts.min_runs.max(1) {
+            let mut command = command(host, config, settings, year, day, &part_opts)?;
+            if attempt > 0 {
+                command.stdout(Stdio::null());
+            }
+            let start = Instant::now();
+            let status = command
+                .status()
+                .map_err(|err| Error::Io("running solution", err))?;
+            best = best.min(start.elapsed());
+            if !status.success() {
+                return Err(Error::SolutionFailed(package_name));
+            }
+        }
+        times.push((part, best));
+    }
+    Ok(times)
+}
+
+/// Build some days' solutions together, without running them.
+///
+/// Returns whether the build succeeded; compiler messages are shown as usual.
+pub fn build(
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    days: &[u8],
+    cargo_opts: &CargoOpts,
+) -> Result<bool, Error> {
+    for &day in days {
+        rust_day_dir(config, settings, year, day)?;
+    }
+    let mut command = Command::new(cargo());
+    command
+        .current_dir(config.implementation(year))
+        .args(["build", "--quiet"]);
+    for &day in days {
+        command.arg("--package").arg(settings.day_name(year, day));
+    }
+    cargo_opts.apply(&mut command);
+    tracing::info!(?command, "building solutions");
+    let status = command
+        .status()
+        .map_err(|err| Error::Io("running cargo", err))?;
+    Ok(status.success())
+}
+
+/// The directory of a day's crate.
+///
+/// Days in [other languages](DayOpts::lang) can't be built or run by cargo, so they are refused
+/// with an error which says so, rather than as uninitialized.
+pub(crate) fn rust_day_dir(
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+) -> Result<PathBuf, Error> {
+    let day_name = settings.day_name(year, day);
+    let dir = day_dir(config, settings, year, day, &DayOpts::default());
+    if dir.exists() {
+        return Ok(dir);
+    }
+    let languages = templates::LANGUAGE_TEMPLATES
+        .iter()
+        .map(|(lang, _)| *lang)
+        .chain(settings.languages.keys().map(String::as_str));
+    for lang in languages {
+        let day_opts = DayOpts {
+            lang: Some(lang.to_string()),
+            ..DayOpts::default()
+        };
+        if day_dir(config, settings, year, day, &day_opts).exists() {
+            return Err(Error::NotRust(day_name, lang.to_string()));
+        }
+    }
+    Err(Error::DayNotInitialized(day_name))
+}
+
+/// Run one part of a day's solution, capturing its answer.
+///
+/// The solution should already be [built](build), so that the build isn't timed. With
+/// `opts.min_runs` above 1, the part is run that many times, and the best time is kept.
+pub fn run_part(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    part: Part,
+    opts: &RunOpts,
+) -> Result<PartRun, Error> {
+    let part_opts = part_opts(opts, part);
+    let provenance = provenance(config, year, day, opts)?;
+    let mut output = None;
+    let mut duration = Duration::MAX;
+    let mut peak_rss = None;
+    for _ in 0..opts.min_runs.max(1) {
+        let mut command = command(host, config, settings, year, day, &part_opts)?;
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let start = Instant::now();
+        let (this_output, this_peak_rss) =
+            measured_output(&mut command).map_err(|err| Error::Io("running solution", err))?;
+        duration = duration.min(start.elapsed());
+        peak_rss = peak_rss.max(this_peak_rss);
+        let failed = !this_output.status.success();
+        output.get_or_insert(this_output);
+        if failed {
+            break;
+        }
+    }
+    let output = output.expect("the solution runs at least once");
+
+    let success = output.status.success();
+    let stderr = if success {
+        String::new()
+    } else {
+        tail(&String::from_utf8_lossy(&output.stderr), STDERR_TAIL)
+    };
+    let answer = if success {
+        answer::find(
+            &String::from_utf8_lossy(&output.stdout),
+            part,
+            &settings.extraction(year),
+        )?
+    } else {
+        None
+    };
+    let expected = match &provenance {
+        InputProvenance::Example(index) => examples::example(config, year, day, *index)?
+            .and_then(|example| example.answers.get(part).map(ToOwned::to_owned)),
+        InputProvenance::Real => Progress::load(host.fs.as_ref(), config, year)?
+            .day(day)
+            .answers
+            .get(part)
+            .map(ToOwned::to_owned),
+        // nothing is known of the answers to some other input
+        InputProvenance::Other(_) => None,
+    };
+
+    Ok(PartRun {
+        part,
+        answer,
+        expected,
+        duration,
+        success,
+        exit_code: output.status.code(),
+        peak_rss,
+        stderr,
+        provenance,
+    })
+}
+
+/// The last `lines` lines of `text`.
+fn tail(text: &str, lines: usize) -> String {
+    let all = text.lines().collect::<Vec<_>>();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+/// Run a command to completion, capturing its standard output and error, and measure its peak
+/// resident memory.
+fn measured_output(command: &mut Command) -> std::io::Result<(Output, Option<u64>)> {
+    let mut child = command.spawn()?;
+    // read both at once, so that neither fills its pipe while the other is read
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let (status, peak_rss) = wait_measured(child)?;
+    let output = Output {
+        status,
+        stdout: stdout.join().expect("reading output does not panic")?,
+        stderr: stderr.join().expect("reading output does not panic")?,
+    };
+    Ok((output, peak_rss))
+}
+
+/// Read a pipe to its end on a thread of its own.
+fn read_in_background(
+    pipe: Option<impl Read + Send + 'static>,
+) -> std::thread::JoinHandle<std::io::Result<Vec<u8>>> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut buffer)?;
+        }
+        Ok(buffer)
+    })
+}
+
+/// Wait for a child process, and measure its peak resident memory.
+#[cfg(unix)]
+fn wait_measured(child: Child) -> std::io::Result<(ExitStatus, Option<u64>)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let pid = child.id() as libc::pid_t;
+    let mut status = 0;
+    // SAFETY: `rusage` is plain old data, for which all zeroes is valid
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: both pointers are to live locals. Reaping the child here means its `Child` must
+        // not be waited on again, and it is dropped unwaited.
+        if unsafe { libc::wait4(pid, &mut status, 0, &mut usage) } == pid {
+            break;
+        }
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+    drop(child);
+
+    // linux reports kilobytes, but macos bytes
+    let max_rss = usage.ru_maxrss.max(0) as u64;
+    let peak_rss = if cfg!(target_os = "macos") {
+        max_rss
+    } else {
+        max_rss * 1024
+    };
+    Ok((ExitStatus::from_raw(status), Some(peak_rss)))
+}
+
+/// Wait for a child process; its memory can't be measured on this platform.
+#[cfg(not(unix))]
+fn wait_measured(mut child: Child) -> std::io::Result<(ExitStatus, Option<u64>)> {
+    Ok((child.wait()?, None))
+}
+
+/// Format a number of bytes for humans, like "12.3 MiB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Build a day's solution and run one part, for its answer.
+pub fn answer(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    part: Part,
+    opts: &RunOpts,
+) -> Result<String, Error> {
+    let run = run_day(
+        host,
+        config,
+        settings,
+        year,
+        day,
+        &RunOpts {
+            min_runs: 1,
+            ..part_opts(opts, part)
+        },
+    )?;
+    let run = run.part(part).expect("the requested part was run");
+    if !run.success {
+        if !run.stderr.is_empty() {
+            eprintln!("{}", run.stderr);
+        }
+        return Err(Error::SolutionFailed(settings.day_name(year, day)));
+    }
+    run.answer.clone().ok_or(Error::NoAnswer(part))
+}
+
+/// The days which [`run_year`] would run.
+pub fn year_days(config: &Config, settings: &Settings, year: u32, opts: &RunOpts) -> Vec<u8> {
+    (1..=25)
+        .filter(|day| opts.days.is_empty() || opts.days.contains(day))
+        .filter(|&day| day_dir(config, settings, year, day, &DayOpts::default()).exists())
+        .filter(|&day| {
+            opts.example.map_or(true, |index| {
+                examples::example_input(config, year, day, index).exists()
+            })
+        })
+        .collect()
+}
+
+/// Build and run every initialized day of a year, or those of `opts.days`, reporting each as it
+/// finishes.
+///
+/// Part 1 is run unless `opts.no_part1`, and part 2 if `opts.part2`. When running an example,
+/// days without it are skipped.
+///
+/// Every day is built first. Then up to `opts.jobs` days run at once, so with more than one job,
+/// days may be reported out of order; the runs returned are in order of day.
+pub fn run_year(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    opts: &RunOpts,
+    mut report: impl FnMut(&DayRun),
+) -> Result<Vec<DayRun>, Error> {
+    let days = year_days(config, settings, year, opts);
+    if days.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // build together for speed; if that fails, build separately to find out which days fail
+    let built = if build(config, settings, year, &days, &opts.cargo)? {
+        vec![true; days.len()]
+    } else {
+        days.iter()
+            .map(|&day| build(config, settings, year, &[day], &opts.cargo))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut runs = std::thread::scope(|scope| {
+        for _ in 0..opts.jobs.clamp(1, days.len().max(1)) {
+            let sender = sender.clone();
+            let (days, built, next) = (&days, &built, &next);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                let Some(&day) = days.get(index) else {
+                    break;
+                };
+                let run = run_built_day(host, config, settings, year, day, built[index], opts);
+                if sender.send(run).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut runs = Vec::new();
+        for run in receiver {
+            let run = run?;
+            report(&run);
+            runs.push(run);
+        }
+        Ok::<_, Error>(runs)
+    })?;
+    runs.sort_by_key(|run| run.day);
+    Ok(runs)
+}
+
+/// Build and run a day's solution, capturing the answer of each part requested in `opts`.
+///
+/// If the day fails to build, each of its parts fails.
+pub fn run_day(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    opts: &RunOpts,
+) -> Result<DayRun, Error> {
+    rust_day_dir(config, settings, year, day)?;
+    let built = build(config, settings, year, &[day], &opts.cargo)?;
+    run_built_day(host, config, settings, year, day, built, opts)
+}
+
+/// Run each part of a day's solution requested in `opts`, once it has been built, or has failed
+/// to.
+fn run_built_day(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    built: bool,
+    opts: &RunOpts,
+) -> Result<DayRun, Error> {
+    let parts = parts(opts)
+        .into_iter()
+        .map(|part| {
+            if built {
+                run_part(host, config, settings, year, day, part, opts)
+            } else {
+                Ok(PartRun {
+                    part,
+                    answer: None,
+                    expected: None,
+                    duration: Duration::ZERO,
+                    success: false,
+                    exit_code: None,
+                    peak_rss: None,
+                    stderr: String::new(),
+                    provenance: provenance(config, year, day, opts)?,
+                })
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(DayRun { year, day, parts })
+}
+
+/// Name of the flamegraph written into a day's directory by [`profile`].
+pub const FLAMEGRAPH: &str = "flamegraph.svg";
+
+/// Profile a day's solution on the real input with `cargo flamegraph`, writing the flamegraph
+/// into the day's directory.
+///
+/// Without a cargo profile, the release profile is used. Either way, debug info is enabled for
+/// it, so that the flamegraph has names in it. Returns the path of the flamegraph.
+pub fn profile(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    opts: &RunOpts,
+) -> Result<PathBuf, Error> {
+    let package_name = settings.day_name(year, day);
+    let day_dir = rust_day_dir(config, settings, year, day)?;
+    crate::website::get_input(host, config, year, day)?;
+
+    // cargo flamegraph has no `--target-dir`, but cargo takes it from the environment too
+    let cargo_opts = CargoOpts {
+        profile: Some(
+            opts.cargo
+                .profile
+                .clone()
+                .unwrap_or_else(|| "release".to_string()),
+        ),
+        target_dir: None,
+        ..opts.cargo.clone()
+    };
+    let flamegraph = day_dir.join(FLAMEGRAPH);
+    let mut command = Command::new(cargo());
+    command
+        .args(["flamegraph", "--package", &package_name, "--output"])
+        .arg(&flamegraph)
+        .current_dir(config.implementation(year))
+        .stdout(output::child_stdout());
+    if let Some(target_dir) = &opts.cargo.target_dir {
+        command.env("CARGO_TARGET_DIR", target_dir);
+    }
+    cargo_opts.apply(&mut command);
+    let debug_var = format!(
+        "CARGO_PROFILE_{}_DEBUG",
+        cargo_opts.profile_name().to_uppercase().replace('-', "_")
+    );
+    command.env(debug_var, "true").arg("--");
+    command.arg(config.input_for(year, day));
+    if opts.no_part1 {
+        command.arg("--no-part1");
+    }
+    if opts.part2 {
+        command.arg("--part2");
+    }
+
+    let status = command
+        .status()
+        .map_err(|err| Error::Io("running cargo flamegraph", err))?;
+    if !status.success() {
+        return Err(Error::ProfileFailed(package_name));
+    }
+    Ok(flamegraph)
+}
+
+/// A part's runs in the debug and release profiles.
+#[derive(Debug, Clone)]
+pub struct ProfileComparison {
+    pub debug: PartRun,
+    pub release: PartRun,
+}
+
+impl ProfileComparison {
+    /// Whether the profiles disagree: on the answer, or on whether the solution succeeded at all.
+    ///
+    /// Integer overflow panics in debug builds, but wraps silently in release builds.
+    pub fn diverges(&self) -> bool {
+        self.debug.success != self.release.success || self.debug.answer != self.release.answer
+    }
+}
+
+/// Run a day's solution in both the debug and release profiles, comparing each part's answers.
+pub fn compare_profiles(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    opts: &RunOpts,
+) -> Result<Vec<ProfileComparison>, Error> {
+    let run_profile = |profile: &str| {
+        let mut opts = opts.clone();
+        opts.cargo.profile = Some(profile.to_string());
+        run_day(host, config, settings, year, day, &opts)
+    };
+    let debug = run_profile("dev")?;
+    let release = run_profile("release")?;
+    Ok(debug
+        .parts
+        .into_iter()
+        .zip(release.parts)
+        .map(|(debug, release)| ProfileComparison { debug, release })
+        .collect())
+}
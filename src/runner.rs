@@ -0,0 +1,207 @@
+//! An optional crate which can run any day of the workspace from a single binary.
+//!
+//! When `runner/` exists in the implementation directory, each newly initialized day is added to
+//! its dependencies and to the dispatch table in its `src/days.rs`, so that
+//! `cargo run -p runner -- 7 --part2` runs day 7.
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use toml_edit::{Document, InlineTable, Item, Table, Value};
+
+use crate::{vfs::Fs, Error};
+
+/// Name of the runner crate, and its directory within the implementation directory.
+pub const RUNNER_DIR: &str = "runner";
+
+/// The stock template of the runner crate, embedded in this binary.
+const RUNNER_TEMPLATE: &[(&str, &str)] = &[
+    ("Cargo.toml", include_str!("../runner-template/Cargo.toml")),
+    (
+        "src/main.rs",
+        include_str!("../runner-template/src/main.rs"),
+    ),
+];
+
+/// Path of the dispatch table within the runner crate.
+const DAYS_PATH: &str = "src/days.rs";
+
+/// A day registered with the runner.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Entry {
+    year: u32,
+    day: u8,
+    crate_name: String,
+}
+
+impl Entry {
+    fn new(year: u32, day: u8, day_name: &str) -> Self {
+        Entry {
+            year,
+            day,
+            // the name by which rust code refers to the crate
+            crate_name: day_name.replace('-', "_"),
+        }
+    }
+}
+
+/// Whether the implementation directory has a runner crate.
+pub fn exists(fs: &dyn Fs, implementation_dir: &Path) -> bool {
+    fs.exists(&implementation_dir.join(RUNNER_DIR).join("Cargo.toml"))
+}
+
+/// Create the runner crate in the implementation directory, with no days registered.
+///
+/// Existing files are left alone.
+pub fn scaffold_runner(fs: &dyn Fs, implementation_dir: &Path) -> Result<(), Error> {
+    let runner_dir = implementation_dir.join(RUNNER_DIR);
+    for (path, contents) in RUNNER_TEMPLATE {
+        let path = runner_dir.join(path);
+        if fs.exists(&path) {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs.create_dir_all(parent)
+                .map_err(|err| Error::Io("creating runner dir", err))?;
+        }
+        tracing::info!(path = %path.display(), "writing runner file");
+        fs.write(&path, contents.as_bytes())
+            .map_err(|err| Error::Io("writing runner file", err))?;
+    }
+    if !fs.exists(&runner_dir.join(DAYS_PATH)) {
+        write_days(fs, implementation_dir, &[])?;
+    }
+    Ok(())
+}
+
+/// The files which registering a day changes, if the runner exists.
+pub(crate) fn files(fs: &dyn Fs, implementation_dir: &Path) -> Vec<PathBuf> {
+    if !exists(fs, implementation_dir) {
+        return Vec::new();
+    }
+    let runner_dir = implementation_dir.join(RUNNER_DIR);
+    vec![runner_dir.join("Cargo.toml"), runner_dir.join(DAYS_PATH)]
+}
+
+/// Register a day's crate with the runner, if the runner exists.
+///
+/// The day's crate must be a library exporting `part1` and `part2`.
+pub fn add_day(
+    fs: &dyn Fs,
+    implementation_dir: &Path,
+    year: u32,
+    day: u8,
+    day_name: &str,
+) -> Result<(), Error> {
+    if !exists(fs, implementation_dir) {
+        return Ok(());
+    }
+
+    let (cargo_toml_path, mut manifest) = runner_manifest(fs, implementation_dir)?;
+    let dependencies = dependencies(&mut manifest)?;
+    if !dependencies.contains_key(day_name) {
+        let mut dependency = InlineTable::new();
+        dependency.insert("path", Value::from(format!("../{day_name}")));
+        dependencies.insert(day_name, Item::Value(Value::InlineTable(dependency)));
+        crate::manifest::write(fs, &cargo_toml_path, &manifest)?;
+    }
+
+    let mut entries = read_days(fs, implementation_dir)?;
+    let entry = Entry::new(year, day, day_name);
+    if !entries.contains(&entry) {
+        entries.push(entry);
+        entries.sort();
+        write_days(fs, implementation_dir, &entries)?;
+    }
+    Ok(())
+}
+
+/// Remove a day's crate from the runner, if the runner exists.
+pub fn remove_day(
+    fs: &dyn Fs,
+    implementation_dir: &Path,
+    year: u32,
+    day: u8,
+    day_name: &str,
+) -> Result<(), Error> {
+    if !exists(fs, implementation_dir) {
+        return Ok(());
+    }
+
+    let (cargo_toml_path, mut manifest) = runner_manifest(fs, implementation_dir)?;
+    if dependencies(&mut manifest)?.remove(day_name).is_some() {
+        crate::manifest::write(fs, &cargo_toml_path, &manifest)?;
+    }
+
+    let mut entries = read_days(fs, implementation_dir)?;
+    let before = entries.len();
+    let entry = Entry::new(year, day, day_name);
+    entries.retain(|existing| *existing != entry);
+    if entries.len() != before {
+        write_days(fs, implementation_dir, &entries)?;
+    }
+    Ok(())
+}
+
+fn runner_manifest(fs: &dyn Fs, implementation_dir: &Path) -> Result<(PathBuf, Document), Error> {
+    let cargo_toml_path = implementation_dir.join(RUNNER_DIR).join("Cargo.toml");
+    let manifest = Document::from_str(
+        &fs.read_to_string(&cargo_toml_path)
+            .map_err(|err| Error::Io("reading runner Cargo.toml", err))?,
+    )?;
+    Ok((cargo_toml_path, manifest))
+}
+
+fn dependencies(manifest: &mut Document) -> Result<&mut Table, Error> {
+    manifest
+        .as_table_mut()
+        .entry("dependencies")
+        .or_insert(Item::Table(Table::new()))
+        .as_table_mut()
+        .ok_or(Error::MalformedToml)
+}
+
+/// The days registered in the runner's dispatch table.
+fn read_days(fs: &dyn Fs, implementation_dir: &Path) -> Result<Vec<Entry>, Error> {
+    let path = implementation_dir.join(RUNNER_DIR).join(DAYS_PATH);
+    if !fs.exists(&path) {
+        return Ok(Vec::new());
+    }
+    let days = fs
+        .read_to_string(&path)
+        .map_err(|err| Error::Io("reading runner days", err))?;
+    let entry_pattern =
+        regex::Regex::new(r"day!\((\d+), (\d+), (\w+)\)").expect("entry pattern is valid");
+    Ok(entry_pattern
+        .captures_iter(&days)
+        .filter_map(|captures| {
+            Some(Entry {
+                year: captures[1].parse().ok()?,
+                day: captures[2].parse().ok()?,
+                crate_name: captures[3].to_owned(),
+            })
+        })
+        .collect())
+}
+
+/// Generate the runner's dispatch table.
+fn write_days(fs: &dyn Fs, implementation_dir: &Path, entries: &[Entry]) -> Result<(), Error> {
+    let mut days = String::from(
+        "//! The days which the runner can run.\n//!\n//! `aoc init` regenerates this file as days are added, so edits will be lost.\n\npub const DAYS: &[crate::Day] = &[\n",
+    );
+    for Entry {
+        year,
+        day,
+        crate_name,
+    } in entries
+    {
+        days.push_str(&format!("    day!({year}, {day}, {crate_name}),\n"));
+    }
+    days.push_str("];\n");
+    fs.write(
+        &implementation_dir.join(RUNNER_DIR).join(DAYS_PATH),
+        days.as_bytes(),
+    )
+    .map_err(|err| Error::Io("writing runner days", err))
+}
@@ -0,0 +1,529 @@
+//! Settings which only concern `aoctool`.
+//!
+//! `aoclib::config::Config` owns the session key and the per-year paths; it is shared with the
+//! day crates, so we can't add fields to it. Everything else lives in a sibling file,
+//! `aoctool.toml`, next to the main configuration file.
+//!
+//! The file records the [version](SETTINGS_VERSION) of its layout. Files of older layouts are
+//! upgraded as they are loaded, one version at a time, by the [migrations](MIGRATIONS).
+
+use aoclib::config::Config;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
+use toml_edit::{Document, Item, Value};
+
+use crate::{answer::Extraction, git::GitSource, Error};
+
+/// Base URL from which stock templates are downloaded by default.
+pub const DEFAULT_TEMPLATE_BASE_URL: &str = "https://raw.githubusercontent.com/coriolinus/aoctool";
+
+/// Pattern for the names of day crates by default.
+pub const DEFAULT_DAY_NAME: &str = "day{day:02}";
+
+/// Pattern for the names of day crates in a workspace shared between years.
+pub const SHARED_WORKSPACE_DAY_NAME: &str = "y{year}d{day:02}";
+
+/// Message of the commit made after `init`, by default.
+pub const DEFAULT_INIT_COMMIT_MESSAGE: &str = "scaffold {package_name}";
+
+/// Version of the settings layout written by this version of `aoctool`.
+pub const SETTINGS_VERSION: i64 = 1;
+
+/// Migrations which upgrade each version of the settings layout to the next, starting from version
+/// 0: files written before the layout was versioned.
+const MIGRATIONS: &[fn(&mut Document) -> Result<(), Error>] = &[rename_post_init];
+
+/// Version 1 spells `hooks.post_init` in kebab-case, like every other key.
+fn rename_post_init(document: &mut Document) -> Result<(), Error> {
+    let Some(hooks) = document.get_mut("hooks").and_then(Item::as_table_like_mut) else {
+        return Ok(());
+    };
+    if let Some(post_init) = hooks.remove("post_init") {
+        if !hooks.contains_key("post-init") {
+            hooks.insert("post-init", post_init);
+        }
+    }
+    Ok(())
+}
+
+/// Upgrade a settings document to the current layout.
+///
+/// Returns whether anything changed.
+fn migrate(document: &mut Document) -> Result<bool, Error> {
+    let version = match document.get("version") {
+        None => 0,
+        Some(version) => version.as_integer().ok_or(Error::MalformedToml)?,
+    };
+    if version > SETTINGS_VERSION {
+        return Err(Error::SettingsTooNew(version, SETTINGS_VERSION));
+    }
+    if version == SETTINGS_VERSION {
+        return Ok(false);
+    }
+    for migration in &MIGRATIONS[version.max(0) as usize..] {
+        migration(document)?;
+    }
+    document["version"] = toml_edit::value(SETTINGS_VERSION);
+    Ok(true)
+}
+
+/// Path to the `aoctool` settings file.
+pub fn path() -> PathBuf {
+    aoclib::config::path().with_file_name("aoctool.toml")
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Settings {
+    /// Never download templates; use only what is already in the template directory.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub local_templates: bool,
+
+    /// Git ref of the aoctool repository from which stock templates are downloaded.
+    ///
+    /// When unset, the tag matching the running version is used, falling back to `master`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_ref: Option<String>,
+
+    /// Base URL from which stock templates are downloaded, for forks and mirrors.
+    ///
+    /// Templates are requested from `<template-base-url>/<template-ref>/<path>`. When unset,
+    /// [`DEFAULT_TEMPLATE_BASE_URL`] is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_base_url: Option<String>,
+
+    /// Year used when none is given and none can be inferred from the current directory, rather
+    /// than this year: outside December, usually the year being worked on is a past one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_year: Option<u32>,
+
+    /// Pattern for the names of day crates and their directories.
+    ///
+    /// `{year}` and `{day}` are substituted, optionally zero-padded as in `{day:02}`. When unset,
+    /// [`DEFAULT_DAY_NAME`] is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day_name: Option<String>,
+
+    /// Regular expression matched against the current directory to infer the year and day, from
+    /// its named groups `year` and `day`.
+    ///
+    /// When unset, [`DEFAULT_CWD_PATTERN`](crate::infer::DEFAULT_CWD_PATTERN) is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd_pattern: Option<String>,
+
+    /// Templates for the workspace files rendered by `init-year`, replacing the stock templates of
+    /// the same names.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year_templates: Option<PathBuf>,
+
+    /// Template for the crate created by `init-year --with-utils`, replacing the stock template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utils_template: Option<PathBuf>,
+
+    /// Toolchain channel pinned in the `rust-toolchain.toml` of new year workspaces.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<String>,
+
+    /// Day templates used for every year, beneath any per-year templates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day_templates: Option<PathBuf>,
+
+    /// How to find the answer in a day binary's output.
+    ///
+    /// When unset, the last non-empty line of the output is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extract: Option<Extraction>,
+
+    /// Id of the private leaderboard shown by `leaderboard` by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leaderboard: Option<u64>,
+
+    /// Session keys for named accounts other than the default.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub accounts: BTreeMap<String, String>,
+
+    /// Named profiles, each with its own session and paths, selected by `aoc --profile <name>`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileSettings>,
+
+    /// Flags passed to each subcommand by default, by the subcommand's name and then the flag's,
+    /// as in `[defaults.init] with-bench = true`.
+    ///
+    /// Flags given on the command line take precedence.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub defaults: BTreeMap<String, BTreeMap<String, toml::Value>>,
+
+    /// Template rendering.
+    pub template: TemplateSettings,
+
+    /// Commands run at points in the workflow.
+    pub hooks: HookSettings,
+
+    /// Defaults for how `run` builds solutions.
+    pub cargo: CargoSettings,
+
+    /// Benchmarking, for `bench`.
+    pub bench: BenchSettings,
+
+    /// Version control.
+    pub git: GitSettings,
+
+    /// GitHub, for `init-year --create-github-repo`.
+    pub github: GitHubSettings,
+
+    /// Languages other than Rust, for `init --lang`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub languages: BTreeMap<String, LanguageSettings>,
+
+    /// Per-year overrides.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub years: BTreeMap<u32, YearSettings>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct TemplateSettings {
+    /// User-defined variables, available within templates alongside the built-in ones.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HookSettings {
+    /// Shell commands run after a day is initialized.
+    ///
+    /// Commands may use the template variables, and `{day_dir}`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub post_init: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CargoSettings {
+    /// Cargo profile with which to build solutions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
+    /// Features of the day crates to enable.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+
+    /// Target directory in which to build solutions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_dir: Option<PathBuf>,
+
+    /// Extra flags for rustc, appended to any in `RUSTFLAGS`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rustflags: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BenchSettings {
+    /// Slowdown of a part's mean time, in percent, beyond which it is flagged as regressed.
+    ///
+    /// When unset, [`DEFAULT_REGRESSION_THRESHOLD`](crate::bench::DEFAULT_REGRESSION_THRESHOLD)
+    /// is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regression_threshold: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct GitSettings {
+    /// Commit each newly initialized day, as with `init --git-commit`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub commit_on_init: bool,
+
+    /// Message of the commit made after `init`, rendered with the template variables.
+    ///
+    /// When unset, [`DEFAULT_INIT_COMMIT_MESSAGE`] is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_commit_message: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct GitHubSettings {
+    /// Personal access token with permission to create repositories.
+    ///
+    /// When unset, the `GITHUB_TOKEN` environment variable is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct LanguageSettings {
+    /// Day templates for this language.
+    ///
+    /// When unset, `<year day templates>-<language>` is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub templates: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct YearSettings {
+    /// Git repository from which this year's day templates are cloned, as `<url>[#<ref>]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day_templates_git: Option<String>,
+
+    /// How to find the answer in this year's day binaries' output.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extract: Option<Extraction>,
+
+    /// Pattern for the names of this year's day crates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day_name: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ProfileSettings {
+    /// Website session key, replacing the default session.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+
+    /// Per-year paths, replacing those of the main configuration.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub years: BTreeMap<u32, PathSettings>,
+}
+
+/// Paths of a year, replacing those of the main configuration where set.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PathSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub implementation: Option<PathBuf>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_files: Option<PathBuf>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day_template: Option<PathBuf>,
+}
+
+impl ProfileSettings {
+    /// Apply the profile to the configuration.
+    ///
+    /// Only what the profile sets is replaced; everything else is left as configured.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(session) = &self.session {
+            config.session = session.clone();
+        }
+        for (&year, paths) in &self.years {
+            paths.apply(config, year);
+        }
+    }
+}
+
+impl PathSettings {
+    /// Replace a year's configured paths with those which are set.
+    pub fn apply(&self, config: &mut Config, year: u32) {
+        let paths = config.paths.entry(year).or_default();
+        if let Some(implementation) = &self.implementation {
+            paths.implementation = Some(implementation.clone());
+        }
+        if let Some(input_files) = &self.input_files {
+            paths.input_files = Some(input_files.clone());
+        }
+        if let Some(day_template) = &self.day_template {
+            paths.day_template = Some(day_template.clone());
+        }
+    }
+}
+
+impl Settings {
+    /// Load the settings file, upgrading it if its layout is older than this version's.
+    ///
+    /// If it does not exist, the defaults are used.
+    pub fn load() -> Result<Self, Error> {
+        let path = path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = std::fs::read_to_string(&path)
+            .map_err(|err| Error::Io("reading settings file", err))?;
+        let mut document = Document::from_str(&data)?;
+        if migrate(&mut document)? {
+            // editing the document preserves the file's comments and formatting. Should the file
+            // be read-only, the upgrade is simply repeated each time it is loaded.
+            let _ = std::fs::write(&path, document.to_string());
+        }
+        let settings: Self = toml::from_str(&document.to_string()).map_err(Error::ParseSettings)?;
+        settings.check_day_names()?;
+        Ok(settings)
+    }
+
+    /// Ensure that every day name pattern substitutes only placeholders which
+    /// [`format_day_name`] understands.
+    fn check_day_names(&self) -> Result<(), Error> {
+        self.day_name
+            .iter()
+            .chain(
+                self.years
+                    .values()
+                    .filter_map(|year_settings| year_settings.day_name.as_ref()),
+            )
+            .try_for_each(|pattern| check_day_name(pattern))
+    }
+
+    /// Save the settings file.
+    pub fn save(&self) -> Result<(), Error> {
+        let path = path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| Error::Io("creating settings directory", err))?;
+        }
+        let data = format!(
+            "version = {SETTINGS_VERSION}\n\n{}",
+            toml::to_string_pretty(self).map_err(Error::SerializeSettings)?
+        );
+        tracing::info!(path = %path.display(), "writing settings file");
+        std::fs::write(&path, data).map_err(|err| Error::Io("writing settings file", err))
+    }
+
+    /// A named profile.
+    pub fn profile(&self, name: &str) -> Result<&ProfileSettings, Error> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| Error::UnknownProfile(name.to_string()))
+    }
+
+    /// The answer extraction rule in effect for a particular year.
+    pub fn extraction(&self, year: u32) -> Extraction {
+        self.years
+            .get(&year)
+            .and_then(|year_settings| year_settings.extract.clone())
+            .or_else(|| self.extract.clone())
+            .unwrap_or_default()
+    }
+
+    /// The pattern for the names of a year's day crates.
+    pub fn day_name_pattern(&self, year: u32) -> &str {
+        self.years
+            .get(&year)
+            .and_then(|year_settings| year_settings.day_name.as_deref())
+            .or(self.day_name.as_deref())
+            .unwrap_or(DEFAULT_DAY_NAME)
+    }
+
+    /// The name of a day's crate and directory.
+    pub fn day_name(&self, year: u32, day: u8) -> String {
+        format_day_name(self.day_name_pattern(year), year, day)
+    }
+
+    /// The git repository from which a year's day templates are cloned, if any.
+    pub fn day_templates_git(&self, year: u32) -> Option<GitSource> {
+        self.years
+            .get(&year)
+            .and_then(|year_settings| year_settings.day_templates_git.as_deref())
+            .map(|source| source.parse().expect("parsing a git source is infallible"))
+    }
+}
+
+/// What is shown in place of a secret.
+pub const REDACTED: &str = "<redacted>";
+
+/// Replace the secrets in a configuration or settings file with [`REDACTED`]: session keys and
+/// tokens anywhere, in tables and in arrays, and the session keys of named accounts.
+///
+/// References to keychain entries are not secret, and are left alone.
+pub fn redact_secrets(document: &str) -> Result<String, Error> {
+    fn redact_table(table: &mut dyn toml_edit::TableLike, all: bool) {
+        for (key, item) in table.iter_mut() {
+            let secret = all || matches!(key.get(), "session" | "token");
+            if let Some(table) = item.as_table_like_mut() {
+                redact_table(table, key.get() == "accounts");
+            } else if let Some(tables) = item.as_array_of_tables_mut() {
+                for table in tables.iter_mut() {
+                    redact_table(table, false);
+                }
+            } else if let Some(value) = item.as_value_mut() {
+                redact_value(value, secret);
+            }
+        }
+    }
+
+    fn redact_value(value: &mut Value, secret: bool) {
+        let redact = secret
+            && value
+                .as_str()
+                .is_some_and(|value| crate::keychain::referenced(value).is_none());
+        if redact {
+            *value = Value::from(REDACTED);
+            return;
+        }
+        match value {
+            Value::Array(array) => {
+                for value in array.iter_mut() {
+                    redact_value(value, secret);
+                }
+            }
+            Value::InlineTable(table) => redact_table(table, false),
+            _ => {}
+        }
+    }
+
+    let mut document = Document::from_str(document)?;
+    redact_table(document.as_table_mut(), false);
+    Ok(document.to_string())
+}
+
+/// A glob matching every name of a day name pattern, as for workspace members: `"day{day:02}"`
+/// becomes `"day*"`.
+pub fn day_name_glob(pattern: &str) -> String {
+    let mut glob = placeholder_pattern().replace_all(pattern, "*").into_owned();
+    // `**` means something else entirely to cargo
+    while glob.contains("**") {
+        glob = glob.replace("**", "*");
+    }
+    glob
+}
+
+fn placeholder_pattern() -> regex::Regex {
+    regex::Regex::new(r"\{(year|day)(?::0(\d+))?\}").expect("placeholder pattern is valid")
+}
+
+/// Ensure that a day name pattern substitutes only `{year}` and `{day}`, each optionally
+/// zero-padded.
+///
+/// Anything else in braces, such as `{day:2}`, is refused rather than substituted wrongly: a crate
+/// name can't be padded with spaces.
+pub fn check_day_name(pattern: &str) -> Result<(), Error> {
+    let placeholder = placeholder_pattern();
+    let braces = regex::Regex::new(r"\{[^{}]*\}").expect("brace pattern is valid");
+    match braces.find_iter(pattern).find(|found| {
+        !placeholder
+            .find(found.as_str())
+            .is_some_and(|known| known.as_str() == found.as_str())
+    }) {
+        Some(unknown) => Err(Error::UnknownPlaceholder(
+            pattern.to_string(),
+            unknown.as_str().to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Substitute `{year}` and `{day}` into a day name pattern.
+///
+/// A substitution may be zero-padded to a width, as in `{day:02}`. Patterns are
+/// [checked](check_day_name) as the settings are loaded.
+pub fn format_day_name(pattern: &str, year: u32, day: u8) -> String {
+    placeholder_pattern()
+        .replace_all(pattern, |captures: &regex::Captures| {
+            let value = match &captures[1] {
+                "year" => year.to_string(),
+                _ => day.to_string(),
+            };
+            let width = captures
+                .get(2)
+                .and_then(|width| width.as_str().parse().ok())
+                .unwrap_or_default();
+            format!("{value:0>width$}")
+        })
+        .into_owned()
+}
@@ -0,0 +1,175 @@
+//! Submit answers to the website, with guard rails.
+//!
+//! An answer computed from an example input is never the answer to the real puzzle, and the
+//! website imposes an escalating timeout on wrong answers. Before submitting, we check where the
+//! answer came from.
+
+use aoclib::config::Config;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::{answer::Part, examples, nonblocking, progress::Progress, vfs::Fs, Error, Host};
+
+/// Where the input which produced an answer came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputProvenance {
+    /// The real puzzle input for the configured session.
+    Real,
+    /// A recorded example, by index.
+    Example(usize),
+    /// Some other file.
+    Other(PathBuf),
+}
+
+impl InputProvenance {
+    /// Determine the provenance of an input file.
+    pub fn of(config: &Config, year: u32, day: u8, input: &Path) -> Self {
+        let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_owned());
+        let input_canonical = canonical(input);
+
+        if input_canonical == canonical(&config.input_for(year, day)) {
+            return Self::Real;
+        }
+
+        let mut index = 1;
+        loop {
+            let example = examples::example_input(config, year, day, index);
+            if !example.exists() {
+                break;
+            }
+            if input_canonical == canonical(&example) {
+                return Self::Example(index);
+            }
+            index += 1;
+        }
+
+        Self::Other(input.to_owned())
+    }
+}
+
+/// Ensure that answers computed from an input may be submitted: that it is the real input.
+///
+/// Checked before a solution is run for its answer, this saves running it in vain.
+pub fn check_provenance(provenance: &InputProvenance) -> Result<(), Error> {
+    match provenance {
+        InputProvenance::Real => Ok(()),
+        InputProvenance::Example(index) => Err(Error::SubmitExampleInput(*index)),
+        InputProvenance::Other(path) => Err(Error::SubmitOtherInput(path.display().to_string())),
+    }
+}
+
+/// Ensure that an answer is safe to submit.
+///
+/// This refuses answers which were not computed from the real input, answers which are identical to
+/// a recorded example answer for the same part, and answers to parts which are already solved,
+/// according to the year's [progress](crate::progress).
+pub fn check_submission(
+    fs: &dyn Fs,
+    config: &Config,
+    year: u32,
+    day: u8,
+    part: Part,
+    answer: &str,
+    provenance: &InputProvenance,
+) -> Result<(), Error> {
+    check_provenance(provenance)?;
+
+    for example in examples::examples_for(config, year, day)? {
+        if example.answers.get(part) == Some(answer) {
+            return Err(Error::SubmitExampleAnswer(example.index, part));
+        }
+    }
+
+    if Progress::load(fs, config, year)?.day(day).stars() >= part.level() {
+        return Err(Error::AlreadySolved(day, part));
+    }
+
+    Ok(())
+}
+
+/// The website's response to a submission.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Correct,
+    Incorrect,
+    /// An answer was submitted too recently.
+    TooSoon,
+    /// This part is already solved, or not yet unlocked.
+    WrongLevel,
+    /// The response could not be interpreted; contains the response body.
+    Unknown(String),
+}
+
+impl Outcome {
+    fn from_response(body: &str) -> Self {
+        if body.contains("That's the right answer") {
+            Self::Correct
+        } else if body.contains("That's not the right answer") {
+            Self::Incorrect
+        } else if body.contains("You gave an answer too recently") {
+            Self::TooSoon
+        } else if body.contains("You don't seem to be solving the right level") {
+            Self::WrongLevel
+        } else {
+            Self::Unknown(body.to_string())
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Correct => write!(f, "that's the right answer"),
+            Self::Incorrect => write!(f, "that's not the right answer"),
+            Self::TooSoon => write!(f, "an answer was submitted too recently; wait and retry"),
+            Self::WrongLevel => write!(f, "this part is already solved or not yet unlocked"),
+            Self::Unknown(_) => write!(f, "unrecognized response from the website"),
+        }
+    }
+}
+
+/// Submit an answer, after checking that it is safe to do so.
+///
+/// Correct answers are recorded in the year's [progress](crate::progress). A session kept in the
+/// keychain is retrieved from it.
+pub fn submit(
+    host: &Host,
+    config: &Config,
+    year: u32,
+    day: u8,
+    part: Part,
+    answer: &str,
+    provenance: &InputProvenance,
+) -> Result<Outcome, Error> {
+    check_submission(
+        host.fs.as_ref(),
+        config,
+        year,
+        day,
+        part,
+        answer,
+        provenance,
+    )?;
+
+    let url = format!("https://adventofcode.com/{year}/day/{day}/answer");
+    let level = part.level().to_string();
+    tracing::info!(%url, level, "submitting answer");
+    let session = crate::keychain::resolve(&config.session)?;
+    let response = nonblocking::block_on(host.http.post_form(
+        &url,
+        Some(&nonblocking::session_cookie(&session)),
+        &[("level", level.as_str()), ("answer", answer)],
+    ))?;
+    if !response.is_success() {
+        return Err(Error::HttpStatus(response.status, url));
+    }
+
+    let outcome = Outcome::from_response(&response.text());
+    if outcome == Outcome::Correct {
+        let mut progress = Progress::load(host.fs.as_ref(), config, year)?;
+        progress.record_answer(day, part, answer);
+        progress.save(host.fs.as_ref(), config, year)?;
+    }
+    Ok(outcome)
+}
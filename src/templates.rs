@@ -0,0 +1,1321 @@
+//! Day templates: locating, downloading, and rendering them.
+//!
+//! By default, every file in a template directory (other than a `.git` directory) is rendered
+//! into the same relative path in the day crate. The stock templates are `Cargo.toml`,
+//! `src/lib.rs`, `src/main.rs`, and `benches/bench.rs`. A template directory may instead contain
+//! a `template.toml` manifest declaring its files:
+//!
+//! ```toml
+//! [[file]]
+//! source = "Cargo.toml"
+//!
+//! [[file]]
+//! source = "bin.rs"
+//! destination = "src/bin/{package_name}.rs"
+//!
+//! [[file]]
+//! source = "README.md"
+//! verbatim = true
+//! ```
+//!
+//! Destinations default to the source path, and are themselves rendered as templates. Verbatim
+//! files are copied without rendering. A manifest may also select the template engine with
+//! `engine = "tera"`; a manifest which declares no files renders every file in the directory.
+//!
+//! Files are also copied verbatim if their extension is in [`VERBATIM_EXTENSIONS`] or in the
+//! manifest's `verbatim-extensions`, or if they are not UTF-8.
+//!
+//! A global template directory may be configured in the settings. Years without their own template
+//! directory use it as-is; years with their own are layered over it, file by file.
+//!
+//! Days in other languages (`init --lang`) use a separate template directory per language, which
+//! is never layered. Stock templates exist for the languages in [`LANGUAGE_TEMPLATES`]; any
+//! other language works once its template directory has been populated.
+
+use aoclib::config::Config;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use tinytemplate::TinyTemplate;
+
+use crate::{http::HttpClient, settings::Settings, vfs::Fs, DayOpts, Error, Host};
+
+/// The stock templates, relative to the template directory, and the copies embedded in this
+/// binary.
+const STOCK_TEMPLATES: &[(&str, &str)] = &[
+    ("Cargo.toml", include_str!("../day-template/Cargo.toml")),
+    ("src/lib.rs", include_str!("../day-template/src/lib.rs")),
+    ("src/main.rs", include_str!("../day-template/src/main.rs")),
+    (
+        "benches/bench.rs",
+        include_str!("../day-template/benches/bench.rs"),
+    ),
+];
+
+/// Stock templates for languages other than Rust, by language, and the copies embedded in this
+/// binary.
+pub const LANGUAGE_TEMPLATES: &[(&str, &[(&str, &str)])] = &[(
+    "python",
+    &[(
+        "solution.py",
+        include_str!("../lang-templates/python/solution.py"),
+    )],
+)];
+
+/// Name of the optional manifest within a template directory.
+pub const MANIFEST: &str = "template.toml";
+
+/// Extensions of files which are always copied without rendering.
+pub const VERBATIM_EXTENSIONS: &[&str] = &[
+    "bin", "gif", "gz", "ico", "jpeg", "jpg", "lock", "pdf", "png", "svg", "zip",
+];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Manifest {
+    /// Template engine used to render this directory.
+    #[serde(default)]
+    pub engine: Engine,
+    /// Extensions, in addition to [`VERBATIM_EXTENSIONS`], of files to copy without rendering.
+    #[serde(default)]
+    pub verbatim_extensions: Vec<String>,
+    /// Files to render. If empty, every file in the directory is rendered.
+    #[serde(rename = "file", default)]
+    pub files: Vec<TemplateFile>,
+}
+
+/// A template engine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Engine {
+    /// [TinyTemplate](https://docs.rs/tinytemplate): `{variable}`, with literal braces escaped
+    /// as `\{`.
+    #[default]
+    TinyTemplate,
+    /// [Tera](https://keats.github.io/tera/docs/): `{{ variable }}`, with loops and conditionals.
+    Tera,
+}
+
+impl std::fmt::Display for Engine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TinyTemplate => write!(f, "tinytemplate"),
+            Self::Tera => write!(f, "tera"),
+        }
+    }
+}
+
+/// A file in a template directory.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemplateFile {
+    /// The template directory containing this file.
+    #[serde(skip)]
+    pub root: PathBuf,
+    /// Path relative to the template directory.
+    pub source: PathBuf,
+    /// Path relative to the day crate. Defaults to `source`.
+    pub destination: Option<String>,
+    /// Copy this file without rendering it.
+    #[serde(default)]
+    pub verbatim: bool,
+}
+
+impl TemplateFile {
+    fn new(root: &Path, source: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.to_owned(),
+            source: source.into(),
+            destination: None,
+            verbatim: false,
+        }
+    }
+
+    /// Full path to the template.
+    pub fn path(&self) -> PathBuf {
+        self.root.join(&self.source)
+    }
+
+    /// The unrendered destination, which identifies this file when layering template sets.
+    pub fn destination_template(&self) -> String {
+        self.destination
+            .clone()
+            .unwrap_or_else(|| self.source.to_string_lossy().into_owned())
+    }
+}
+
+/// Load the manifest of a template directory, if it has one.
+pub fn manifest(fs: &dyn Fs, template_dir: &Path) -> Result<Option<Manifest>, Error> {
+    let manifest_path = template_dir.join(MANIFEST);
+    if !fs.exists(&manifest_path) {
+        return Ok(None);
+    }
+    let data = fs
+        .read_to_string(&manifest_path)
+        .map_err(|err| Error::Io("reading template manifest", err))?;
+    toml::from_str(&data)
+        .map(Some)
+        .map_err(|err| Error::ParseTemplateManifest(err, manifest_path.display().to_string()))
+}
+
+/// The files of one or more template directories, and how to render them.
+///
+/// This is what `aoc init` renders days with, and it can drive other scaffolding too: load a
+/// directory of templates, and render it with any serializable context.
+///
+/// ```no_run
+/// # fn main() -> Result<(), aoctool::Error> {
+/// use aoctool::{templates::TemplateSet, vfs::RealFs, DayOpts};
+///
+/// #[derive(serde::Serialize)]
+/// struct Notes {
+///     title: String,
+/// }
+///
+/// let template_set = TemplateSet::load(&RealFs, "templates/notes".as_ref())?;
+/// let context = Notes {
+///     title: "Historian Hysteria".into(),
+/// };
+/// template_set.render_into(&RealFs, &context, "notes/day01".as_ref(), &DayOpts::default())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TemplateSet {
+    pub engine: Engine,
+    /// Extensions of files to copy without rendering, beyond [`VERBATIM_EXTENSIONS`].
+    pub verbatim_extensions: Vec<String>,
+    pub files: Vec<TemplateFile>,
+}
+
+impl TemplateSet {
+    /// Load the template set in a directory: the files in its manifest, or every file it
+    /// contains.
+    pub fn load(fs: &dyn Fs, template_dir: &Path) -> Result<Self, Error> {
+        Self::load_layered(fs, &[template_dir])
+    }
+
+    /// Load template directories layered over each other.
+    ///
+    /// Later directories take precedence: a file with the same destination as one in an earlier
+    /// directory replaces it, and a manifest's engine replaces any earlier choice. Verbatim
+    /// extensions accumulate. Directories which do not exist are skipped.
+    pub fn load_layered(fs: &dyn Fs, template_dirs: &[&Path]) -> Result<Self, Error> {
+        let mut engine = Engine::default();
+        let mut verbatim_extensions = Vec::new();
+        let mut files: Vec<TemplateFile> = Vec::new();
+        for template_dir in template_dirs {
+            if !fs.is_dir(template_dir) {
+                continue;
+            }
+            let layer = match manifest(fs, template_dir)? {
+                Some(manifest) => {
+                    engine = manifest.engine;
+                    verbatim_extensions.extend(manifest.verbatim_extensions);
+                    manifest.files
+                }
+                None => Vec::new(),
+            };
+            let layer = if layer.is_empty() {
+                let mut paths = Vec::new();
+                walk(fs, template_dir, Path::new(""), &mut paths)?;
+                paths.sort();
+                paths
+                    .into_iter()
+                    .map(|path| TemplateFile::new(template_dir, path))
+                    .collect()
+            } else {
+                layer
+                    .into_iter()
+                    .map(|file| TemplateFile {
+                        root: template_dir.to_path_buf(),
+                        ..file
+                    })
+                    .collect()
+            };
+            for file in layer {
+                let destination = file.destination_template();
+                files.retain(|existing| existing.destination_template() != destination);
+                files.push(file);
+            }
+        }
+        Ok(Self {
+            engine,
+            verbatim_extensions,
+            files,
+        })
+    }
+
+    /// Whether a file is to be copied without rendering.
+    pub fn is_verbatim(&self, template: &TemplateFile) -> bool {
+        template.verbatim
+            || template
+                .source
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .map(|extension| {
+                    VERBATIM_EXTENSIONS
+                        .iter()
+                        .copied()
+                        .chain(self.verbatim_extensions.iter().map(String::as_str))
+                        .any(|verbatim| verbatim.eq_ignore_ascii_case(extension))
+                })
+                .unwrap_or_default()
+    }
+}
+
+/// Collect the paths, relative to `root`, of every file under `root/relative`.
+fn walk(fs: &dyn Fs, root: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    let entries = fs
+        .read_dir(&root.join(relative))
+        .map_err(|err| Error::Io("reading template directory", err))?;
+    for entry in entries {
+        let file_name = entry.file_name().unwrap_or_default();
+        let path = relative.join(file_name);
+        if fs.is_dir(&entry) {
+            if file_name != ".git" {
+                walk(fs, root, &path, files)?;
+            }
+        } else if path != Path::new(MANIFEST) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Whether a year has its own template directory, as opposed to using the global one.
+fn has_year_templates(config: &Config, settings: &Settings, year: u32) -> bool {
+    settings.day_templates_git(year).is_some()
+        || config
+            .paths
+            .get(&year)
+            .map(|paths| paths.day_template.is_some())
+            .unwrap_or_default()
+}
+
+/// The directory into which the stock templates are downloaded for a year.
+///
+/// This is the year's own template directory, unless it has none and a global template directory
+/// is configured.
+pub fn stock_template_dir(config: &Config, settings: &Settings, year: u32) -> PathBuf {
+    match &settings.day_templates {
+        Some(global) if !has_year_templates(config, settings, year) => global.clone(),
+        _ => config.day_template(year),
+    }
+}
+
+/// The template directories for a year, in layering order: the global template directory, if
+/// configured, followed by the year's own template directory, if it has one.
+pub fn template_dirs(config: &Config, settings: &Settings, year: u32) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(global) = &settings.day_templates {
+        dirs.push(global.clone());
+    }
+    if has_year_templates(config, settings, year) || dirs.is_empty() {
+        dirs.push(config.day_template(year));
+    }
+    dirs
+}
+
+/// Stock templates which are missing, and would be downloaded on the next `init`.
+///
+/// Stock templates are downloaded into the [stock template directory](stock_template_dir), unless
+/// the templates come from a git repository, the directory has a manifest, or the year's
+/// templates are layered over the global template directory.
+pub fn missing_stock_templates(
+    fs: &dyn Fs,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+) -> Vec<PathBuf> {
+    let stock_dir = stock_template_dir(config, settings, year);
+    let is_layered = settings.day_templates.is_some() && has_year_templates(config, settings, year);
+    if settings.day_templates_git(year).is_some()
+        || is_layered
+        || fs.exists(&stock_dir.join(MANIFEST))
+    {
+        return Vec::new();
+    }
+    STOCK_TEMPLATES
+        .iter()
+        .map(|(template, _)| stock_dir.join(template))
+        .filter(|path| !fs.exists(path))
+        .collect()
+}
+
+/// Ensure the template directories for a year exist and are initialized.
+///
+/// The directories are returned in [layering order](template_dirs).
+///
+/// If the year's templates come from a git repository, it is cloned or updated. Otherwise,
+/// [missing stock templates](missing_stock_templates) are [installed](install_stock_template).
+///
+/// With `settings.local_templates`, the network is never used.
+pub(crate) fn ensure_template_dirs(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+) -> Result<Vec<PathBuf>, Error> {
+    if let Some(source) = settings.day_templates_git(year) {
+        if !settings.local_templates {
+            source.sync(&config.day_template(year))?;
+        }
+    }
+
+    let stock_dir = stock_template_dir(config, settings, year);
+    for template_path in missing_stock_templates(host.fs.as_ref(), config, settings, year) {
+        let template = template_path
+            .strip_prefix(&stock_dir)
+            .expect("missing stock templates are within the stock template dir")
+            .to_string_lossy()
+            .into_owned();
+        install_stock_template(
+            host,
+            settings,
+            &format!("day-template/{template}"),
+            embedded_template(STOCK_TEMPLATES, &template),
+            &template_path,
+        )?;
+    }
+
+    Ok(template_dirs(config, settings, year))
+}
+
+/// The template directory for days of a year in a language other than Rust.
+///
+/// This is configured per language in the settings, defaulting to a sibling of the year's template
+/// directory: `day-template` becomes `day-template-python`.
+pub fn language_template_dir(
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    lang: &str,
+) -> PathBuf {
+    if let Some(templates) = settings
+        .languages
+        .get(lang)
+        .and_then(|language| language.templates.clone())
+    {
+        return templates;
+    }
+    let year_dir = config.day_template(year);
+    let mut dir_name = year_dir.file_name().unwrap_or_default().to_os_string();
+    dir_name.push("-");
+    dir_name.push(lang);
+    year_dir.with_file_name(dir_name)
+}
+
+/// The stock templates of a language.
+fn language_templates(lang: &str) -> &'static [(&'static str, &'static str)] {
+    LANGUAGE_TEMPLATES
+        .iter()
+        .find(|(name, _)| *name == lang)
+        .map(|(_, templates)| *templates)
+        .unwrap_or_default()
+}
+
+/// Stock templates of a language which are missing from its template directory.
+///
+/// Languages without stock templates, and directories with a manifest, are never missing any.
+fn missing_language_templates(fs: &dyn Fs, template_dir: &Path, lang: &str) -> Vec<PathBuf> {
+    if fs.exists(&template_dir.join(MANIFEST)) {
+        return Vec::new();
+    }
+    language_templates(lang)
+        .iter()
+        .map(|(template, _)| template_dir.join(template))
+        .filter(|path| !fs.exists(path))
+        .collect()
+}
+
+/// Stock templates which are missing for a day, and would be downloaded on the next `init`.
+pub fn missing_day_templates(
+    fs: &dyn Fs,
+    config: &Config,
+    settings: &Settings,
+    day_opts: &DayOpts,
+    year: u32,
+) -> Vec<PathBuf> {
+    match day_opts.language() {
+        Some(lang) => missing_language_templates(
+            fs,
+            &language_template_dir(config, settings, year, lang),
+            lang,
+        ),
+        None => missing_stock_templates(fs, config, settings, year),
+    }
+}
+
+/// Ensure the template directory for a language exists, installing any missing stock templates.
+fn ensure_language_template_dir(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    lang: &str,
+) -> Result<PathBuf, Error> {
+    let template_dir = language_template_dir(config, settings, year, lang);
+    for template_path in missing_language_templates(host.fs.as_ref(), &template_dir, lang) {
+        let template = template_path
+            .strip_prefix(&template_dir)
+            .expect("missing language templates are within the language template dir")
+            .to_string_lossy()
+            .into_owned();
+        install_stock_template(
+            host,
+            settings,
+            &format!("lang-templates/{lang}/{template}"),
+            embedded_template(language_templates(lang), &template),
+            &template_path,
+        )?;
+    }
+
+    if !host.fs.is_dir(&template_dir) {
+        return Err(Error::NoLanguageTemplates(
+            lang.to_string(),
+            template_dir.display().to_string(),
+        ));
+    }
+    Ok(template_dir)
+}
+
+/// The embedded copy of a stock template.
+fn embedded_template(templates: &[(&str, &'static str)], template: &str) -> &'static str {
+    templates
+        .iter()
+        .find(|(name, _)| *name == template)
+        .map(|(_, contents)| *contents)
+        .expect("missing stock templates are among the embedded templates")
+}
+
+/// Install a missing stock template at `destination`.
+///
+/// It is downloaded from `template` in this project's repository. If that fails, or with
+/// `settings.local_templates`, the copy embedded in this binary is used instead, so that a fresh
+/// install works without network access.
+fn install_stock_template(
+    host: &Host,
+    settings: &Settings,
+    template: &str,
+    embedded: &str,
+    destination: &Path,
+) -> Result<(), Error> {
+    let fs = host.fs.as_ref();
+    // if we have a subdirectory of template_dir, like `crate/src/foo.rs`, this will ensure everything exists
+    if let Some(parent) = destination.parent() {
+        fs.create_dir_all(parent)
+            .map_err(|err| Error::Io("creating template parent directory", err))?;
+    }
+    if fs.exists(destination) {
+        return Err(Error::Io(
+            "creating template file",
+            std::io::Error::from(std::io::ErrorKind::AlreadyExists),
+        ));
+    }
+    let downloaded = if settings.local_templates {
+        None
+    } else {
+        download_template(host.http.as_ref(), settings, template).ok()
+    };
+    let contents = downloaded.as_deref().unwrap_or_else(|| embedded.as_bytes());
+    fs.write(destination, contents)
+        .map_err(|err| Error::Io("writing template file", err))
+}
+
+/// Git refs of this project's repository from which stock templates are downloaded, in order of
+/// preference.
+///
+/// By default, this is the tag of the running version, falling back to `master` for versions
+/// which were never tagged.
+fn template_refs(settings: &Settings) -> Vec<String> {
+    match &settings.template_ref {
+        Some(reference) => vec![reference.clone()],
+        None => vec![
+            concat!("v", env!("CARGO_PKG_VERSION")).to_string(),
+            "master".to_string(),
+        ],
+    }
+}
+
+/// Download a stock template from this project's repository.
+///
+/// `template` is relative to the repository root.
+fn download_template(
+    http: &dyn HttpClient,
+    settings: &Settings,
+    template: &str,
+) -> Result<Vec<u8>, Error> {
+    let base_url = settings
+        .template_base_url
+        .as_deref()
+        .unwrap_or(crate::settings::DEFAULT_TEMPLATE_BASE_URL)
+        .trim_end_matches('/');
+    let refs = template_refs(settings);
+    let mut refs = refs.iter().peekable();
+    while let Some(reference) = refs.next() {
+        let url = format!("{}/{}/{}", base_url, reference, template);
+        tracing::info!(%url, "downloading template");
+        let response = crate::nonblocking::block_on(http.get(&url, None))?;
+        if response.status == 404 && refs.peek().is_some() {
+            continue;
+        }
+        if !response.is_success() {
+            return Err(Error::HttpStatus(response.status, url));
+        }
+        return Ok(response.body);
+    }
+    unreachable!("there is always at least one template ref")
+}
+
+/// A stock template whose upstream version differs from the local copy.
+#[derive(Debug, Clone)]
+pub struct TemplateUpdate {
+    /// Path relative to the template directory.
+    pub path: String,
+    /// The local copy, if one exists.
+    pub local: Option<String>,
+    pub upstream: String,
+}
+
+impl TemplateUpdate {
+    /// A unified diff from the local copy to the upstream version.
+    pub fn diff(&self) -> String {
+        let local = self.local.as_deref().unwrap_or_default();
+        similar::TextDiff::from_lines(local, &self.upstream)
+            .unified_diff()
+            .header(
+                &format!("local/{}", self.path),
+                &format!("upstream/{}", self.path),
+            )
+            .to_string()
+    }
+
+    /// Replace the local copy with the upstream version.
+    pub fn apply(&self, fs: &dyn Fs, template_dir: &Path) -> Result<(), Error> {
+        let path = template_dir.join(&self.path);
+        if let Some(parent) = path.parent() {
+            fs.create_dir_all(parent)
+                .map_err(|err| Error::Io("creating template parent directory", err))?;
+        }
+        tracing::info!(path = %path.display(), "writing updated template");
+        fs.write(&path, self.upstream.as_bytes())
+            .map_err(|err| Error::Io("writing updated template", err))
+    }
+}
+
+/// Download the latest stock templates, and report those which differ from the local copies.
+///
+/// `progress` is told how many templates have been downloaded, of how many, after each.
+pub fn template_updates(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<Vec<TemplateUpdate>, Error> {
+    if settings.day_templates_git(year).is_some() {
+        return Err(Error::TemplatesFromGit);
+    }
+    let template_dir = stock_template_dir(config, settings, year);
+    let mut updates = Vec::new();
+    for (index, (template, _)) in STOCK_TEMPLATES.iter().enumerate() {
+        let upstream = String::from_utf8_lossy(&download_template(
+            host.http.as_ref(),
+            settings,
+            &format!("day-template/{template}"),
+        )?)
+        .into_owned();
+        progress(index + 1, STOCK_TEMPLATES.len());
+        let local = host.fs.read_to_string(&template_dir.join(template)).ok();
+        if local.as_deref() != Some(upstream.as_str()) {
+            updates.push(TemplateUpdate {
+                path: template.to_string(),
+                local,
+                upstream,
+            });
+        }
+    }
+    Ok(updates)
+}
+
+/// Names and descriptions of the built-in template variables.
+pub const BUILTIN_VARIABLES: &[(&str, &str)] = &[
+    ("year", "puzzle year"),
+    ("day", "puzzle day"),
+    ("package_name", "name of the day crate"),
+    ("date", "puzzle date, as YYYY-MM-DD"),
+    ("iso_week", "ISO week number of the puzzle date"),
+    ("puzzle_url", "URL of the puzzle"),
+    ("puzzle_title", "puzzle title, if the puzzle has unlocked"),
+    ("author", "user.name from the git configuration"),
+    (
+        "aoclib_version",
+        "version of aoclib in the workspace's Cargo.lock",
+    ),
+    ("with_bench", "whether a benchmark scaffold was requested"),
+    ("bin_only", "whether a binary-only crate was requested"),
+    (
+        "lang",
+        "language of the day, as given to --lang; \"rust\" by default",
+    ),
+    ("input_path", "path to the day's puzzle input"),
+    (
+        "workspace_dependencies",
+        "whether the workspace's Cargo.toml has [workspace.dependencies]",
+    ),
+    (
+        "workspace_package",
+        "keys of [workspace.package] in the workspace's Cargo.toml, for day crates to inherit",
+    ),
+    (
+        "package_defaults",
+        "package fields, as `key` and `value`, which [workspace.package] lacks, for day crates to set",
+    ),
+    (
+        "with_utils",
+        "whether the workspace has a utils crate, from init-year --with-utils",
+    ),
+];
+
+/// Variables available within templates.
+///
+/// Keep [`BUILTIN_VARIABLES`] in sync with the fields of this struct.
+#[derive(Debug, Clone, Serialize)]
+pub struct Context {
+    /// User-defined variables.
+    ///
+    /// This is serialized first so that the built-in variables which follow take precedence
+    /// over user-defined variables of the same name.
+    #[serde(flatten)]
+    pub vars: BTreeMap<String, String>,
+    pub year: u32,
+    pub day: u8,
+    pub package_name: String,
+    /// Puzzle date, as `YYYY-MM-DD`.
+    pub date: String,
+    /// ISO week number of the puzzle date.
+    pub iso_week: u8,
+    pub puzzle_url: String,
+    /// Puzzle title, if the puzzle has unlocked and the website was reachable.
+    pub puzzle_title: String,
+    /// `user.name` from the git configuration, if set.
+    pub author: String,
+    /// Version of `aoclib` in the implementation's `Cargo.lock`, if locked.
+    pub aoclib_version: String,
+    /// Whether a benchmark scaffold was requested.
+    pub with_bench: bool,
+    /// Whether a binary-only crate, without a library, was requested.
+    pub bin_only: bool,
+    /// Language of the day.
+    pub lang: String,
+    /// Path to the day's puzzle input.
+    pub input_path: String,
+    /// Whether the workspace has a [utilities crate](crate::utils).
+    pub with_utils: bool,
+    /// Whether the workspace's `Cargo.toml` has `[workspace.dependencies]` for day crates to
+    /// inherit.
+    pub workspace_dependencies: bool,
+    /// Keys of `[workspace.package]` in the workspace's `Cargo.toml`, for day crates to inherit.
+    pub workspace_package: Vec<String>,
+    /// The `version` and `edition`, unless `[workspace.package]` provides them, for day crates to
+    /// set for themselves.
+    pub package_defaults: Vec<PackageField>,
+}
+
+/// A field of a day crate's `[package]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageField {
+    pub key: String,
+    pub value: String,
+}
+
+impl Context {
+    /// The context of a day, without its puzzle title, which [`initialize`](crate::initialize)
+    /// fetches and fills in.
+    pub fn new(
+        host: &Host,
+        config: &Config,
+        settings: &Settings,
+        day_opts: &DayOpts,
+        year: u32,
+        day: u8,
+        package_name: &str,
+    ) -> Result<Self, Error> {
+        let date = time::Date::from_calendar_date(year as i32, time::Month::December, day)
+            .map_err(Error::InvalidDate)?;
+        let fs = host.fs.as_ref();
+        let implementation_dir = config.implementation(year);
+        let workspace_package = crate::manifest::workspace_package_keys(fs, &implementation_dir);
+        Ok(Self {
+            vars: settings.template.vars.clone(),
+            year,
+            day,
+            package_name: package_name.to_string(),
+            date: format!("{year:04}-12-{day:02}"),
+            iso_week: date.iso_week(),
+            puzzle_url: aoclib::website::url_for_day(year, day),
+            puzzle_title: String::new(),
+            author: crate::git::config_value("user.name").unwrap_or_default(),
+            aoclib_version: locked_version(fs, &implementation_dir, "aoclib").unwrap_or_default(),
+            with_bench: day_opts.with_bench,
+            bin_only: day_opts.bin_only,
+            lang: day_opts.language().unwrap_or("rust").to_string(),
+            input_path: config.input_for(year, day).display().to_string(),
+            with_utils: crate::utils::exists(fs, &implementation_dir),
+            workspace_dependencies: crate::manifest::has_workspace_dependencies(
+                fs,
+                &implementation_dir,
+            ),
+            package_defaults: crate::manifest::package_defaults(&workspace_package),
+            workspace_package,
+        })
+    }
+
+    /// A context for the first day of a year with placeholder values, built without using the
+    /// network.
+    pub fn placeholder(config: &Config, settings: &Settings, year: u32) -> Self {
+        let day = 1;
+        Self {
+            vars: settings.template.vars.clone(),
+            year,
+            day,
+            package_name: settings.day_name(year, day),
+            date: format!("{year:04}-12-{day:02}"),
+            iso_week: 48,
+            puzzle_url: aoclib::website::url_for_day(year, day),
+            puzzle_title: "Placeholder Title".to_string(),
+            author: "Placeholder Author".to_string(),
+            aoclib_version: "0.0.0".to_string(),
+            with_bench: false,
+            bin_only: false,
+            lang: "rust".to_string(),
+            input_path: config.input_for(year, day).display().to_string(),
+            with_utils: false,
+            workspace_dependencies: true,
+            workspace_package: vec!["version".to_string(), "edition".to_string()],
+            package_defaults: Vec::new(),
+        }
+    }
+}
+
+/// Find the locked version of a package in a workspace's `Cargo.lock`.
+fn locked_version(fs: &dyn Fs, workspace: &Path, package: &str) -> Option<String> {
+    let lockfile = fs.read_to_string(&workspace.join("Cargo.lock")).ok()?;
+    let lockfile: toml::Table = toml::from_str(&lockfile).ok()?;
+    lockfile
+        .get("package")?
+        .as_array()?
+        .iter()
+        .find(|entry| entry.get("name").and_then(toml::Value::as_str) == Some(package))?
+        .get("version")?
+        .as_str()
+        .map(ToOwned::to_owned)
+}
+
+/// Render a single template string.
+///
+/// Templates are not HTML, so values are substituted without escaping.
+pub fn render_str(
+    engine: Engine,
+    name: &str,
+    text: &str,
+    context: &impl Serialize,
+) -> Result<String, Error> {
+    match engine {
+        Engine::TinyTemplate => {
+            let mut tt = TinyTemplate::new();
+            tt.set_default_formatter(&tinytemplate::format_unescaped);
+            tt.add_template(name, text)
+                .map_err(|err| Error::Template(err, name.to_string()))?;
+            tt.render(name, context)
+                .map_err(|err| Error::Template(err, name.to_string()))
+        }
+        Engine::Tera => {
+            let context = tera::Context::from_serialize(context)
+                .map_err(|err| Error::TeraTemplate(err, name.to_string()))?;
+            tera::Tera::one_off(text, &context, false)
+                .map_err(|err| Error::TeraTemplate(err, name.to_string()))
+        }
+    }
+}
+
+/// A rendered template, not yet written.
+#[derive(Debug, Clone)]
+pub struct RenderedFile {
+    /// Path relative to the day crate.
+    pub destination: PathBuf,
+    pub contents: Vec<u8>,
+}
+
+impl TemplateSet {
+    /// Render every file of this set.
+    ///
+    /// Templates which render to nothing but whitespace are skipped, so a whole file can be made
+    /// conditional, e.g. with `{{ if with_bench }}`.
+    pub fn render(
+        &self,
+        fs: &dyn Fs,
+        context: &impl Serialize,
+    ) -> Result<Vec<RenderedFile>, Error> {
+        let mut rendered_files = Vec::with_capacity(self.files.len());
+        for template in &self.files {
+            let source = template.source.to_string_lossy().into_owned();
+            let destination = match &template.destination {
+                Some(destination) => render_str(self.engine, destination, destination, context)?,
+                None => source.clone(),
+            };
+            let contents = fs
+                .read(&template.path())
+                .map_err(|err| Error::Io("reading template file", err))?;
+            let contents = match String::from_utf8(contents) {
+                Ok(template_text) if !self.is_verbatim(template) => {
+                    let rendered = render_str(self.engine, &source, &template_text, context)?;
+                    if rendered.trim().is_empty() {
+                        continue;
+                    }
+                    rendered.into_bytes()
+                }
+                // verbatim, or not UTF-8 and so not a template
+                Ok(template_text) => template_text.into_bytes(),
+                Err(err) => err.into_bytes(),
+            };
+            rendered_files.push(RenderedFile {
+                destination: destination.into(),
+                contents,
+            });
+        }
+        Ok(rendered_files)
+    }
+}
+
+/// Render the year's templates for a day, without writing anything.
+///
+/// Missing stock templates are not downloaded; see [`missing_day_templates`].
+pub fn preview_templates(
+    fs: &dyn Fs,
+    config: &Config,
+    settings: &Settings,
+    day_opts: &DayOpts,
+    context: &impl Serialize,
+    year: u32,
+) -> Result<Vec<RenderedFile>, Error> {
+    let template_dirs = match day_opts.language() {
+        Some(lang) => vec![language_template_dir(config, settings, year, lang)],
+        None => template_dirs(config, settings, year),
+    };
+    let template_dirs = template_dirs
+        .iter()
+        .map(PathBuf::as_path)
+        .collect::<Vec<_>>();
+    TemplateSet::load_layered(fs, &template_dirs)?.render(fs, context)
+}
+
+/// Render the year's templates into a day directory.
+///
+/// The templates are those `aoc init` would use, downloading missing stock templates; `context`
+/// may be a [`Context`], or any other serializable value, such as one which flattens a `Context`
+/// to add variables of its own. See [`TemplateSet::render_into`] for how existing files are
+/// treated.
+pub fn render_templates_into(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    day_opts: &DayOpts,
+    context: &impl Serialize,
+    day_dir: &Path,
+    year: u32,
+) -> Result<(), Error> {
+    render_templates_recorded(
+        host,
+        config,
+        settings,
+        day_opts,
+        context,
+        day_dir,
+        year,
+        &mut Vec::new(),
+    )
+}
+
+/// [`render_templates_into`], recording each file it writes and its original contents, if it
+/// existed, so that they can be restored.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_templates_recorded(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    day_opts: &DayOpts,
+    context: &impl Serialize,
+    day_dir: &Path,
+    year: u32,
+    originals: &mut Vec<(PathBuf, Option<Vec<u8>>)>,
+) -> Result<(), Error> {
+    // render templates
+    let template_dirs = match day_opts.language() {
+        Some(lang) => vec![ensure_language_template_dir(
+            host, config, settings, year, lang,
+        )?],
+        None => ensure_template_dirs(host, config, settings, year)?,
+    };
+    let template_dirs = template_dirs
+        .iter()
+        .map(PathBuf::as_path)
+        .collect::<Vec<_>>();
+    let fs = host.fs.as_ref();
+    TemplateSet::load_layered(fs, &template_dirs)?
+        .render_into_recorded(fs, context, day_dir, day_opts, originals)?;
+    Ok(())
+}
+
+impl TemplateSet {
+    /// Render every file of this set into a directory, returning the paths written.
+    ///
+    /// Existing files are left alone, unless `day_opts.force` is set; then they are overwritten,
+    /// and with `day_opts.backup`, those which differ are first copied aside.
+    pub fn render_into(
+        &self,
+        fs: &dyn Fs,
+        context: &impl Serialize,
+        dir: &Path,
+        day_opts: &DayOpts,
+    ) -> Result<Vec<PathBuf>, Error> {
+        self.render_into_recorded(fs, context, dir, day_opts, &mut Vec::new())
+    }
+
+    /// [`render_into`](Self::render_into), recording each file it writes and its original
+    /// contents, if it existed.
+    fn render_into_recorded(
+        &self,
+        fs: &dyn Fs,
+        context: &impl Serialize,
+        dir: &Path,
+        day_opts: &DayOpts,
+        originals: &mut Vec<(PathBuf, Option<Vec<u8>>)>,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let mut written = Vec::new();
+        for rendered in self.render(fs, context)? {
+            let destination = dir.join(rendered.destination);
+            if let Some(parent) = destination.parent() {
+                fs.create_dir_all(parent)
+                    .map_err(|err| Error::Io("creating template destination directory", err))?;
+            }
+
+            if !day_opts.force && fs.exists(&destination) {
+                continue;
+            }
+
+            if day_opts.force && day_opts.backup {
+                if let Ok(existing) = fs.read(&destination) {
+                    if existing != rendered.contents {
+                        let mut backup = destination.clone().into_os_string();
+                        backup.push(".bak");
+                        let backup = PathBuf::from(backup);
+                        originals.push((backup.clone(), fs.read(&backup).ok()));
+                        fs.write(&backup, &existing)
+                            .map_err(|err| Error::Io("backing up overwritten file", err))?;
+                    }
+                }
+            }
+
+            tracing::info!(path = %destination.display(), "writing rendered template");
+            originals.push((destination.clone(), fs.read(&destination).ok()));
+            fs.write(&destination, &rendered.contents)
+                .map_err(|err| Error::Io("writing rendered template", err))?;
+            written.push(destination);
+        }
+
+        Ok(written)
+    }
+}
+
+/// A problem found in a template by [`TemplateSet::validate`].
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub path: PathBuf,
+    /// 1-based line and column, where known.
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some((line, column)) = self.position {
+            write!(f, ":{line}:{column}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl TemplateSet {
+    /// Check every template of this set, without writing anything.
+    ///
+    /// Each template is parsed, and rendered with `context`. TinyTemplate templates are also
+    /// checked for references to variables which `context` lacks, even within branches which
+    /// `context` does not take. Tera templates are only checked along the branches taken.
+    pub fn validate(&self, fs: &dyn Fs, context: &Context) -> Result<Vec<Problem>, Error> {
+        let context_value = serde_json::to_value(context).expect("contexts serialize to json");
+        let mut problems = Vec::new();
+        for template in &self.files {
+            if let Some(destination) = &template.destination {
+                let path = template.root.join(MANIFEST);
+                problems.extend(self.validate_str(&path, destination, &context_value));
+            }
+            if self.is_verbatim(template) {
+                continue;
+            }
+            let contents = fs
+                .read(&template.path())
+                .map_err(|err| Error::Io("reading template file", err))?;
+            if let Ok(text) = String::from_utf8(contents) {
+                problems.extend(self.validate_str(&template.path(), &text, &context_value));
+            }
+        }
+        Ok(problems)
+    }
+
+    fn validate_str(&self, path: &Path, text: &str, context: &serde_json::Value) -> Vec<Problem> {
+        let problem = |position, message| Problem {
+            path: path.to_owned(),
+            position,
+            message,
+        };
+        match self.engine {
+            Engine::TinyTemplate => {
+                let mut tt = TinyTemplate::new();
+                tt.set_default_formatter(&tinytemplate::format_unescaped);
+                if let Err(err) = tt.add_template("template", text) {
+                    return vec![tinytemplate_problem(path, err)];
+                }
+                let unknown = unknown_tinytemplate_variables(text, context)
+                    .into_iter()
+                    .map(|(position, name)| {
+                        problem(Some(position), format!("unknown variable: {name}"))
+                    })
+                    .collect::<Vec<_>>();
+                if !unknown.is_empty() {
+                    return unknown;
+                }
+                match tt.render("template", context) {
+                    Ok(_) => Vec::new(),
+                    Err(err) => vec![tinytemplate_problem(path, err)],
+                }
+            }
+            Engine::Tera => {
+                let mut tera = tera::Tera::default();
+                let result = tera.add_raw_template("template", text).and_then(|()| {
+                    let context = tera::Context::from_value(context.clone())?;
+                    tera.render("template", &context)
+                });
+                match result {
+                    Ok(_) => Vec::new(),
+                    Err(err) => {
+                        let mut message = err.to_string();
+                        let mut source = std::error::Error::source(&err);
+                        while let Some(err) = source {
+                            message = format!("{message}: {err}");
+                            source = err.source();
+                        }
+                        vec![problem(None, message)]
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn tinytemplate_problem(path: &Path, err: tinytemplate::error::Error) -> Problem {
+    use tinytemplate::error::Error as TtError;
+    let position = match &err {
+        TtError::ParseError { line, column, .. } | TtError::RenderError { line, column, .. } => {
+            Some((*line, *column))
+        }
+        _ => None,
+    };
+    let message = match err {
+        TtError::ParseError { msg, .. } | TtError::RenderError { msg, .. } => msg,
+        err => err.to_string(),
+    };
+    Problem {
+        path: path.to_owned(),
+        position,
+        message,
+    }
+}
+
+/// Find references in a TinyTemplate to variables which are not in `context`, with their 1-based
+/// line and column.
+///
+/// Names bound by `for` and `with` blocks, and TinyTemplate's own `@` names, are always known.
+fn unknown_tinytemplate_variables(
+    text: &str,
+    context: &serde_json::Value,
+) -> Vec<((usize, usize), String)> {
+    let value = regex::Regex::new(r"\{\s*([A-Za-z_@][\w.@]*)\s*(?:\|\s*\w+\s*)?\}")
+        .expect("value pattern is valid");
+    let block = regex::Regex::new(
+        r"\{\{\s*(?:(?:else\s+)?if\s+(?:not\s+)?([\w.@]+)|for\s+(\w+)\s+in\s+([\w.@]+)|with\s+([\w.@]+)\s+as\s+(\w+)|call\s+\w+\s+with\s+([\w.@]+))\s*\}\}",
+    )
+    .expect("block pattern is valid");
+
+    let mut bound = Vec::new();
+    let mut references = Vec::new();
+    for captures in block.captures_iter(text) {
+        for group in [1, 3, 4, 6] {
+            if let Some(reference) = captures.get(group) {
+                references.push(reference);
+            }
+        }
+        for group in [2, 5] {
+            if let Some(name) = captures.get(group) {
+                bound.push(name.as_str());
+            }
+        }
+    }
+    for captures in value.captures_iter(text) {
+        let whole = captures.get(0).expect("group 0 always matches");
+        // `{{ endif }}` and friends look like values, as do escaped braces
+        let preceding = text[..whole.start()].chars().next_back();
+        let following = text[whole.end()..].chars().next();
+        if matches!(preceding, Some('{' | '\\')) || following == Some('}') {
+            continue;
+        }
+        references.extend(captures.get(1));
+    }
+
+    let mut unknown = references
+        .into_iter()
+        .filter(|reference| {
+            let name = reference.as_str().split('.').next().unwrap_or_default();
+            !(name.starts_with('@')
+                || name == "this"
+                || bound.contains(&name)
+                || context.get(name).is_some())
+        })
+        .map(|reference| {
+            let before = &text[..reference.start()];
+            let line = before.matches('\n').count() + 1;
+            let column = before.len() - before.rfind('\n').map_or(0, |newline| newline + 1) + 1;
+            ((line, column), reference.as_str().to_owned())
+        })
+        .collect::<Vec<_>>();
+    unknown.sort();
+    unknown
+}
+
+/// Stock templates for the files of a year's workspace, and the copies embedded in this binary.
+const YEAR_TEMPLATES: &[(&str, &str)] = &[
+    ("README.md", include_str!("../year-template/README.md")),
+    ("LICENSE-MIT", include_str!("../year-template/LICENSE-MIT")),
+    (
+        "LICENSE-UNLICENSE",
+        include_str!("../year-template/LICENSE-UNLICENSE"),
+    ),
+];
+
+/// A license for a year's workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum License {
+    Mit,
+    Unlicense,
+}
+
+impl License {
+    /// The name of this license's template.
+    fn template(self) -> &'static str {
+        match self {
+            Self::Mit => "LICENSE-MIT",
+            Self::Unlicense => "LICENSE-UNLICENSE",
+        }
+    }
+
+    /// The SPDX identifier of this license, as for the `license` field of a manifest.
+    pub fn spdx(self) -> &'static str {
+        match self {
+            Self::Mit => "MIT",
+            Self::Unlicense => "Unlicense",
+        }
+    }
+}
+
+/// A day in the [`YearContext`].
+#[derive(Debug, Clone, Serialize)]
+pub struct YearDay {
+    pub day: u8,
+    pub package_name: String,
+    pub puzzle_url: String,
+}
+
+/// Variables available within the templates of a year's workspace files.
+#[derive(Debug, Clone, Serialize)]
+pub struct YearContext {
+    /// User-defined variables.
+    #[serde(flatten)]
+    pub vars: BTreeMap<String, String>,
+    pub year: u32,
+    /// URL of the year's calendar.
+    pub calendar_url: String,
+    /// `user.name` from the git configuration, if set.
+    pub author: String,
+    pub days: Vec<YearDay>,
+}
+
+impl YearContext {
+    pub fn new(settings: &Settings, year: u32) -> Self {
+        Self {
+            vars: settings.template.vars.clone(),
+            year,
+            calendar_url: format!("https://adventofcode.com/{year}"),
+            author: crate::git::config_value("user.name").unwrap_or_default(),
+            days: (1..=25)
+                .map(|day| YearDay {
+                    day,
+                    package_name: settings.day_name(year, day),
+                    puzzle_url: aoclib::website::url_for_day(year, day),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Render the workspace files of a year, `README.md` and optionally `LICENSE`, into its
+/// implementation directory.
+///
+/// Files which already exist are left alone. Templates in the settings' `year-templates` directory
+/// replace the stock templates of the same name.
+pub(crate) fn render_year_files(
+    fs: &dyn Fs,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    license: Option<License>,
+) -> Result<(), Error> {
+    let implementation_dir = config.implementation(year);
+    let context = YearContext::new(settings, year);
+    let files = std::iter::once(("README.md", "README.md"))
+        .chain(license.map(|license| ("LICENSE", license.template())));
+    for (destination, template) in files {
+        let destination = implementation_dir.join(destination);
+        if fs.exists(&destination) {
+            continue;
+        }
+        let custom = settings
+            .year_templates
+            .as_ref()
+            .map(|dir| dir.join(template))
+            .filter(|path| fs.exists(path));
+        let text = match custom {
+            Some(path) => fs
+                .read_to_string(&path)
+                .map_err(|err| Error::Io("reading year template", err))?,
+            None => embedded_template(YEAR_TEMPLATES, template).to_string(),
+        };
+        let rendered = render_str(Engine::TinyTemplate, template, &text, &context)?;
+        tracing::info!(path = %destination.display(), "writing year workspace file");
+        fs.write(&destination, rendered.as_bytes())
+            .map_err(|err| Error::Io("writing year workspace file", err))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! When do puzzles unlock?
+//!
+//! Puzzles unlock at midnight US/Eastern, which during December is always 05:00 UTC.
+
+use std::time::Duration;
+use time::{Month, OffsetDateTime, Time};
+
+use crate::Error;
+
+/// The instant at which a day's puzzle unlocks.
+pub fn unlock_time(year: u32, day: u8) -> Result<OffsetDateTime, Error> {
+    let date = time::Date::from_calendar_date(year as i32, Month::December, day)
+        .map_err(Error::InvalidDate)?;
+    let time = Time::from_hms(5, 0, 0).map_err(Error::InvalidDate)?;
+    Ok(date.with_time(time).assume_utc())
+}
+
+/// How long until a day's puzzle unlocks, or `None` if it already has.
+pub fn time_until_unlock(year: u32, day: u8) -> Result<Option<Duration>, Error> {
+    let remaining = unlock_time(year, day)? - OffsetDateTime::now_utc();
+    if remaining.is_positive() {
+        Ok(Some(Duration::try_from(remaining).unwrap_or_default()))
+    } else {
+        Ok(None)
+    }
+}
+
+/// The next puzzle to unlock, as its year and day.
+///
+/// Between Advent of Codes, this is day 1 of the next one.
+pub fn next_unlock() -> Result<(u32, u8), Error> {
+    let now = OffsetDateTime::now_utc();
+    let year = now.year() as u32;
+    for day in 1..=25 {
+        if unlock_time(year, day)? > now {
+            return Ok((year, day));
+        }
+    }
+    Ok((year + 1, 1))
+}
+
+/// Block until a day's puzzle unlocks.
+///
+/// `on_tick` is called about once per second with the time remaining, for progress display.
+pub fn wait_for_unlock(year: u32, day: u8, mut on_tick: impl FnMut(Duration)) -> Result<(), Error> {
+    while let Some(remaining) = time_until_unlock(year, day)? {
+        on_tick(remaining);
+        std::thread::sleep(remaining.min(Duration::from_secs(1)));
+    }
+    Ok(())
+}
+
+/// Format a duration as `[Nd ]HH:MM:SS`.
+pub fn format_countdown(duration: Duration) -> String {
+    // round up so that the countdown reaches zero exactly at unlock
+    let total = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+    let (days, rest) = (total / 86_400, total % 86_400);
+    let (hours, minutes, seconds) = (rest / 3600, rest % 3600 / 60, rest % 60);
+    if days > 0 {
+        format!("{days}d {hours:02}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{hours:02}:{minutes:02}:{seconds:02}")
+    }
+}
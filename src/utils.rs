@@ -0,0 +1,84 @@
+//! An optional crate of helpers shared between the year's solutions.
+//!
+//! When `utils/` exists in the implementation directory, each newly initialized day depends on it.
+
+use std::path::Path;
+
+use crate::{settings::Settings, vfs::Fs, Error};
+
+/// Name of the utilities crate, and its directory within the implementation directory.
+pub const UTILS_DIR: &str = "utils";
+
+/// The stock template of the utilities crate, embedded in this binary.
+const UTILS_TEMPLATE: &[(&str, &str)] = &[
+    ("Cargo.toml", include_str!("../utils-template/Cargo.toml")),
+    ("src/lib.rs", include_str!("../utils-template/src/lib.rs")),
+    (
+        "src/direction.rs",
+        include_str!("../utils-template/src/direction.rs"),
+    ),
+    ("src/grid.rs", include_str!("../utils-template/src/grid.rs")),
+    (
+        "src/parse.rs",
+        include_str!("../utils-template/src/parse.rs"),
+    ),
+];
+
+/// Whether the implementation directory has a utilities crate.
+pub fn exists(fs: &dyn Fs, implementation_dir: &Path) -> bool {
+    fs.exists(&implementation_dir.join(UTILS_DIR).join("Cargo.toml"))
+}
+
+/// Create the utilities crate in the implementation directory.
+///
+/// The crate is copied from the settings' `utils-template` directory if set, and otherwise from
+/// the stock template. Existing files are left alone.
+pub fn scaffold_utils(
+    fs: &dyn Fs,
+    settings: &Settings,
+    implementation_dir: &Path,
+) -> Result<(), Error> {
+    let utils_dir = implementation_dir.join(UTILS_DIR);
+    match &settings.utils_template {
+        Some(template_dir) => copy_new(fs, template_dir, &utils_dir),
+        None => {
+            for (path, contents) in UTILS_TEMPLATE {
+                write_new(fs, &utils_dir.join(path), contents.as_bytes())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Write a file, and its parent directories, unless it already exists.
+fn write_new(fs: &dyn Fs, path: &Path, contents: &[u8]) -> Result<(), Error> {
+    if fs.exists(path) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs.create_dir_all(parent)
+            .map_err(|err| Error::Io("creating utils dir", err))?;
+    }
+    tracing::info!(path = %path.display(), "writing utils file");
+    fs.write(path, contents)
+        .map_err(|err| Error::Io("writing utils file", err))
+}
+
+/// Recursively copy the files of a directory which do not already exist at the destination.
+fn copy_new(fs: &dyn Fs, source: &Path, destination: &Path) -> Result<(), Error> {
+    let entries = fs
+        .read_dir(source)
+        .map_err(|err| Error::Io("reading utils template dir", err))?;
+    for path in entries {
+        let destination = destination.join(path.file_name().unwrap_or_default());
+        if fs.is_dir(&path) {
+            copy_new(fs, &path, &destination)?;
+        } else {
+            let contents = fs
+                .read(&path)
+                .map_err(|err| Error::Io("reading utils template file", err))?;
+            write_new(fs, &destination, &contents)?;
+        }
+    }
+    Ok(())
+}
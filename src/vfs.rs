@@ -0,0 +1,413 @@
+//! The filesystem, behind a trait, so that the operations which scaffold days can be run against
+//! memory, or kept within the configured directories.
+//!
+//! Everything `init`, `init-year`, `remove`, `adopt`, `migrate`, and the template commands read
+//! and write, and the inputs and progress they keep, goes through the filesystem of the
+//! [`Host`](crate::Host) they are given; the real one is [`RealFs`]. [`Rooted`] wraps another,
+//! refusing changes outside of a set of directories; `aoc init` uses one rooted at the year's
+//! [directories](roots).
+
+use aoclib::config::Config;
+use path_absolutize::Absolutize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use crate::{settings::Settings, templates, DayOpts};
+
+/// The file operations used while scaffolding.
+pub trait Fs: Send + Sync {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    /// Read a file which must be UTF-8.
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+    /// Write a file, replacing it if it exists.
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    /// Append to a file, creating it if it doesn't exist.
+    fn append(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    /// Copy a file, replacing the destination if it exists.
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.write(to, &self.read(from)?)
+    }
+    /// Move a file or directory, replacing a file at the destination if it exists.
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+    /// The paths of a directory's entries, sorted.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+    fn exists(&self, path: &Path) -> bool;
+    fn is_dir(&self, path: &Path) -> bool;
+    fn is_file(&self, path: &Path) -> bool {
+        self.exists(path) && !self.is_dir(path)
+    }
+    /// The absolute path of something which exists, with symlinks resolved.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+}
+
+/// The real filesystem.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RealFs;
+
+impl Fs for RealFs {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        std::fs::write(path, contents)
+    }
+
+    fn append(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        use io::Write;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(contents)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        std::fs::copy(from, to).map(|_| ())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        std::fs::rename(from, to)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_dir_all(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut paths = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        path.canonicalize()
+    }
+}
+
+/// A filesystem in memory, for tests.
+///
+/// Like the real one, files can only be written in directories which exist.
+#[derive(Debug, Default)]
+pub struct MemoryFs {
+    files: Mutex<BTreeMap<PathBuf, Vec<u8>>>,
+    dirs: Mutex<BTreeSet<PathBuf>>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every file, and its contents.
+    pub fn files(&self) -> BTreeMap<PathBuf, Vec<u8>> {
+        self.files
+            .lock()
+            .expect("memory fs is not poisoned")
+            .clone()
+    }
+
+    fn ensure_parent(&self, path: &Path) -> io::Result<()> {
+        let dirs = self.dirs.lock().expect("memory fs is not poisoned");
+        match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() && !dirs.contains(parent) => {
+                Err(not_found("parent directory does not exist"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Fs for MemoryFs {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files
+            .lock()
+            .expect("memory fs is not poisoned")
+            .get(path)
+            .cloned()
+            .ok_or_else(|| not_found("file does not exist"))
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.ensure_parent(path)?;
+        self.files
+            .lock()
+            .expect("memory fs is not poisoned")
+            .insert(path.to_owned(), contents.to_vec());
+        Ok(())
+    }
+
+    fn append(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.ensure_parent(path)?;
+        self.files
+            .lock()
+            .expect("memory fs is not poisoned")
+            .entry(path.to_owned())
+            .or_default()
+            .extend_from_slice(contents);
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.ensure_parent(to)?;
+        let mut dirs = self.dirs.lock().expect("memory fs is not poisoned");
+        let mut files = self.files.lock().expect("memory fs is not poisoned");
+        if let Some(contents) = files.remove(from) {
+            files.insert(to.to_owned(), contents);
+            return Ok(());
+        }
+        if !dirs.contains(from) {
+            return Err(not_found("nothing to move"));
+        }
+        let moved = |path: &Path| to.join(path.strip_prefix(from).expect("path is within from"));
+        *dirs = std::mem::take(&mut *dirs)
+            .into_iter()
+            .map(|dir| {
+                if dir.starts_with(from) {
+                    moved(&dir)
+                } else {
+                    dir
+                }
+            })
+            .collect();
+        *files = std::mem::take(&mut *files)
+            .into_iter()
+            .map(|(file, contents)| {
+                if file.starts_with(from) {
+                    (moved(&file), contents)
+                } else {
+                    (file, contents)
+                }
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut dirs = self.dirs.lock().expect("memory fs is not poisoned");
+        for ancestor in path.ancestors() {
+            if !ancestor.as_os_str().is_empty() {
+                dirs.insert(ancestor.to_owned());
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.files
+            .lock()
+            .expect("memory fs is not poisoned")
+            .remove(path)
+            .map(drop)
+            .ok_or_else(|| not_found("file does not exist"))
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut dirs = self.dirs.lock().expect("memory fs is not poisoned");
+        if !dirs.contains(path) {
+            return Err(not_found("directory does not exist"));
+        }
+        dirs.retain(|dir| !dir.starts_with(path));
+        self.files
+            .lock()
+            .expect("memory fs is not poisoned")
+            .retain(|file, _| !file.starts_with(path));
+        Ok(())
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        if !self.is_dir(path) {
+            return Err(not_found("directory does not exist"));
+        }
+        let is_entry = |entry: &&PathBuf| entry.parent() == Some(path);
+        let dirs = self.dirs.lock().expect("memory fs is not poisoned");
+        let files = self.files.lock().expect("memory fs is not poisoned");
+        let mut paths = dirs
+            .iter()
+            .filter(is_entry)
+            .chain(files.keys().filter(is_entry))
+            .cloned()
+            .collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.is_dir(path)
+            || self
+                .files
+                .lock()
+                .expect("memory fs is not poisoned")
+                .contains_key(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.dirs
+            .lock()
+            .expect("memory fs is not poisoned")
+            .contains(path)
+    }
+
+    /// There are no symlinks in memory, so this is only the absolute path.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        if self.exists(path) {
+            Ok(absolute(path))
+        } else {
+            Err(not_found("path does not exist"))
+        }
+    }
+}
+
+fn not_found(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, message.to_string())
+}
+
+/// Another filesystem, refusing to change anything outside of its roots.
+///
+/// Paths are checked after resolving `..` and any symlinks among their existing ancestors, so
+/// neither leads out of the roots. Reading is never refused.
+pub struct Rooted {
+    inner: Arc<dyn Fs>,
+    roots: Vec<PathBuf>,
+}
+
+impl Rooted {
+    pub fn new(inner: Arc<dyn Fs>, roots: Vec<PathBuf>) -> Self {
+        let roots = roots
+            .iter()
+            .map(|root| resolve(inner.as_ref(), root))
+            .collect();
+        Rooted { inner, roots }
+    }
+
+    fn check(&self, path: &Path) -> io::Result<()> {
+        let path = resolve(self.inner.as_ref(), path);
+        if self.roots.iter().any(|root| path.starts_with(root)) {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is outside the configured directories", path.display()),
+            ))
+        }
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    path.absolutize()
+        .map(|path| path.into_owned())
+        .unwrap_or_else(|_| path.to_owned())
+}
+
+/// The absolute path, with symlinks resolved as far as it exists.
+fn resolve(fs: &dyn Fs, path: &Path) -> PathBuf {
+    let path = absolute(path);
+    for ancestor in path.ancestors() {
+        if let Ok(resolved) = fs.canonicalize(ancestor) {
+            let rest = path
+                .strip_prefix(ancestor)
+                .expect("a path is within its ancestors");
+            return resolved.join(rest);
+        }
+    }
+    path
+}
+
+impl Fs for Rooted {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.inner.read(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.check(path)?;
+        self.inner.write(path, contents)
+    }
+
+    fn append(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.check(path)?;
+        self.inner.append(path, contents)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check(to)?;
+        self.inner.copy(from, to)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check(from)?;
+        self.check(to)?;
+        self.inner.rename(from, to)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.check(path)?;
+        self.inner.create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.check(path)?;
+        self.inner.remove_file(path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.check(path)?;
+        self.inner.remove_dir_all(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        self.inner.read_dir(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.inner.is_dir(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.inner.canonicalize(path)
+    }
+}
+
+/// The directories a day of a year may be scaffolded within: the implementation and inputs
+/// directories, and the template directories.
+pub fn roots(config: &Config, settings: &Settings, year: u32, day_opts: &DayOpts) -> Vec<PathBuf> {
+    let mut roots = vec![config.implementation(year), config.input_files(year)];
+    roots.extend(templates::template_dirs(config, settings, year));
+    if let Some(lang) = day_opts.language() {
+        roots.push(templates::language_template_dir(
+            config, settings, year, lang,
+        ));
+    }
+    roots
+}
@@ -0,0 +1,83 @@
+//! Interactions with adventofcode.com beyond those provided by `aoclib`.
+
+use aoclib::config::Config;
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::{http::HttpClient, nonblocking, settings::Settings, Error, Host};
+
+/// Scrape a year's calendar for the stars earned on each day.
+///
+/// Days without stars are omitted. This blocks on [`nonblocking::fetch_stars`].
+pub fn fetch_stars(
+    http: &dyn HttpClient,
+    config: &Config,
+    year: u32,
+) -> Result<BTreeMap<u8, u8>, Error> {
+    nonblocking::block_on(nonblocking::fetch_stars(http, config, year))
+}
+
+/// The name of the user to whom a session belongs.
+///
+/// This blocks on [`nonblocking::whoami`].
+pub fn whoami(http: &dyn HttpClient, session: &str) -> Result<String, Error> {
+    nonblocking::block_on(nonblocking::whoami(http, session))
+}
+
+/// Download the input for a puzzle, unless it exists already, returning its path.
+///
+/// This blocks on [`nonblocking::get_input`].
+pub fn get_input(host: &Host, config: &Config, year: u32, day: u8) -> Result<PathBuf, Error> {
+    nonblocking::block_on(nonblocking::get_input(host, config, year, day))
+}
+
+/// Path at which the input for a named account is stored.
+///
+/// This is the default input path, with the account name inserted before the extension:
+/// `input-05.txt` becomes `input-05.alt.txt`.
+pub fn input_for_account(config: &Config, year: u32, day: u8, account: &str) -> PathBuf {
+    let path = config.input_for(year, day);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}.{account}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.{account}"),
+    };
+    path.with_file_name(file_name)
+}
+
+/// Download the input for a puzzle using the session of a named account.
+///
+/// Like [`get_input`], this does nothing if the input file already exists. This blocks on
+/// [`nonblocking::get_input_for_account`].
+pub fn get_input_for_account(
+    host: &Host,
+    config: &Config,
+    settings: &Settings,
+    year: u32,
+    day: u8,
+    account: &str,
+) -> Result<PathBuf, Error> {
+    nonblocking::block_on(nonblocking::get_input_for_account(
+        host, config, settings, year, day, account,
+    ))
+}
+
+/// Fetch the title of a puzzle from its page.
+///
+/// Returns `Ok(None)` if the page has no recognizable title, e.g. because the puzzle has not
+/// yet unlocked. This blocks on [`nonblocking::puzzle_title`].
+pub fn puzzle_title(http: &dyn HttpClient, year: u32, day: u8) -> Result<Option<String>, Error> {
+    nonblocking::block_on(nonblocking::puzzle_title(http, year, day))
+}
+
+/// Undo the HTML escaping the website applies to text.
+pub(crate) fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
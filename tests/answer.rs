@@ -0,0 +1,135 @@
+//! Finding answers in the output of day binaries.
+
+use aoctool::{
+    answer::{find, reported, Extraction, Part},
+    Error,
+};
+
+const OUTPUT: &str = "parsing 1000 lines
+[aoc] part 1: 1234
+took 3ms
+[aoc] part 2: abc def
+";
+
+#[test]
+fn reported_answers() {
+    assert_eq!(reported(OUTPUT, Part::One).as_deref(), Some("1234"));
+    assert_eq!(reported(OUTPUT, Part::Two).as_deref(), Some("abc def"));
+
+    // the last report of a part wins
+    let output = "[aoc] part 1: 1\n[aoc] part 1: 2\n";
+    assert_eq!(reported(output, Part::One).as_deref(), Some("2"));
+
+    for output in [
+        "part 1: 1234",
+        "[aoc] part 10: 1234",
+        "[aoc] part 1:",
+        "note: [aoc] part 1: 1234",
+    ] {
+        assert_eq!(reported(output, Part::One), None, "{output}");
+    }
+}
+
+#[test]
+fn reported_answers_take_precedence() {
+    let extraction = Extraction::Regex {
+        pattern: r"took (\d+)ms".to_string(),
+        group: 1,
+    };
+    assert_eq!(
+        find(OUTPUT, Part::One, &extraction).unwrap().as_deref(),
+        Some("1234")
+    );
+    // without a report, the rule applies
+    let output = "parsing\ntook 3ms\n";
+    assert_eq!(
+        find(output, Part::One, &extraction).unwrap().as_deref(),
+        Some("3")
+    );
+    assert_eq!(
+        find(output, Part::Two, &Extraction::LastLine)
+            .unwrap()
+            .as_deref(),
+        Some("took 3ms")
+    );
+}
+
+#[test]
+fn last_line() {
+    let extract = |output| Extraction::LastLine.extract(output).unwrap();
+    assert_eq!(extract("1\n  42  \n\n\n").as_deref(), Some("42"));
+    assert_eq!(extract(""), None);
+    assert_eq!(extract("\n  \n"), None);
+}
+
+#[test]
+fn regex() {
+    let extraction = Extraction::Regex {
+        pattern: r"answer: (\w+)".to_string(),
+        group: 1,
+    };
+    let extract = |output| extraction.extract(output).unwrap();
+    // the last of several matches
+    assert_eq!(
+        extract("answer: 1\nanswer: 2\ndone\n").as_deref(),
+        Some("2")
+    );
+    assert_eq!(extract("no answer here"), None);
+
+    let whole = Extraction::Regex {
+        pattern: r"answer: \w+".to_string(),
+        group: 0,
+    };
+    assert_eq!(
+        whole.extract("answer: 7").unwrap().as_deref(),
+        Some("answer: 7")
+    );
+
+    // a group which doesn't exist never matches
+    let missing_group = Extraction::Regex {
+        pattern: r"answer: (\w+)".to_string(),
+        group: 2,
+    };
+    assert_eq!(missing_group.extract("answer: 7").unwrap(), None);
+
+    let invalid = Extraction::Regex {
+        pattern: "(".to_string(),
+        group: 1,
+    };
+    let err = invalid.extract("answer: 7").unwrap_err();
+    assert!(matches!(err, Error::AnswerPattern(_)), "{err}");
+}
+
+#[test]
+fn json_field() {
+    let extraction = Extraction::JsonField {
+        field: "result.answer".to_string(),
+    };
+    let extract = |output| extraction.extract(output).unwrap();
+    assert_eq!(
+        extract("{\"result\": {\"answer\": 42}}\nbye\n").as_deref(),
+        Some("42")
+    );
+    assert_eq!(
+        extract("{\"result\": {\"answer\": \"x\"}}\n{\"result\": {\"answer\": \"y\"}}").as_deref(),
+        Some("y")
+    );
+    assert_eq!(extract("{\"result\": {\"other\": 1}}"), None);
+    assert_eq!(extract("{\"result\": {\"answer\": [1, 2]}}"), None);
+    assert_eq!(extract("not json"), None);
+}
+
+#[test]
+fn rules_from_settings() {
+    let regex =
+        toml::from_str::<Extraction>("rule = \"regex\"\npattern = 'answer: (\\d+)'\n").unwrap();
+    assert_eq!(
+        regex,
+        Extraction::Regex {
+            pattern: r"answer: (\d+)".to_string(),
+            group: 1,
+        }
+    );
+    let last_line = toml::from_str::<Extraction>("rule = \"last-line\"\n").unwrap();
+    assert_eq!(last_line, Extraction::LastLine);
+}
@@ -0,0 +1,25 @@
+//! Overrides from the environment.
+//!
+//! These set environment variables of the whole test process, so this file holds only one test.
+
+use aoclib::config::Config;
+use aoctool::env::{INPUTS_DIR_VAR, SESSION_VAR, YEAR_VAR};
+use std::path::{Path, PathBuf};
+
+#[test]
+fn inputs_dir_is_split_by_year() {
+    std::env::set_var(SESSION_VAR, "53616c7465645f5f");
+    std::env::set_var(YEAR_VAR, "2022");
+    std::env::set_var(INPUTS_DIR_VAR, "/ci/inputs");
+
+    let mut config = Config::default();
+    config.set_input_files(2023, PathBuf::from("/aoc/2023/inputs"));
+    config.set_input_files(2024, PathBuf::from("/aoc/2024/inputs"));
+    aoctool::env::apply(&mut config);
+
+    assert_eq!(config.session, "53616c7465645f5f");
+    assert_eq!(config.input_files(2022), Path::new("/ci/inputs/2022"));
+    assert_eq!(config.input_files(2023), Path::new("/ci/inputs/2023"));
+    assert_eq!(config.input_files(2024), Path::new("/ci/inputs/2024"));
+    assert_ne!(config.input_for(2023, 1), config.input_for(2024, 1));
+}
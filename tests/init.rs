@@ -0,0 +1,213 @@
+//! Scaffolding years and days in memory.
+
+use aoclib::config::Config;
+use aoctool::{
+    http::MockClient,
+    settings::Settings,
+    vfs::{Fs, MemoryFs, Rooted},
+    DayOpts, Error, Host, PathOpts, YearOpts,
+};
+use std::{io, path::Path, sync::Arc};
+
+/// A year initialized in memory, with its templates outside of its implementation.
+fn setup() -> (Config, Settings, Arc<MemoryFs>, Arc<MockClient>, Host) {
+    let fs = Arc::new(MemoryFs::new());
+    let mock = Arc::new(MockClient::new());
+    let host = Host {
+        http: mock.clone(),
+        fs: fs.clone(),
+    };
+    let mut config = Config::default();
+    let mut settings = Settings {
+        local_templates: true,
+        ..Settings::default()
+    };
+    let path_opts = PathOpts {
+        input_files: Some("/aoc/2024/inputs".into()),
+        implementation: Some("/aoc/2024".into()),
+        day_templates: Some("/aoc/templates".into()),
+    };
+    aoctool::initialize_year(
+        &host,
+        &mut config,
+        &mut settings,
+        2024,
+        path_opts,
+        YearOpts::default(),
+    )
+    .unwrap();
+    (config, settings, fs, mock, host)
+}
+
+fn read(fs: &MemoryFs, path: &str) -> String {
+    fs.read_to_string(Path::new(path)).unwrap()
+}
+
+#[test]
+fn init_year_in_memory() {
+    let (config, _, fs, mock, _) = setup();
+    assert_eq!(config.implementation(2024), Path::new("/aoc/2024"));
+    assert_eq!(config.input_files(2024), Path::new("/aoc/2024/inputs"));
+
+    let manifest = read(&fs, "/aoc/2024/Cargo.toml");
+    assert!(manifest.contains("[workspace]"), "{manifest}");
+    let gitignore = read(&fs, "/aoc/2024/.gitignore");
+    assert!(
+        gitignore.lines().any(|line| line == "/target/"),
+        "{gitignore}"
+    );
+    assert!(
+        gitignore.lines().any(|line| line == "inputs/"),
+        "{gitignore}"
+    );
+
+    assert!(!Path::new("/aoc/2024").exists());
+    assert!(mock.requests().is_empty());
+}
+
+#[test]
+fn init_day_in_memory() {
+    let (config, settings, fs, mock, host) = setup();
+    aoctool::initialize(
+        &host,
+        &config,
+        &settings,
+        2024,
+        1,
+        false,
+        true,
+        &DayOpts::default(),
+    )
+    .unwrap();
+
+    let manifest = read(&fs, "/aoc/2024/Cargo.toml");
+    assert!(manifest.contains("\"day01\""), "{manifest}");
+    let day_manifest = read(&fs, "/aoc/2024/day01/Cargo.toml");
+    assert!(day_manifest.contains("name = \"day01\""), "{day_manifest}");
+    // the stock templates were installed from those embedded, rather than downloaded
+    assert!(fs.is_file(Path::new("/aoc/templates/Cargo.toml")));
+    assert!(fs.is_file(Path::new("/aoc/2024/inputs/progress.toml")));
+
+    assert!(!Path::new("/aoc").exists());
+    assert!(mock.requests().is_empty());
+}
+
+#[test]
+fn init_day_sets_what_workspace_package_lacks() {
+    let (config, settings, fs, _, host) = setup();
+    fs.write(
+        Path::new("/aoc/2024/Cargo.toml"),
+        b"[workspace]\nmembers = []\nresolver = \"2\"\n\n[workspace.package]\nauthors = [\"Eric\"]\n",
+    )
+    .unwrap();
+    aoctool::initialize(
+        &host,
+        &config,
+        &settings,
+        2024,
+        1,
+        false,
+        true,
+        &DayOpts::default(),
+    )
+    .unwrap();
+
+    let day_manifest = read(&fs, "/aoc/2024/day01/Cargo.toml");
+    for line in [
+        "authors.workspace = true",
+        "version = \"0.1.0\"",
+        "edition = \"2021\"",
+    ] {
+        assert!(
+            day_manifest.lines().any(|found| found == line),
+            "{day_manifest}"
+        );
+    }
+    assert!(
+        !day_manifest.contains("edition.workspace"),
+        "{day_manifest}"
+    );
+}
+
+#[test]
+fn init_day_rooted_outside_templates() {
+    let (config, settings, fs, _, host) = setup();
+    let host = host.rooted(vec!["/aoc/2024".into()]);
+
+    let err = aoctool::initialize(
+        &host,
+        &config,
+        &settings,
+        2024,
+        1,
+        false,
+        true,
+        &DayOpts::default(),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, Error::Io(_, err) if err.kind() == io::ErrorKind::PermissionDenied),
+        "{err}"
+    );
+
+    // the day was rolled back
+    assert!(!fs.exists(Path::new("/aoc/2024/day01")));
+    let manifest = read(&fs, "/aoc/2024/Cargo.toml");
+    assert!(!manifest.contains("day01"), "{manifest}");
+}
+
+#[test]
+fn rooted_refuses_escapes() {
+    let fs = Arc::new(MemoryFs::new());
+    fs.create_dir_all(Path::new("/aoc/2024")).unwrap();
+    let rooted = Rooted::new(fs.clone(), vec!["/aoc/2024".into()]);
+
+    rooted.write(Path::new("/aoc/2024/notes.md"), b"").unwrap();
+    for path in [
+        "/aoc/notes.md",
+        "/aoc/2024/../notes.md",
+        "/aoc/2024-notes.md",
+    ] {
+        let err = rooted.write(Path::new(path), b"").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied, "{path}");
+    }
+    let err = rooted.remove_dir_all(Path::new("/aoc")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+    // reading is never refused
+    fs.write(Path::new("/aoc/notes.md"), b"notes").unwrap();
+    assert_eq!(rooted.read(Path::new("/aoc/notes.md")).unwrap(), b"notes");
+}
+
+#[test]
+fn failed_forced_init_restores_overwritten_files() {
+    let (config, settings, fs, _, host) = setup();
+    let init = |skip_get_input, day_opts: &DayOpts| {
+        aoctool::initialize(
+            &host,
+            &config,
+            &settings,
+            2024,
+            1,
+            false,
+            skip_get_input,
+            day_opts,
+        )
+    };
+    init(true, &DayOpts::default()).unwrap();
+    let main = Path::new("/aoc/2024/day01/src/main.rs");
+    fs.write(main, b"// my solution\n").unwrap();
+
+    // the input can't be downloaded, so this fails after rendering over the day
+    let force = DayOpts {
+        force: true,
+        backup: true,
+        ..DayOpts::default()
+    };
+    let err = init(false, &force).unwrap_err();
+    assert!(matches!(err, Error::HttpStatus(404, _)), "{err}");
+
+    assert_eq!(fs.read(main).unwrap(), b"// my solution\n");
+    assert!(!fs.exists(Path::new("/aoc/2024/day01/src/main.rs.bak")));
+    assert!(fs.exists(Path::new("/aoc/2024/day01/Cargo.toml")));
+}
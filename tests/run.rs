@@ -0,0 +1,68 @@
+//! Where the input of a run comes from.
+
+use aoclib::config::Config;
+use aoctool::{
+    run::{provenance, RunOpts},
+    submit::{check_provenance, InputProvenance},
+    Error,
+};
+use std::path::PathBuf;
+
+/// A configuration of inputs in an empty directory of this test's own.
+fn setup(name: &str) -> (Config, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("aoctool-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("inputs/examples")).unwrap();
+    let mut config = Config::default();
+    config.set_input_files(2024, dir.join("inputs"));
+    (config, dir)
+}
+
+#[test]
+fn provenance_follows_the_input_chosen() {
+    let (config, dir) = setup("provenance");
+    std::fs::write(config.input_for(2024, 1), "3   4\n").unwrap();
+    std::fs::write(dir.join("inputs/examples/01-1.txt"), "1   2\n").unwrap();
+    std::fs::write(dir.join("other.txt"), "5   6\n").unwrap();
+    let with_input = |input: PathBuf| RunOpts {
+        input: Some(input),
+        ..RunOpts::default()
+    };
+
+    let real = provenance(&config, 2024, 1, &RunOpts::default()).unwrap();
+    assert_eq!(real, InputProvenance::Real);
+    let real = provenance(&config, 2024, 1, &with_input(config.input_for(2024, 1))).unwrap();
+    assert_eq!(real, InputProvenance::Real);
+    check_provenance(&real).unwrap();
+
+    let example = RunOpts {
+        example: Some(1),
+        ..RunOpts::default()
+    };
+    let example = provenance(&config, 2024, 1, &example).unwrap();
+    assert_eq!(example, InputProvenance::Example(1));
+    let err = check_provenance(&example).unwrap_err();
+    assert!(matches!(err, Error::SubmitExampleInput(1)), "{err}");
+    // an example is recognized however it is given
+    let example = with_input(dir.join("inputs/examples/01-1.txt"));
+    assert_eq!(
+        provenance(&config, 2024, 1, &example).unwrap(),
+        InputProvenance::Example(1)
+    );
+
+    let other = provenance(&config, 2024, 1, &with_input(dir.join("other.txt"))).unwrap();
+    assert!(matches!(other, InputProvenance::Other(_)), "{other:?}");
+    let err = check_provenance(&other).unwrap_err();
+    assert!(matches!(err, Error::SubmitOtherInput(_)), "{err}");
+}
+
+#[test]
+fn missing_examples_are_refused() {
+    let (config, _) = setup("provenance-missing");
+    let opts = RunOpts {
+        example: Some(2),
+        ..RunOpts::default()
+    };
+    let err = provenance(&config, 2024, 1, &opts).unwrap_err();
+    assert!(matches!(err, Error::NoSuchExample(1, 2)), "{err}");
+}
@@ -0,0 +1,85 @@
+//! Day name patterns, and redacting secrets.
+
+use aoctool::{
+    settings::{check_day_name, format_day_name, redact_secrets, REDACTED},
+    Error,
+};
+
+#[test]
+fn day_names_are_substituted() {
+    assert_eq!(format_day_name("day{day:02}", 2024, 5), "day05");
+    assert_eq!(format_day_name("y{year}d{day}", 2024, 5), "y2024d5");
+    assert_eq!(format_day_name("d{day:03}", 2024, 12), "d012");
+}
+
+#[test]
+fn unknown_placeholders_are_refused() {
+    for pattern in ["day{day:02}", "y{year}d{day}", "aoc{year:04}-{day:02}"] {
+        check_day_name(pattern).unwrap();
+    }
+    for (pattern, placeholder) in [
+        ("day{day:2}", "{day:2}"),
+        ("day{month}", "{month}"),
+        ("day{day:02x}", "{day:02x}"),
+        ("{year}-{}", "{}"),
+    ] {
+        let err = check_day_name(pattern).unwrap_err();
+        assert!(
+            matches!(&err, Error::UnknownPlaceholder(_, found) if found == placeholder),
+            "{err}"
+        );
+    }
+}
+
+#[test]
+fn secrets_are_redacted() {
+    let document = r#"# my configuration
+session = "53616c7465645f5f"
+default-year = 2024
+
+[profiles.work]
+session = "776f726b"
+input-files = "/work/inputs"
+
+[accounts]
+alt = "616c74"
+vault = "keychain:vault"
+
+[[hooks]]
+command = "notify"
+token = "secret-token"
+
+[github]
+servers = [{ url = "https://example.com", token = "inline-token" }]
+token = ["first", "second"]
+"#;
+    let redacted = redact_secrets(document).unwrap();
+    for secret in [
+        "53616c7465645f5f",
+        "776f726b",
+        "616c74",
+        "secret-token",
+        "inline-token",
+        "first",
+        "second",
+    ] {
+        assert!(!redacted.contains(secret), "{secret} in {redacted}");
+    }
+    for kept in [
+        "# my configuration",
+        "default-year = 2024",
+        "\"/work/inputs\"",
+        "keychain:vault",
+        "\"notify\"",
+        "\"https://example.com\"",
+    ] {
+        assert!(redacted.contains(kept), "{kept} not in {redacted}");
+    }
+    assert_eq!(redacted.matches(REDACTED).count(), 7, "{redacted}");
+}
+
+#[test]
+fn keychain_references_are_not_redacted() {
+    let document = "session = \"keychain:default\"\n";
+    assert_eq!(redact_secrets(document).unwrap(), document);
+}
@@ -0,0 +1,177 @@
+//! Requests of the website, answered by a mock client.
+
+use aoclib::config::Config;
+use aoctool::{
+    answer::Part,
+    http::MockClient,
+    submit::{InputProvenance, Outcome},
+    Error, Host,
+};
+use std::{path::PathBuf, sync::Arc};
+
+const SESSION: &str = "53616c7465645f5f";
+
+/// An empty directory of this test's own.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoctool-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn setup(name: &str) -> (Config, Arc<MockClient>, Host) {
+    let dir = scratch_dir(name);
+    let mut config = Config::default();
+    config.session = SESSION.to_string();
+    config.set_implementation(2024, dir.join("implementation"));
+    config.set_input_files(2024, dir.join("inputs"));
+    let mock = Arc::new(MockClient::new());
+    let host = Host {
+        http: mock.clone(),
+        ..Host::real()
+    };
+    (config, mock, host)
+}
+
+#[test]
+fn fetch_input_downloads_once() {
+    let (config, mock, host) = setup("fetch-input");
+    let url = "https://adventofcode.com/2024/day/1/input";
+    mock.respond(url, 200, "3   4\n4   3\n");
+
+    let path = aoctool::website::get_input(&host, &config, 2024, 1).unwrap();
+    assert_eq!(path, config.input_for(2024, 1));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "3   4\n4   3\n");
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].url, url);
+    assert_eq!(
+        requests[0].cookie.as_deref(),
+        Some(format!("session={SESSION}").as_str())
+    );
+
+    // the input exists now, so there is nothing to download
+    aoctool::website::get_input(&host, &config, 2024, 1).unwrap();
+    assert_eq!(mock.requests().len(), 1);
+}
+
+#[test]
+fn fetch_input_blocks_from_async_code() {
+    let (config, mock, host) = setup("fetch-input-async");
+    mock.respond("https://adventofcode.com/2024/day/1/input", 200, "1\n");
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let path = runtime
+        .block_on(async { aoctool::website::get_input(&host, &config, 2024, 1) })
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(path).unwrap(), "1\n");
+}
+
+#[test]
+fn fetch_input_reports_failure() {
+    let (config, mock, host) = setup("fetch-input-failure");
+
+    let err = aoctool::website::get_input(&host, &config, 2024, 2).unwrap_err();
+    assert!(matches!(err, Error::HttpStatus(404, _)), "{err}");
+    assert!(!config.input_for(2024, 2).exists());
+    assert_eq!(mock.requests().len(), 1);
+}
+
+/// Without keychain support, a reference can't be resolved, and must never be sent as a cookie.
+#[cfg(not(feature = "keychain"))]
+#[test]
+fn keychain_references_are_resolved() {
+    let (mut config, mock, host) = setup("keychain");
+    config.session = aoctool::keychain::reference("default");
+
+    let errors = [
+        aoctool::website::get_input(&host, &config, 2024, 1).unwrap_err(),
+        aoctool::website::fetch_stars(host.http.as_ref(), &config, 2024).unwrap_err(),
+        aoctool::leaderboard::fetch_json(host.http.as_ref(), &config, 2024, 1).unwrap_err(),
+        aoctool::submit::submit(
+            &host,
+            &config,
+            2024,
+            1,
+            Part::One,
+            "11",
+            &InputProvenance::Real,
+        )
+        .unwrap_err(),
+    ];
+    for err in errors {
+        assert!(matches!(err, Error::NoKeychain), "{err}");
+    }
+    assert!(mock.requests().is_empty());
+}
+
+#[test]
+fn submit_checks_then_records() {
+    let (config, mock, host) = setup("submit");
+    let url = "https://adventofcode.com/2024/day/1/answer";
+    mock.respond(url, 200, "That's the right answer!");
+
+    let outcome = aoctool::submit::submit(
+        &host,
+        &config,
+        2024,
+        1,
+        Part::One,
+        "11",
+        &InputProvenance::Real,
+    )
+    .unwrap();
+    assert_eq!(outcome, Outcome::Correct);
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].url, url);
+    assert_eq!(
+        requests[0].cookie.as_deref(),
+        Some(format!("session={SESSION}").as_str())
+    );
+    assert_eq!(
+        requests[0].form,
+        [
+            ("level".to_string(), "1".to_string()),
+            ("answer".to_string(), "11".to_string())
+        ]
+    );
+
+    // the correct answer was recorded, so the part is not submitted again
+    let err = aoctool::submit::submit(
+        &host,
+        &config,
+        2024,
+        1,
+        Part::One,
+        "11",
+        &InputProvenance::Real,
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::AlreadySolved(1, Part::One)), "{err}");
+    assert_eq!(mock.requests().len(), 1);
+}
+
+#[test]
+fn submit_refuses_example_answers() {
+    let (config, mock, host) = setup("submit-example");
+
+    let err = aoctool::submit::submit(
+        &host,
+        &config,
+        2024,
+        1,
+        Part::Two,
+        "31",
+        &InputProvenance::Example(1),
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::SubmitExampleInput(1)), "{err}");
+    assert!(mock.requests().is_empty());
+}
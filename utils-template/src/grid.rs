@@ -0,0 +1,82 @@
+use crate::Direction;
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// The coordinates of each cell, in row order.
+    pub fn points(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// The coordinates one step from `(x, y)` in `direction`, if they are within the grid.
+    pub fn step(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.deltas();
+        let x = x.checked_add_signed(dx as isize)?;
+        let y = y.checked_add_signed(dy as isize)?;
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /// The coordinates orthogonally adjacent to `(x, y)` within the grid.
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(x, y, direction))
+    }
+}
+
+impl Grid<u8> {
+    /// Parse a grid of bytes, one row per line.
+    ///
+    /// Returns `None` if the rows are not all the same width.
+    pub fn parse(input: &str) -> Option<Self> {
+        Self::parse_with(input, Some)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parse a grid of cells, one row per line, converting each byte.
+    ///
+    /// Returns `None` if the rows are not all the same width, or any byte fails to convert.
+    pub fn parse_with(input: &str, mut convert: impl FnMut(u8) -> Option<T>) -> Option<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for line in input.lines().map(str::trim_end).filter(|line| !line.is_empty()) {
+            if *width.get_or_insert(line.len()) != line.len() {
+                return None;
+            }
+            for byte in line.bytes() {
+                cells.push(convert(byte)?);
+            }
+            height += 1;
+        }
+        Some(Self {
+            width: width.unwrap_or_default(),
+            height,
+            cells,
+        })
+    }
+}
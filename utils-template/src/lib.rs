@@ -0,0 +1,8 @@
+//! Helpers shared between this year's solutions.
+
+pub mod direction;
+pub mod grid;
+pub mod parse;
+
+pub use direction::Direction;
+pub use grid::Grid;
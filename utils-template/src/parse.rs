@@ -0,0 +1,23 @@
+use std::str::FromStr;
+
+/// Every integer in some text, including any leading `-`, ignoring everything else.
+pub fn integers<T: FromStr>(text: &str) -> impl Iterator<Item = T> + '_ {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .filter_map(|token| token.parse().ok())
+}
+
+/// Parse each non-empty line.
+pub fn lines<T: FromStr>(text: &str) -> Result<Vec<T>, T::Err> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Split text into blocks separated by blank lines.
+pub fn blocks(text: &str) -> impl Iterator<Item = &str> {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|block| !block.is_empty())
+}